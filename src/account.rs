@@ -3,17 +3,201 @@ pub mod account_service;
 pub mod commands;
 pub mod events;
 pub mod repositories;
+pub mod transactions;
 
 pub use account_handler::AccountHandler;
 pub use account_service::AccountService;
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use events::{ACCOUNT_AGGREGATE_TYPE, AccountEvent};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
 use ulid::Ulid;
 
+use crate::fee::{FeeKind, FeeSchedule};
+use crate::money::Currency;
+use crate::traits::{
+    Aggregate, EventStore, SnapshotStore,
+    event::{ApplyError, EventEnvelope},
+    event_store::EventStoreError,
+    snapshot_store::SnapshotStoreError,
+};
+
+// Defaults keep snapshots taken before a field was added readable
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Account {
     pub account_id: Option<Ulid>,
     pub balance: Decimal,
+    pub currency: Currency,
+    pub status: AccountStatus,
+    /// How far the balance may go below zero, in the account currency.
+    pub overdraft_limit: Decimal,
+    /// Largest single withdrawal, unlimited when `None`.
+    pub per_transaction_limit: Option<Decimal>,
+    /// Largest total withdrawn over any rolling day, unlimited when `None`.
+    pub daily_withdrawal_limit: Option<Decimal>,
+    /// Withdrawals of the last day, oldest first, checked against the daily limit.
+    pub recent_withdrawals: Vec<RecentWithdrawal>,
+    /// Annual interest rate as a fraction, `0.02` for 2%. Accounts without one earn nothing.
+    pub interest_rate: Decimal,
+    /// Interest accrued but not yet posted to the balance, not rounded to the currency.
+    pub accrued_interest: Decimal,
+    /// Last day interest was accrued for.
+    pub interest_accrued_through: Option<NaiveDate>,
+    /// Last month end accrued interest was posted on.
+    pub interest_posted_through: Option<NaiveDate>,
+    /// Fees charged to the account, none without a schedule.
+    pub fee_schedule: Option<FeeSchedule>,
+    /// Last month end the monthly maintenance fee was charged for.
+    pub maintenance_charged_through: Option<NaiveDate>,
+    /// Fees charged within the waiver window and not waived, oldest first.
+    pub recent_fees: Vec<ChargedFee>,
+    /// Transactions reversed by a compensating event, in the order they were reversed.
+    pub reversals: Vec<Reversal>,
+    /// Funds reserved for later capture, oldest first. Expired holds stay listed until they
+    /// are released or a later hold replaces them, but no longer reserve anything.
+    pub holds: Vec<Hold>,
+    /// Transfers that debited or credited the account, each moves money at most once.
+    pub transfers: BTreeSet<Ulid>,
+}
+
+/// How long a hold reserves funds when it is placed without an expiry.
+pub const DEFAULT_HOLD_EXPIRY: TimeDelta = TimeDelta::days(7);
+
+/// Funds reserved on an account, taken from its balance once captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hold {
+    pub hold_id: Ulid,
+    pub amount: Decimal,
+    pub placed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Hold {
+    pub fn is_open_at(&self, at: DateTime<Utc>) -> bool {
+        at < self.expires_at
+    }
+}
+
+/// A transaction undone by a `transaction_reversed` event, linked to it by its sequence number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reversal {
+    pub reversed_sequence_number: Ulid,
+    /// Type of the reversed event, `deposit` for instance.
+    pub event_type: String,
+    pub amount: Decimal,
+    pub reason: String,
+}
+
+/// How long after it was charged a fee can still be waived.
+pub const FEE_WAIVER_WINDOW: TimeDelta = TimeDelta::days(90);
+
+/// A fee taken from the balance, refunded if it is waived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChargedFee {
+    pub fee_id: Ulid,
+    pub kind: FeeKind,
+    pub amount: Decimal,
+    pub charged_at: DateTime<Utc>,
+}
+
+/// Length of the rolling window the daily withdrawal limit applies to.
+pub const DAILY_LIMIT_WINDOW: TimeDelta = TimeDelta::days(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentWithdrawal {
+    pub withdrawn_at: DateTime<Utc>,
+    pub amount: Decimal,
+}
+
+/// A point in an account's history, the state after the last event it includes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsOf {
+    /// Includes the events recorded at or before the moment.
+    Time(DateTime<Utc>),
+    /// Includes the events up to and including the one with this sequence number.
+    Sequence(Ulid),
+}
+
+impl AsOf {
+    pub fn includes(&self, envelope: &EventEnvelope<Account, AccountEvent>) -> bool {
+        match self {
+            AsOf::Time(at) => envelope.metadata.recorded_at <= *at,
+            AsOf::Sequence(sequence_number) => envelope.sequence_number() <= *sequence_number,
+        }
+    }
+
+    /// The moment of the point, for a sequence number the moment its event was appended.
+    pub fn at(&self) -> DateTime<Utc> {
+        match self {
+            AsOf::Time(at) => *at,
+            AsOf::Sequence(sequence_number) => DateTime::<Utc>::from(sequence_number.datetime()),
+        }
+    }
+}
+
+/// Parses a sequence number, or else an RFC 3339 timestamp. Timestamps never parse as sequence
+/// numbers, which have no separators.
+impl FromStr for AsOf {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(sequence_number) = Ulid::from_string(s) {
+            return Ok(AsOf::Sequence(sequence_number));
+        }
+
+        DateTime::parse_from_rfc3339(s)
+            .map(|at| AsOf::Time(at.with_timezone(&Utc)))
+            .map_err(|e| {
+                format!(
+                    "Expected an RFC 3339 timestamp or a sequence number, got {}: {}",
+                    s, e
+                )
+            })
+    }
+}
+
+/// Lifecycle of an account. Frozen accounts reject deposits and withdrawals until they are
+/// unfrozen, closed accounts reject them for good.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountStatus {
+    #[default]
+    Open,
+    Frozen,
+    Closed,
+}
+
+impl AccountStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountStatus::Open => "open",
+            AccountStatus::Frozen => "frozen",
+            AccountStatus::Closed => "closed",
+        }
+    }
+}
+
+impl fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AccountStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(AccountStatus::Open),
+            "frozen" => Ok(AccountStatus::Frozen),
+            "closed" => Ok(AccountStatus::Closed),
+            other => Err(format!("Unknown account status: {}", other)),
+        }
+    }
 }
 
 impl Account {
@@ -22,7 +206,133 @@ impl Account {
         Self {
             account_id,
             balance,
+            currency: Currency::default(),
+            status: AccountStatus::Open,
+            overdraft_limit: Decimal::ZERO,
+            per_transaction_limit: None,
+            daily_withdrawal_limit: None,
+            recent_withdrawals: Vec::new(),
+            interest_rate: Decimal::ZERO,
+            accrued_interest: Decimal::ZERO,
+            interest_accrued_through: None,
+            interest_posted_through: None,
+            fee_schedule: None,
+            maintenance_charged_through: None,
+            recent_fees: Vec::new(),
+            reversals: Vec::new(),
+            holds: Vec::new(),
+            transfers: BTreeSet::new(),
+        }
+    }
+
+    /// Total withdrawn in the day before `at`.
+    pub fn withdrawn_in_day_before(&self, at: DateTime<Utc>) -> Decimal {
+        self.recent_withdrawals
+            .iter()
+            .filter(|w| w.withdrawn_at > at - DAILY_LIMIT_WINDOW)
+            .map(|w| w.amount)
+            .sum()
+    }
+
+    /// Records a withdrawal for the daily limit, forgetting those that fell out of the window.
+    pub fn record_withdrawal(&mut self, withdrawn_at: DateTime<Utc>, amount: Decimal) {
+        self.recent_withdrawals
+            .retain(|w| w.withdrawn_at > withdrawn_at - DAILY_LIMIT_WINDOW);
+        self.recent_withdrawals.push(RecentWithdrawal {
+            withdrawn_at,
+            amount,
+        });
+    }
+
+    /// Records a fee for waivers, forgetting those that can no longer be waived.
+    pub fn record_fee(&mut self, fee: ChargedFee) {
+        self.recent_fees
+            .retain(|f| f.charged_at > fee.charged_at - FEE_WAIVER_WINDOW);
+        self.recent_fees.push(fee);
+    }
+
+    pub fn recent_fee(&self, fee_id: Ulid) -> Option<&ChargedFee> {
+        self.recent_fees.iter().find(|fee| fee.fee_id == fee_id)
+    }
+
+    /// Whether the transfer already debited or credited the account.
+    pub fn has_transfer(&self, transfer_id: Ulid) -> bool {
+        self.transfers.contains(&transfer_id)
+    }
+
+    /// Records the transfer an event moved money for, rejecting a transfer applied before.
+    pub fn record_transfer(&mut self, transfer_id: Option<Ulid>) -> Result<(), ApplyError> {
+        let Some(transfer_id) = transfer_id else {
+            return Ok(());
+        };
+
+        if !self.transfers.insert(transfer_id) {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Transfer {} was already applied to the account",
+                transfer_id
+            )));
         }
+        Ok(())
+    }
+
+    pub fn is_reversed(&self, sequence_number: Ulid) -> bool {
+        self.reversals
+            .iter()
+            .any(|reversal| reversal.reversed_sequence_number == sequence_number)
+    }
+
+    /// The balance plus the overdraft limit. Withdrawals are checked against it when they are
+    /// replayed, holds were already accounted for when they were issued.
+    pub fn balance_with_overdraft(&self) -> Decimal {
+        self.balance + self.overdraft_limit
+    }
+
+    /// Total reserved by the holds still open at `at`.
+    pub fn held_at(&self, at: DateTime<Utc>) -> Decimal {
+        self.holds
+            .iter()
+            .filter(|hold| hold.is_open_at(at))
+            .map(|hold| hold.amount)
+            .sum()
+    }
+
+    /// What can be withdrawn or held at `at`: the balance plus the overdraft limit, less the
+    /// open holds. Withdrawals and holds are checked against it when they are issued.
+    pub fn available_balance(&self, at: DateTime<Utc>) -> Decimal {
+        self.balance_with_overdraft() - self.held_at(at)
+    }
+
+    pub fn hold(&self, hold_id: Ulid) -> Option<&Hold> {
+        self.holds.iter().find(|hold| hold.hold_id == hold_id)
+    }
+
+    /// Rebuilds the account from its latest snapshot and the events appended after it,
+    /// returning it together with the version of the last event applied.
+    pub fn load<S, Err>(event_store: &S, account_id: Ulid) -> Result<(Account, u64), Err>
+    where
+        S: EventStore + SnapshotStore,
+        Err: From<EventStoreError> + From<SnapshotStoreError> + From<ApplyError>,
+    {
+        let (state, snapshot_version) =
+            match event_store.get_snapshot::<Account>(account_id, ACCOUNT_AGGREGATE_TYPE)? {
+                Some(snapshot) => (snapshot.state, snapshot.version),
+                None => (Account::default(), 0),
+            };
+
+        let events_envelopes = event_store
+            .get_events_for_aggregate_since::<Account, AccountEvent>(
+                account_id,
+                ACCOUNT_AGGREGATE_TYPE,
+                snapshot_version,
+            )?;
+        let version = events_envelopes
+            .last()
+            .map_or(snapshot_version, |e| e.version());
+        // Replayed with their envelopes, withdrawals count towards the daily limit from when
+        // they were recorded
+        let account = Account::from_snapshot_and_history(state, &events_envelopes)?;
+
+        Ok((account, version))
     }
 }
 
@@ -31,6 +341,22 @@ impl Default for Account {
         Self {
             account_id: None,
             balance: Decimal::from(0),
+            currency: Currency::default(),
+            status: AccountStatus::Open,
+            overdraft_limit: Decimal::ZERO,
+            per_transaction_limit: None,
+            daily_withdrawal_limit: None,
+            recent_withdrawals: Vec::new(),
+            interest_rate: Decimal::ZERO,
+            accrued_interest: Decimal::ZERO,
+            interest_accrued_through: None,
+            interest_posted_through: None,
+            fee_schedule: None,
+            maintenance_charged_through: None,
+            recent_fees: Vec::new(),
+            reversals: Vec::new(),
+            holds: Vec::new(),
+            transfers: BTreeSet::new(),
         }
     }
 }
@@ -1,16 +1,27 @@
 use crate::account::Account;
+use crate::account::transactions::{Transaction, TransactionKind};
+use crate::money::Money;
 use crate::traits::event_bus::EventBusError;
 use crate::traits::event_store::EventStoreError;
 use crate::traits::{
-    Aggregate, Event, EventBus, EventStore, Repository, event::ApplyError,
+    Aggregate, Event, EventBus, EventStore, Projection, Repository, SnapshotStore,
+    TransactionRepository,
+    event::{ApplyError, EventEnvelope},
     repository::RepositoryError,
+    snapshot_store::SnapshotStoreError,
 };
 
 use super::events::{
-    ACCOUNT_AGGREGATE_TYPE, AccountEvent, AccountOpenedEvent, DepositEvent, WithdrawEvent,
+    ACCOUNT_AGGREGATE_TYPE, AccountEvent, AccountOpenedEvent, ConvertedDepositEvent, DepositEvent,
+    OverdraftLimitChangedEvent, TransactionReversedEvent, WithdrawEvent,
+    WithdrawalLimitsChangedEvent,
 };
 
 use thiserror::Error;
+use ulid::Ulid;
+
+/// Number of events read from the event store at a time while catching up.
+const CATCH_UP_BATCH_SIZE: usize = 500;
 
 #[derive(Debug, Error)]
 pub enum AccountHandlerError {
@@ -28,12 +39,15 @@ pub enum AccountHandlerError {
 
     #[error("Event store error: {0}")]
     EventStoreError(#[from] EventStoreError),
+
+    #[error("Snapshot store error: {0}")]
+    SnapshotStoreError(#[from] SnapshotStoreError),
 }
 
 pub struct AccountHandler<
-    R: Repository<Account> + Send + Sync + Clone + 'static,
+    R: Repository<Account> + TransactionRepository + Projection + Send + Sync + Clone + 'static,
     B: EventBus + Send + Sync + Clone + 'static,
-    S: EventStore + Send + Sync + Clone + 'static,
+    S: EventStore + SnapshotStore + Send + Sync + Clone + 'static,
 > {
     repository: R,
     event_bus: B,
@@ -41,9 +55,9 @@ pub struct AccountHandler<
 }
 
 impl<
-    R: Repository<Account> + Send + Sync + Clone + 'static,
+    R: Repository<Account> + TransactionRepository + Projection + Send + Sync + Clone + 'static,
     B: EventBus + Send + Sync + Clone + 'static,
-    S: EventStore + Send + Sync + Clone + 'static,
+    S: EventStore + SnapshotStore + Send + Sync + Clone + 'static,
 > AccountHandler<R, B, S>
 {
     pub fn new(repository: R, event_bus: B, event_store: S) -> Self {
@@ -60,25 +74,227 @@ impl<
         let event_store = self.event_store.clone();
 
         self.event_bus.subscribe(
-            "account",
-            Box::new(move |event: AccountEvent| {
+            "account_projection",
+            ACCOUNT_AGGREGATE_TYPE,
+            Box::new(move |envelope: EventEnvelope<Account, AccountEvent>| {
                 let handler =
                     AccountHandler::new(repository.clone(), event_bus.clone(), event_store.clone());
-                match event {
-                    AccountEvent::Opened(event) => handler
-                        .handle_account_opened(event)
-                        .map_err(|e| EventBusError::HandleError(e.to_string())),
-                    AccountEvent::Deposited(event) => handler
-                        .handle_account_deposited(event)
-                        .map_err(|e| EventBusError::HandleError(e.to_string())),
-                    AccountEvent::Withdrawn(event) => handler
-                        .handle_account_withdrawn(event)
-                        .map_err(|e| EventBusError::HandleError(e.to_string())),
-                }
+                handler
+                    .handle(envelope)
+                    .map_err(|e| EventBusError::HandleError(e.to_string()))
             }),
         );
     }
 
+    /// Applies an event delivered by the bus. The stored events between the checkpoint and it
+    /// are applied first: one that failed before, and that the bus gave up on, is retried
+    /// rather than skipped, and the checkpoint never passes an event that was not applied.
+    pub fn handle(
+        &self,
+        envelope: EventEnvelope<Account, AccountEvent>,
+    ) -> Result<(), AccountHandlerError> {
+        self.catch_up_before(Some(envelope.sequence_number()))?;
+        self.apply(envelope)
+    }
+
+    /// Applies an event to the projection, records the transaction it made if it changed the
+    /// balance, and advances its checkpoint. Events at or before the checkpoint were already
+    /// applied and are skipped, which makes redelivery harmless.
+    fn apply(
+        &self,
+        envelope: EventEnvelope<Account, AccountEvent>,
+    ) -> Result<(), AccountHandlerError> {
+        let sequence_number = envelope.sequence_number();
+        if self
+            .repository
+            .get_checkpoint()?
+            .is_some_and(|checkpoint| sequence_number <= checkpoint)
+        {
+            return Ok(());
+        }
+
+        if let Some(transaction) = self.transaction(&envelope)? {
+            self.repository.record_transaction(transaction)?;
+        }
+
+        match envelope.event {
+            AccountEvent::Opened(event) => self.handle_account_opened(event)?,
+            AccountEvent::Deposited(event) => self.handle_account_deposited(event)?,
+            AccountEvent::Withdrawn(event) => self.handle_account_withdrawn(event)?,
+            AccountEvent::Closed(event) => self.handle_account_status_changed(event)?,
+            AccountEvent::Frozen(event) => self.handle_account_status_changed(event)?,
+            AccountEvent::Unfrozen(event) => self.handle_account_status_changed(event)?,
+            AccountEvent::ConvertedDeposit(event) => self.handle_converted_deposit(event)?,
+            AccountEvent::OverdraftLimitChanged(event) => {
+                self.handle_overdraft_limit_changed(event)?
+            }
+            AccountEvent::WithdrawalLimitsChanged(event) => {
+                self.handle_withdrawal_limits_changed(event)?
+            }
+            AccountEvent::HoldPlaced(event) => self.handle_holds_changed(event)?,
+            AccountEvent::HoldCaptured(event) => self.handle_holds_changed(event)?,
+            AccountEvent::HoldReleased(event) => self.handle_holds_changed(event)?,
+            AccountEvent::InterestRateChanged(event) => self.handle_interest_changed(event)?,
+            AccountEvent::InterestAccrued(event) => self.handle_interest_changed(event)?,
+            AccountEvent::InterestPosted(event) => self.handle_interest_changed(event)?,
+            AccountEvent::FeeScheduleChanged(event) => self.handle_fees_changed(event)?,
+            AccountEvent::FeeCharged(event) => self.handle_fees_changed(event)?,
+            AccountEvent::FeeWaived(event) => self.handle_fees_changed(event)?,
+            AccountEvent::TransactionReversed(event) => self.handle_transaction_reversed(event)?,
+        }
+
+        self.repository.save_checkpoint(sequence_number)?;
+        Ok(())
+    }
+
+    /// Applies every stored event the projection has not seen yet, returning how many were
+    /// applied. Run on startup so the projection does not depend on the bus redelivering
+    /// history.
+    pub fn catch_up(&self) -> Result<usize, AccountHandlerError> {
+        self.catch_up_before(None)
+    }
+
+    /// Applies the stored events after the checkpoint, those before `before` only when set.
+    fn catch_up_before(&self, before: Option<Ulid>) -> Result<usize, AccountHandlerError> {
+        let mut applied = 0;
+
+        loop {
+            let checkpoint = self.repository.get_checkpoint()?;
+            let envelopes = self
+                .event_store
+                .get_events_for_aggregate_type::<Account, AccountEvent>(
+                    ACCOUNT_AGGREGATE_TYPE,
+                    checkpoint,
+                    CATCH_UP_BATCH_SIZE,
+                )?;
+
+            if envelopes.is_empty() {
+                return Ok(applied);
+            }
+
+            for envelope in envelopes {
+                if before.is_some_and(|before| envelope.sequence_number() >= before) {
+                    return Ok(applied);
+                }
+                self.apply(envelope)?;
+                applied += 1;
+            }
+        }
+    }
+
+    /// Drops the projection and replays the complete event history into it.
+    pub fn rebuild(&self) -> Result<usize, AccountHandlerError> {
+        self.repository.reset()?;
+        self.catch_up()
+    }
+
+    /// The transaction an event made, running on from the balance of the account's previous
+    /// one, `None` for events that leave the balance alone.
+    pub fn transaction(
+        &self,
+        envelope: &EventEnvelope<Account, AccountEvent>,
+    ) -> Result<Option<Transaction>, AccountHandlerError> {
+        let credit = |amount: Money| (amount.value(), amount.currency());
+        let debit = |amount: Money| (-amount.value(), amount.currency());
+
+        let (kind, (amount, currency), description, reversal_of) = match &envelope.event {
+            AccountEvent::Opened(event) => (
+                TransactionKind::Opening,
+                credit(event.balance),
+                "Opening balance".to_string(),
+                None,
+            ),
+            AccountEvent::Deposited(event) => (
+                TransactionKind::Deposit,
+                credit(event.amount),
+                "Deposit".to_string(),
+                None,
+            ),
+            AccountEvent::Withdrawn(event) => (
+                TransactionKind::Withdrawal,
+                debit(event.amount),
+                "Withdrawal".to_string(),
+                None,
+            ),
+            AccountEvent::ConvertedDeposit(event) => (
+                TransactionKind::ConvertedDeposit,
+                credit(event.amount),
+                format!("Deposit of {} at {}", event.source, event.rate),
+                None,
+            ),
+            AccountEvent::HoldCaptured(event) => (
+                TransactionKind::HoldCapture,
+                debit(event.amount),
+                format!("Capture of hold {}", event.hold_id),
+                None,
+            ),
+            AccountEvent::InterestPosted(event) => (
+                TransactionKind::Interest,
+                credit(event.amount),
+                format!("Interest through {}", event.posted_on),
+                None,
+            ),
+            AccountEvent::FeeCharged(event) => (
+                TransactionKind::Fee,
+                debit(event.amount),
+                format!("{} fee", event.kind),
+                None,
+            ),
+            AccountEvent::FeeWaived(event) => (
+                TransactionKind::FeeWaiver,
+                credit(event.amount),
+                format!("Waiver of fee {}: {}", event.fee_id, event.reason),
+                None,
+            ),
+            AccountEvent::TransactionReversed(event) => (
+                TransactionKind::Reversal,
+                // Undoing a credit debits the account and the other way round
+                if TransactionReversedEvent::reverses_credit(&event.reversed_event_type)
+                    .unwrap_or_default()
+                {
+                    debit(event.amount)
+                } else {
+                    credit(event.amount)
+                },
+                format!(
+                    "Reversal of {}: {}",
+                    event.reversed_sequence_number, event.reason
+                ),
+                Some(event.reversed_sequence_number),
+            ),
+            AccountEvent::Closed(_)
+            | AccountEvent::Frozen(_)
+            | AccountEvent::Unfrozen(_)
+            | AccountEvent::OverdraftLimitChanged(_)
+            | AccountEvent::WithdrawalLimitsChanged(_)
+            | AccountEvent::HoldPlaced(_)
+            | AccountEvent::HoldReleased(_)
+            | AccountEvent::InterestRateChanged(_)
+            | AccountEvent::InterestAccrued(_)
+            | AccountEvent::FeeScheduleChanged(_) => return Ok(None),
+        };
+
+        let account_id = envelope.aggregate_id;
+        let previous_balance = self
+            .repository
+            .get_last_transaction(account_id)?
+            .map(|transaction| transaction.balance)
+            .unwrap_or_default();
+
+        Ok(Some(Transaction {
+            sequence_number: envelope.sequence_number(),
+            account_id,
+            kind,
+            amount,
+            currency,
+            balance: previous_balance + amount,
+            description,
+            recorded_at: envelope.metadata.recorded_at,
+            reversal_of,
+            reversed_by: None,
+        }))
+    }
+
     pub fn handle_account_opened(
         &self,
         event: AccountOpenedEvent,
@@ -90,12 +306,8 @@ impl<
     }
 
     pub fn handle_account_deposited(&self, event: DepositEvent) -> Result<(), AccountHandlerError> {
-        let events_envelopes = self
-            .event_store
-            .get_events_for_aggregate(event.aggregate_id(), ACCOUNT_AGGREGATE_TYPE)?;
-        let events: Vec<AccountEvent> = events_envelopes.into_iter().map(|e| e.event).collect();
-
-        let account = Account::from_history(events)?;
+        let (account, _) =
+            Account::load::<_, AccountHandlerError>(&self.event_store, event.aggregate_id())?;
 
         self.repository.update(account)?;
         Ok(())
@@ -105,14 +317,383 @@ impl<
         &self,
         event: WithdrawEvent,
     ) -> Result<(), AccountHandlerError> {
-        let events_envelopes = self
-            .event_store
-            .get_events_for_aggregate(event.aggregate_id(), ACCOUNT_AGGREGATE_TYPE)?;
-        let events: Vec<AccountEvent> = events_envelopes.into_iter().map(|e| e.event).collect();
+        let (account, _) =
+            Account::load::<_, AccountHandlerError>(&self.event_store, event.aggregate_id())?;
 
-        let account = Account::from_history(events)?;
+        self.repository.update(account)?;
+        Ok(())
+    }
+
+    pub fn handle_converted_deposit(
+        &self,
+        event: ConvertedDepositEvent,
+    ) -> Result<(), AccountHandlerError> {
+        let (account, _) =
+            Account::load::<_, AccountHandlerError>(&self.event_store, event.aggregate_id())?;
+
+        self.repository.update(account)?;
+        Ok(())
+    }
+
+    pub fn handle_overdraft_limit_changed(
+        &self,
+        event: OverdraftLimitChangedEvent,
+    ) -> Result<(), AccountHandlerError> {
+        let (account, _) =
+            Account::load::<_, AccountHandlerError>(&self.event_store, event.aggregate_id())?;
+
+        self.repository.update(account)?;
+        Ok(())
+    }
+
+    pub fn handle_withdrawal_limits_changed(
+        &self,
+        event: WithdrawalLimitsChangedEvent,
+    ) -> Result<(), AccountHandlerError> {
+        let (account, _) =
+            Account::load::<_, AccountHandlerError>(&self.event_store, event.aggregate_id())?;
+
+        self.repository.update(account)?;
+        Ok(())
+    }
+
+    /// Placing, capturing and releasing holds change the account's holds, captures also its
+    /// balance.
+    pub fn handle_holds_changed(
+        &self,
+        event: impl Event<Account>,
+    ) -> Result<(), AccountHandlerError> {
+        let (account, _) =
+            Account::load::<_, AccountHandlerError>(&self.event_store, event.aggregate_id())?;
+
+        self.repository.update(account)?;
+        Ok(())
+    }
+
+    /// Setting the rate and accruing interest change the account's interest, posting also its
+    /// balance.
+    pub fn handle_interest_changed(
+        &self,
+        event: impl Event<Account>,
+    ) -> Result<(), AccountHandlerError> {
+        let (account, _) =
+            Account::load::<_, AccountHandlerError>(&self.event_store, event.aggregate_id())?;
 
         self.repository.update(account)?;
         Ok(())
     }
+
+    /// Setting the schedule changes the account's fees, charging and waiving them its balance.
+    pub fn handle_fees_changed(
+        &self,
+        event: impl Event<Account>,
+    ) -> Result<(), AccountHandlerError> {
+        let (account, _) =
+            Account::load::<_, AccountHandlerError>(&self.event_store, event.aggregate_id())?;
+
+        self.repository.update(account)?;
+        Ok(())
+    }
+
+    /// Reversals change the account's balance and its list of reversals.
+    pub fn handle_transaction_reversed(
+        &self,
+        event: TransactionReversedEvent,
+    ) -> Result<(), AccountHandlerError> {
+        let (account, _) =
+            Account::load::<_, AccountHandlerError>(&self.event_store, event.aggregate_id())?;
+
+        self.repository.update(account)?;
+        Ok(())
+    }
+
+    /// Closing, freezing and unfreezing only change the account's status.
+    pub fn handle_account_status_changed(
+        &self,
+        event: impl Event<Account>,
+    ) -> Result<(), AccountHandlerError> {
+        let (account, _) =
+            Account::load::<_, AccountHandlerError>(&self.event_store, event.aggregate_id())?;
+
+        self.repository.update(account)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::AccountService;
+    use crate::account::repositories::AccountRepositoryInMemory;
+    use crate::account::transactions::{TransactionPage, TransactionQuery};
+    use crate::event_bus_in_memory::EventBusInMemory;
+    use crate::event_store_in_memory::EventStoreInMemory;
+    use crate::money::Currency;
+    use crate::traits::event::EventMetadata;
+    use rust_decimal::Decimal;
+    use std::sync::{Arc, Mutex};
+
+    /// Projection failing to record the next `failing_transactions` transactions.
+    #[derive(Clone, Default)]
+    struct FlakyRepository {
+        inner: AccountRepositoryInMemory,
+        failing_transactions: Arc<Mutex<u32>>,
+    }
+
+    impl Repository<Account> for FlakyRepository {
+        fn create(&self, account: Account) -> Result<(), RepositoryError> {
+            self.inner.create(account)
+        }
+
+        fn update(&self, account: Account) -> Result<(), RepositoryError> {
+            self.inner.update(account)
+        }
+
+        fn delete(&self, id: Ulid) -> Result<(), RepositoryError> {
+            self.inner.delete(id)
+        }
+
+        fn get(&self, id: Ulid) -> Result<Account, RepositoryError> {
+            self.inner.get(id)
+        }
+    }
+
+    impl TransactionRepository for FlakyRepository {
+        fn record_transaction(&self, transaction: Transaction) -> Result<(), RepositoryError> {
+            let mut failing = self.failing_transactions.lock().unwrap();
+            if *failing > 0 {
+                *failing -= 1;
+                return Err(RepositoryError::RepositoryError(
+                    "database is locked".to_string(),
+                ));
+            }
+            self.inner.record_transaction(transaction)
+        }
+
+        fn get_last_transaction(
+            &self,
+            account_id: Ulid,
+        ) -> Result<Option<Transaction>, RepositoryError> {
+            self.inner.get_last_transaction(account_id)
+        }
+
+        fn get_transactions(
+            &self,
+            account_id: Ulid,
+            query: &TransactionQuery,
+        ) -> Result<TransactionPage, RepositoryError> {
+            self.inner.get_transactions(account_id, query)
+        }
+    }
+
+    impl Projection for FlakyRepository {
+        fn reset(&self) -> Result<(), RepositoryError> {
+            self.inner.reset()
+        }
+
+        fn get_checkpoint(&self) -> Result<Option<Ulid>, RepositoryError> {
+            self.inner.get_checkpoint()
+        }
+
+        fn save_checkpoint(&self, sequence_number: Ulid) -> Result<(), RepositoryError> {
+            self.inner.save_checkpoint(sequence_number)
+        }
+    }
+
+    #[test]
+    fn events_the_bus_gave_up_on_are_applied_before_later_ones() {
+        let event_store = EventStoreInMemory::new();
+        let repository = FlakyRepository::default();
+        let handler = AccountHandler::new(
+            repository.clone(),
+            EventBusInMemory::new(),
+            event_store.clone(),
+        );
+        let service = AccountService::new(repository.inner.clone(), event_store);
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(10), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .deposit(account_id, Decimal::from(5), Currency::EUR, &metadata)
+            .unwrap();
+        service
+            .withdraw(account_id, Decimal::from(3), Currency::EUR, &metadata)
+            .unwrap();
+        let history = service.get_account_events(account_id).unwrap();
+        let [opened, deposited, withdrawn] = <[_; 3]>::try_from(history).unwrap();
+
+        handler.handle(opened).unwrap();
+        *repository.failing_transactions.lock().unwrap() = 1;
+        assert!(handler.handle(deposited).is_err());
+
+        // The bus moved on, the withdrawal brings the deposit along
+        handler.handle(withdrawn).unwrap();
+        handler.catch_up().unwrap();
+        let kinds: Vec<TransactionKind> = service
+            .get_transactions(account_id, &TransactionQuery::new())
+            .unwrap()
+            .transactions
+            .iter()
+            .map(|transaction| transaction.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                TransactionKind::Opening,
+                TransactionKind::Deposit,
+                TransactionKind::Withdrawal
+            ]
+        );
+        assert_eq!(
+            repository.get(account_id).unwrap().balance,
+            Decimal::from(12)
+        );
+    }
+
+    #[test]
+    fn catch_up_only_replays_unseen_events() {
+        let event_store = EventStoreInMemory::new();
+        let repository = AccountRepositoryInMemory::new();
+        let handler = AccountHandler::new(
+            repository.clone(),
+            EventBusInMemory::new(),
+            event_store.clone(),
+        );
+        let service = AccountService::new(repository.clone(), event_store);
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(10), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .deposit(account_id, Decimal::from(5), Currency::EUR, &metadata)
+            .unwrap();
+        assert_eq!(handler.catch_up().unwrap(), 2);
+
+        service
+            .withdraw(account_id, Decimal::from(3), Currency::EUR, &metadata)
+            .unwrap();
+        assert_eq!(handler.catch_up().unwrap(), 1);
+        assert_eq!(
+            repository.get(account_id).unwrap().balance,
+            Decimal::from(12)
+        );
+
+        assert_eq!(handler.rebuild().unwrap(), 3);
+        assert_eq!(
+            repository.get(account_id).unwrap().balance,
+            Decimal::from(12)
+        );
+    }
+
+    #[test]
+    fn transactions_run_the_balance_and_link_reversals() {
+        let event_store = EventStoreInMemory::new();
+        let repository = AccountRepositoryInMemory::new();
+        let handler = AccountHandler::new(
+            repository.clone(),
+            EventBusInMemory::new(),
+            event_store.clone(),
+        );
+        let service = AccountService::new(repository.clone(), event_store);
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(10), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .deposit(account_id, Decimal::from(5), Currency::EUR, &metadata)
+            .unwrap();
+        service
+            .set_interest_rate(account_id, Decimal::new(2, 2), &metadata)
+            .unwrap();
+        service
+            .withdraw(account_id, Decimal::from(3), Currency::EUR, &metadata)
+            .unwrap();
+        handler.catch_up().unwrap();
+
+        let page = service
+            .get_transactions(account_id, &TransactionQuery::new())
+            .unwrap();
+        let balances: Vec<(TransactionKind, Decimal, Decimal)> = page
+            .transactions
+            .iter()
+            .map(|transaction| (transaction.kind, transaction.amount, transaction.balance))
+            .collect();
+        assert_eq!(
+            balances,
+            [
+                (
+                    TransactionKind::Opening,
+                    Decimal::from(10),
+                    Decimal::from(10)
+                ),
+                (
+                    TransactionKind::Deposit,
+                    Decimal::from(5),
+                    Decimal::from(15)
+                ),
+                (
+                    TransactionKind::Withdrawal,
+                    Decimal::from(-3),
+                    Decimal::from(12)
+                ),
+            ]
+        );
+        assert_eq!(page.next_after, None);
+
+        let deposit = page.transactions[1].sequence_number;
+        service
+            .reverse_transaction(account_id, deposit, "deposited twice", &metadata)
+            .unwrap();
+        handler.catch_up().unwrap();
+
+        let reversals = service
+            .get_transactions(
+                account_id,
+                &TransactionQuery::new().with_kind(TransactionKind::Reversal),
+            )
+            .unwrap()
+            .transactions;
+        assert_eq!(reversals.len(), 1);
+        assert_eq!(reversals[0].reversal_of, Some(deposit));
+        assert_eq!(reversals[0].balance, Decimal::from(7));
+
+        // Two per page, the second continuing after the first
+        let first = service
+            .get_transactions(account_id, &TransactionQuery::new().with_limit(2))
+            .unwrap();
+        assert_eq!(
+            first.transactions[1].reversed_by,
+            Some(reversals[0].sequence_number)
+        );
+        let after = first.next_after.unwrap();
+        let second = service
+            .get_transactions(
+                account_id,
+                &TransactionQuery::new().with_limit(2).with_after(after),
+            )
+            .unwrap();
+        assert_eq!(second.transactions.len(), 2);
+        assert_eq!(second.next_after, None);
+
+        // Ranges include their start and exclude their end
+        let recorded_at = first.transactions[1].recorded_at;
+        let from = service
+            .get_transactions(account_id, &TransactionQuery::new().with_from(recorded_at))
+            .unwrap();
+        assert_eq!(from.transactions.len(), 3);
+        let to = service
+            .get_transactions(account_id, &TransactionQuery::new().with_to(recorded_at))
+            .unwrap();
+        assert_eq!(to.transactions.len(), 1);
+    }
 }
@@ -1,147 +1,2147 @@
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
+use serde::Serialize;
 use ulid::Ulid;
 
-use crate::account::Account;
-use crate::traits::event::ApplyError;
-use crate::traits::event_bus::EventBusError;
+use crate::account::transactions::{TransactionPage, TransactionQuery};
+use crate::account::{Account, AccountStatus, AsOf};
+use crate::fee::{FeeKind, FeeSchedule};
+use crate::interest::{DayCount, is_month_end, last_month_end};
+use crate::money::Currency;
+use crate::traits::event::{ApplyError, EventEnvelope, EventMetadata};
 use crate::traits::event_store::EventStoreError;
+use crate::traits::fx_rate_provider::{FxPolicy, FxRateError};
 use crate::traits::repository::RepositoryError;
-use crate::traits::{Aggregate, Command, Event, EventBus, EventStore, Repository};
-
-use crate::account::events::AccountEvent;
+use crate::traits::snapshot_store::{Snapshot, SnapshotPolicy, SnapshotStoreError};
+use crate::traits::{
+    Aggregate, Command, Event, EventStore, Repository, SnapshotStore, TransactionRepository,
+};
 
 use super::commands::{
-    DepositCommand, DepositError, OpenAccountCommand, OpenAccountError, WithdrawCommand,
-    WithdrawError,
+    AccrueInterestCommand, AccrueInterestError, CaptureHoldCommand, CaptureHoldError,
+    ChargeFeeCommand, ChargeFeeError, CloseAccountCommand, CloseAccountError,
+    ConvertedDepositCommand, ConvertedDepositError, DepositCommand, DepositError,
+    FreezeAccountCommand, FreezeAccountError, OpenAccountCommand, OpenAccountError,
+    PlaceHoldCommand, PlaceHoldError, PostInterestCommand, PostInterestError,
+    RefundTransferCommand, RefundTransferError, ReleaseHoldCommand, ReleaseHoldError,
+    ReverseTransactionCommand, ReverseTransactionError, SetFeeScheduleCommand, SetFeeScheduleError,
+    SetInterestRateCommand, SetInterestRateError, SetOverdraftLimitCommand, SetOverdraftLimitError,
+    SetWithdrawalLimitsCommand, SetWithdrawalLimitsError, UnfreezeAccountCommand,
+    UnfreezeAccountError, WaiveFeeCommand, WaiveFeeError, WithdrawCommand, WithdrawError,
 };
-use super::events::ACCOUNT_AGGREGATE_TYPE;
+use super::events::{ACCOUNT_AGGREGATE_TYPE, AccountEvent};
 
 use thiserror::Error;
 
+/// Number of times a command is re-run against a freshly loaded account after losing a
+/// concurrent append to another writer.
+const MAX_CONCURRENCY_RETRIES: usize = 3;
+
 #[derive(Debug, Error)]
 pub enum AccountServiceError {
     #[error("Event application error: {0}")]
     ApplyError(#[from] ApplyError),
     #[error("Event store error: {0}")]
     EventStoreError(#[from] EventStoreError),
-    #[error("Event bus error: {0}")]
-    EventBusError(#[from] EventBusError),
     #[error("Repository error: {0}")]
     RepositoryError(#[from] RepositoryError),
+    #[error("Snapshot store error: {0}")]
+    SnapshotStoreError(#[from] SnapshotStoreError),
     #[error("Open account command error: {0}")]
     OpenAccountError(#[from] OpenAccountError),
     #[error("Deposit command error: {0}")]
     DepositError(#[from] DepositError),
     #[error("Withdraw command error: {0}")]
     WithdrawError(#[from] WithdrawError),
+    #[error("Close account command error: {0}")]
+    CloseAccountError(#[from] CloseAccountError),
+    #[error("Freeze account command error: {0}")]
+    FreezeAccountError(#[from] FreezeAccountError),
+    #[error("Unfreeze account command error: {0}")]
+    UnfreezeAccountError(#[from] UnfreezeAccountError),
+    #[error("Set overdraft limit command error: {0}")]
+    SetOverdraftLimitError(#[from] SetOverdraftLimitError),
+    #[error("Set withdrawal limits command error: {0}")]
+    SetWithdrawalLimitsError(#[from] SetWithdrawalLimitsError),
+    #[error("Place hold command error: {0}")]
+    PlaceHoldError(#[from] PlaceHoldError),
+    #[error("Capture hold command error: {0}")]
+    CaptureHoldError(#[from] CaptureHoldError),
+    #[error("Release hold command error: {0}")]
+    ReleaseHoldError(#[from] ReleaseHoldError),
+    #[error("Set interest rate command error: {0}")]
+    SetInterestRateError(#[from] SetInterestRateError),
+    #[error("Accrue interest command error: {0}")]
+    AccrueInterestError(#[from] AccrueInterestError),
+    #[error("Post interest command error: {0}")]
+    PostInterestError(#[from] PostInterestError),
+    #[error("Set fee schedule command error: {0}")]
+    SetFeeScheduleError(#[from] SetFeeScheduleError),
+    #[error("Charge fee command error: {0}")]
+    ChargeFeeError(#[from] ChargeFeeError),
+    #[error("Waive fee command error: {0}")]
+    WaiveFeeError(#[from] WaiveFeeError),
+    #[error("Reverse transaction command error: {0}")]
+    ReverseTransactionError(#[from] ReverseTransactionError),
+    #[error("Converted deposit command error: {0}")]
+    ConvertedDepositError(#[from] ConvertedDepositError),
+    #[error("FX rate error: {0}")]
+    FxRateError(#[from] FxRateError),
+    #[error("Refund transfer command error: {0}")]
+    RefundTransferError(#[from] RefundTransferError),
     #[error("Operation error: {0}")]
     OperationError(String),
 }
 
-pub struct AccountService<R: Repository<Account>, E: EventStore, B: EventBus> {
+impl AccountServiceError {
+    /// Whether the operation failed on the infrastructure rather than being rejected, so it
+    /// may succeed when it is retried.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            AccountServiceError::EventStoreError(_)
+                | AccountServiceError::RepositoryError(_)
+                | AccountServiceError::SnapshotStoreError(_)
+                | AccountServiceError::FxRateError(FxRateError::FxRateProviderError(_))
+        )
+    }
+}
+
+/// The account as it closed `day`: the events recorded by the end of the day, with interest
+/// posted at a month end counting from the day after it however late it was recorded. Accruals
+/// change neither the balance nor the rate and are left out.
+fn closing_state(
+    history: &[EventEnvelope<Account, AccountEvent>],
+    day: NaiveDate,
+) -> Result<Account, ApplyError> {
+    let end_of_day = day
+        .succ_opt()
+        .and_then(|next| next.and_hms_opt(0, 0, 0))
+        .map_or(DateTime::<Utc>::MAX_UTC, |end| end.and_utc());
+
+    Account::from_history(
+        history
+            .iter()
+            .filter(|e| match &e.event {
+                AccountEvent::InterestAccrued(_) => false,
+                AccountEvent::InterestPosted(posting) => posting.posted_on < day,
+                _ => e.metadata.recorded_at < end_of_day,
+            })
+            .map(|e| e.event.clone())
+            .collect(),
+    )
+}
+
+/// `events` followed by the fees the account's schedule charges for `kinds`, each charged on
+/// top of the events before it. The overdraft usage fee is only charged when the account is
+/// left overdrawn.
+fn with_fees(
+    account: &Account,
+    mut events: Vec<AccountEvent>,
+    kinds: &[FeeKind],
+    at: DateTime<Utc>,
+) -> Result<Vec<AccountEvent>, AccountServiceError> {
+    // A command that changed nothing, such as a repeated transfer step, charges nothing
+    let Some(schedule) = account.fee_schedule.as_ref().filter(|_| !events.is_empty()) else {
+        return Ok(events);
+    };
+
+    let mut state = account.clone();
+    for event in &events {
+        event.apply(&mut state)?;
+    }
+
+    for &kind in kinds {
+        let amount = schedule.fee(kind);
+        if amount.is_zero() || (kind == FeeKind::Overdraft && state.balance >= Decimal::ZERO) {
+            continue;
+        }
+
+        let command = ChargeFeeCommand {
+            kind,
+            amount,
+            currency: state.currency,
+            at,
+            period: None,
+        };
+
+        for event in command.execute(state.clone())? {
+            event.apply(&mut state)?;
+            events.push(AccountEvent::FeeCharged(event));
+        }
+    }
+
+    Ok(events)
+}
+
+// Events reach the event bus through the event store's outbox, see `OutboxRelay`
+pub struct AccountService<R: Repository<Account>, E: EventStore + SnapshotStore> {
     repository: R,  // reading
     event_store: E, // writing
-    event_bus: B,   // publishing
+    snapshot_policy: SnapshotPolicy,
+    fx_policy: Option<FxPolicy>,
 }
 
-impl<R: Repository<Account>, E: EventStore, B: EventBus> AccountService<R, E, B> {
-    pub fn new(repository: R, event_store: E, event_bus: B) -> Self {
+impl<R: Repository<Account>, E: EventStore + SnapshotStore> AccountService<R, E> {
+    pub fn new(repository: R, event_store: E) -> Self {
         Self {
             repository,
             event_store,
-            event_bus,
+            snapshot_policy: SnapshotPolicy::default(),
+            fx_policy: None,
         }
     }
 
-    pub fn create_account(&self, balance: Decimal) -> Result<Account, AccountServiceError> {
+    pub fn with_snapshot_policy(mut self, snapshot_policy: SnapshotPolicy) -> Self {
+        self.snapshot_policy = snapshot_policy;
+        self
+    }
+
+    /// Converts deposits in a currency other than the account's instead of rejecting them.
+    pub fn with_fx_policy(mut self, fx_policy: FxPolicy) -> Self {
+        self.fx_policy = Some(fx_policy);
+        self
+    }
+
+    pub fn create_account(
+        &self,
+        balance: Decimal,
+        currency: Currency,
+        metadata: &EventMetadata,
+    ) -> Result<Account, AccountServiceError> {
         let mut account = Account::default();
 
-        let command = OpenAccountCommand { balance };
+        let command = OpenAccountCommand { balance, currency };
 
         let events = command.execute(account.clone())?;
 
-        for event in events {
-            event.apply(&mut account)?;
+        self.commit(
+            &mut account,
+            0,
+            events,
+            metadata,
+            "Account ID is required after creation",
+        )?;
 
-            self.event_store.append_event(
-                account.account_id.ok_or_else(|| {
-                    AccountServiceError::OperationError(
-                        "Account ID is required after creation".to_string(),
-                    )
-                })?,
-                ACCOUNT_AGGREGATE_TYPE,
-                event.clone(),
+        Ok(account)
+    }
+
+    /// Deposits into an account. Amounts in a currency other than the account's are converted
+    /// when an FX policy is set and rejected otherwise.
+    pub fn deposit(
+        &self,
+        account_id: Ulid,
+        amount: Decimal,
+        currency: Currency,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.deposit_for(account_id, amount, currency, None, metadata)
+    }
+
+    /// Credits a transfer to its destination account like [`AccountService::deposit`], at most
+    /// once per transfer: crediting it again changes nothing.
+    pub fn credit_transfer(
+        &self,
+        account_id: Ulid,
+        transfer_id: Ulid,
+        amount: Decimal,
+        currency: Currency,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.deposit_for(account_id, amount, currency, Some(transfer_id), metadata)
+    }
+
+    fn deposit_for(
+        &self,
+        account_id: Ulid,
+        amount: Decimal,
+        currency: Currency,
+        transfer_id: Option<Ulid>,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            if let Some(fx_policy) = &self.fx_policy
+                && account.account_id.is_some()
+                && currency != account.currency
+            {
+                let command = ConvertedDepositCommand {
+                    amount,
+                    currency,
+                    rate: fx_policy.rates.rate(currency, account.currency)?,
+                    spread: fx_policy.spread,
+                    transfer_id,
+                };
+
+                let events = command
+                    .execute(account.clone())?
+                    .into_iter()
+                    .map(AccountEvent::ConvertedDeposit)
+                    .collect();
+                let events = with_fees(&account, events, &[FeeKind::Fx], Utc::now())?;
+
+                return self.commit(
+                    &mut account,
+                    version,
+                    events,
+                    metadata,
+                    "Account ID is required for deposit event",
+                );
+            }
+
+            let command = DepositCommand {
+                amount,
+                currency,
+                transfer_id,
+            };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for deposit event",
+            )
+        })
+    }
+
+    pub fn withdraw(
+        &self,
+        account_id: Ulid,
+        amount: Decimal,
+        currency: Currency,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.withdraw_for(
+            account_id,
+            amount,
+            currency,
+            None,
+            &[FeeKind::Withdrawal, FeeKind::Overdraft],
+            metadata,
+        )
+    }
+
+    /// Debits a transfer from its source account like [`AccountService::withdraw`], at most
+    /// once per transfer: debiting it again changes nothing. The debit counts towards the
+    /// account's withdrawal limits, but of the fees only the overdraft fee applies: the
+    /// per-withdrawal fee is not charged for transfers, and would stay charged if the transfer
+    /// failed and was refunded.
+    pub fn debit_transfer(
+        &self,
+        account_id: Ulid,
+        transfer_id: Ulid,
+        amount: Decimal,
+        currency: Currency,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.withdraw_for(
+            account_id,
+            amount,
+            currency,
+            Some(transfer_id),
+            &[FeeKind::Overdraft],
+            metadata,
+        )
+    }
+
+    fn withdraw_for(
+        &self,
+        account_id: Ulid,
+        amount: Decimal,
+        currency: Currency,
+        transfer_id: Option<Ulid>,
+        fees: &[FeeKind],
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = WithdrawCommand {
+                amount,
+                currency,
+                at: Utc::now(),
+                transfer_id,
+            };
+
+            let events = command
+                .execute(account.clone())?
+                .into_iter()
+                .map(AccountEvent::Withdrawn)
+                .collect();
+            let events = with_fees(&account, events, fees, command.at)?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for withdraw event",
+            )
+        })
+    }
+
+    /// Closes an account for good, which requires its balance to be zero.
+    pub fn close_account(
+        &self,
+        account_id: Ulid,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let events = CloseAccountCommand { at: Utc::now() }.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for close event",
+            )
+        })
+    }
+
+    /// Freezes an account, rejecting deposits and withdrawals until it is unfrozen.
+    pub fn freeze_account(
+        &self,
+        account_id: Ulid,
+        reason: &str,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = FreezeAccountCommand {
+                reason: reason.to_string(),
+            };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for freeze event",
+            )
+        })
+    }
+
+    pub fn unfreeze_account(
+        &self,
+        account_id: Ulid,
+        reason: &str,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = UnfreezeAccountCommand {
+                reason: reason.to_string(),
+            };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for unfreeze event",
+            )
+        })
+    }
+
+    /// Sets how far the account may be overdrawn. Lowering the limit below the current
+    /// overdraft is allowed, further withdrawals are then rejected until the balance recovers.
+    pub fn set_overdraft_limit(
+        &self,
+        account_id: Ulid,
+        limit: Decimal,
+        currency: Currency,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = SetOverdraftLimitCommand { limit, currency };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for overdraft limit event",
+            )
+        })
+    }
+
+    /// Sets the per-transaction and rolling daily withdrawal limits, replacing both.
+    pub fn set_withdrawal_limits(
+        &self,
+        account_id: Ulid,
+        per_transaction: Option<Decimal>,
+        daily: Option<Decimal>,
+        currency: Currency,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = SetWithdrawalLimitsCommand {
+                per_transaction,
+                daily,
+                currency,
+            };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for withdrawal limits event",
+            )
+        })
+    }
+
+    /// Reserves funds on an account until `expires_at`, returning the ID of the hold.
+    pub fn place_hold(
+        &self,
+        account_id: Ulid,
+        amount: Decimal,
+        currency: Currency,
+        expires_at: DateTime<Utc>,
+        metadata: &EventMetadata,
+    ) -> Result<Ulid, AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = PlaceHoldCommand {
+                amount,
+                currency,
+                at: Utc::now(),
+                expires_at,
+            };
+
+            let events = command.execute(account.clone())?;
+            let hold_id = events.first().map(|event| event.hold_id).ok_or_else(|| {
+                AccountServiceError::OperationError("Hold command produced no event".to_string())
+            })?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for hold event",
             )?;
 
-            self.event_bus.produce_event(event)?;
+            Ok(hold_id)
+        })
+    }
+
+    /// Takes the funds of a hold from the account, all of them unless `amount` names less.
+    pub fn capture_hold(
+        &self,
+        account_id: Ulid,
+        hold_id: Ulid,
+        amount: Option<Decimal>,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = CaptureHoldCommand {
+                hold_id,
+                amount,
+                at: Utc::now(),
+            };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for capture event",
+            )
+        })
+    }
+
+    pub fn release_hold(
+        &self,
+        account_id: Ulid,
+        hold_id: Ulid,
+        reason: &str,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = ReleaseHoldCommand {
+                hold_id,
+                reason: reason.to_string(),
+            };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for release event",
+            )
+        })
+    }
+
+    /// Sets the account's annual interest rate, as a fraction.
+    pub fn set_interest_rate(
+        &self,
+        account_id: Ulid,
+        annual_rate: Decimal,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = SetInterestRateCommand { annual_rate };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for interest rate event",
+            )
+        })
+    }
+
+    /// Accrues interest for every day from the one after the last accrual, or the day a rate
+    /// was first set, through `through`, and posts it after each month end. A day's interest is
+    /// computed from the balance and rate the account closed the day with, replayed from its
+    /// events. Days already accrued and month ends already posted are skipped, so re-running is
+    /// harmless. Returns the number of days accrued.
+    pub fn accrue_interest(
+        &self,
+        account_id: Ulid,
+        through: NaiveDate,
+        day_count: DayCount,
+        metadata: &EventMetadata,
+    ) -> Result<usize, AccountServiceError> {
+        let (account, _) = Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+        if account.status == AccountStatus::Closed {
+            return Ok(0);
         }
 
-        Ok(account)
+        // Finishes a run interrupted between accruing a month end and posting it
+        if let Some(accrued_through) = account.interest_accrued_through {
+            self.post_interest(account_id, last_month_end(accrued_through), metadata)?;
+        }
+
+        let mut history = self.get_account_events(account_id)?;
+        let first_day = match account.interest_accrued_through {
+            Some(accrued_through) => accrued_through.succ_opt(),
+            None => history
+                .iter()
+                .find(|e| matches!(e.event, AccountEvent::InterestRateChanged(_)))
+                .map(|e| e.metadata.recorded_at.date_naive()),
+        };
+        let Some(first_day) = first_day else {
+            return Ok(0);
+        };
+
+        let mut accrued = 0;
+        for day in first_day.iter_days().take_while(|day| *day <= through) {
+            let closing = closing_state(&history, day)?;
+
+            self.retry_on_conflict(|| {
+                let (mut account, version) =
+                    Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+                let command = AccrueInterestCommand {
+                    day,
+                    balance: closing.balance,
+                    annual_rate: closing.interest_rate,
+                    day_count,
+                };
+
+                let events = command.execute(account.clone())?;
+                if events.is_empty() {
+                    return Ok(());
+                }
+
+                self.commit(
+                    &mut account,
+                    version,
+                    events,
+                    metadata,
+                    "Account ID is required for interest accrual event",
+                )
+            })?;
+            accrued += 1;
+
+            if is_month_end(day) {
+                self.post_interest(account_id, day, metadata)?;
+                history = self.get_account_events(account_id)?;
+            }
+        }
+
+        Ok(accrued)
     }
 
-    pub fn deposit(&self, account_id: Ulid, amount: Decimal) -> Result<(), AccountServiceError> {
-        let events_envelopes = self
-            .event_store
-            .get_events_for_aggregate(account_id, ACCOUNT_AGGREGATE_TYPE)?;
-        let mut account = Account::from_history::<AccountEvent>(
-            events_envelopes.into_iter().map(|e| e.event).collect(),
-        )?;
+    fn post_interest(
+        &self,
+        account_id: Ulid,
+        posted_on: NaiveDate,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
 
-        let command = DepositCommand { amount };
+            let command = PostInterestCommand { posted_on };
 
-        let events = command.execute(account.clone())?;
+            let events = command.execute(account.clone())?;
+            if events.is_empty() {
+                return Ok(());
+            }
 
-        for event in events {
-            event.apply(&mut account)?;
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for interest posting event",
+            )
+        })
+    }
 
-            self.event_store.append_event(
-                account.account_id.ok_or_else(|| {
-                    AccountServiceError::OperationError(
-                        "Account ID is required for deposit event".to_string(),
-                    )
-                })?,
-                ACCOUNT_AGGREGATE_TYPE,
-                event.clone(),
+    /// Sets the fees charged to the account, in `currency`.
+    pub fn set_fee_schedule(
+        &self,
+        account_id: Ulid,
+        schedule: FeeSchedule,
+        currency: Currency,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = SetFeeScheduleCommand {
+                schedule: schedule.clone(),
+                currency,
+            };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for fee schedule event",
+            )
+        })
+    }
+
+    /// Charges a fee by hand, returning its ID.
+    pub fn charge_fee(
+        &self,
+        account_id: Ulid,
+        kind: FeeKind,
+        amount: Decimal,
+        currency: Currency,
+        metadata: &EventMetadata,
+    ) -> Result<Ulid, AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = ChargeFeeCommand {
+                kind,
+                amount,
+                currency,
+                at: Utc::now(),
+                period: None,
+            };
+
+            let events = command.execute(account.clone())?;
+            let fee_id = events.first().map(|event| event.fee_id).ok_or_else(|| {
+                AccountServiceError::OperationError("Fee command produced no event".to_string())
+            })?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for fee event",
             )?;
 
-            self.event_bus.produce_event(event)?;
+            Ok(fee_id)
+        })
+    }
+
+    /// Refunds a recently charged fee.
+    pub fn waive_fee(
+        &self,
+        account_id: Ulid,
+        fee_id: Ulid,
+        reason: &str,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = WaiveFeeCommand {
+                fee_id,
+                reason: reason.to_string(),
+                at: Utc::now(),
+            };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for fee waiver event",
+            )
+        })
+    }
+
+    /// Charges the monthly maintenance fee for every month end from the one after the last
+    /// charged, or the day a schedule was first set, through `through`, at the fee of the
+    /// schedule in force that day. Only month ends the account closed open are charged, frozen
+    /// and closed accounts pay no maintenance. Month ends already charged are skipped, so
+    /// re-running is harmless. Returns the number of fees charged.
+    pub fn charge_monthly_fees(
+        &self,
+        account_id: Ulid,
+        through: NaiveDate,
+        metadata: &EventMetadata,
+    ) -> Result<usize, AccountServiceError> {
+        let (account, _) = Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+        if account.status == AccountStatus::Closed {
+            return Ok(0);
         }
 
-        Ok(())
+        let history = self.get_account_events(account_id)?;
+        let first_day = match account.maintenance_charged_through {
+            Some(charged_through) => charged_through.succ_opt(),
+            None => history
+                .iter()
+                .find(|e| matches!(e.event, AccountEvent::FeeScheduleChanged(_)))
+                .map(|e| e.metadata.recorded_at.date_naive()),
+        };
+        let Some(first_day) = first_day else {
+            return Ok(0);
+        };
+
+        let mut charged = 0;
+        for month_end in first_day
+            .iter_days()
+            .take_while(|day| *day <= through)
+            .filter(|day| is_month_end(*day))
+        {
+            let closing = closing_state(&history, month_end)?;
+            if closing.status != AccountStatus::Open {
+                continue;
+            }
+            let fee = closing
+                .fee_schedule
+                .map_or(Decimal::ZERO, |schedule| schedule.monthly_maintenance);
+            if fee.is_zero() {
+                continue;
+            }
+
+            let was_charged = self.retry_on_conflict(|| {
+                let (mut account, version) =
+                    Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+                let command = ChargeFeeCommand {
+                    kind: FeeKind::MonthlyMaintenance,
+                    amount: fee,
+                    currency: account.currency,
+                    at: Utc::now(),
+                    period: Some(month_end),
+                };
+
+                let events = command.execute(account.clone())?;
+                if events.is_empty() {
+                    return Ok(false);
+                }
+
+                self.commit(
+                    &mut account,
+                    version,
+                    events,
+                    metadata,
+                    "Account ID is required for fee event",
+                )?;
+                Ok(true)
+            })?;
+            if was_charged {
+                charged += 1;
+            }
+        }
+
+        Ok(charged)
+    }
+
+    /// Undoes the deposit, withdrawal or capture stored under `sequence_number` with a
+    /// compensating event. Each transaction can only be reversed once.
+    pub fn reverse_transaction(
+        &self,
+        account_id: Ulid,
+        sequence_number: Ulid,
+        reason: &str,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = ReverseTransactionCommand {
+                sequence_number,
+                original: self
+                    .get_account_events(account_id)?
+                    .into_iter()
+                    .find(|e| e.sequence_number() == sequence_number)
+                    .map(|e| e.event),
+                reason: reason.to_string(),
+            };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for reversal event",
+            )
+        })
+    }
+
+    /// Gives a transfer's money back to its source account by reversing the withdrawal that
+    /// debited it, whatever the account's status. Refunding it again changes nothing.
+    pub fn refund_transfer(
+        &self,
+        account_id: Ulid,
+        transfer_id: Ulid,
+        reason: &str,
+        metadata: &EventMetadata,
+    ) -> Result<(), AccountServiceError> {
+        self.retry_on_conflict(|| {
+            let (mut account, version) =
+                Account::load::<_, AccountServiceError>(&self.event_store, account_id)?;
+
+            let command = RefundTransferCommand {
+                transfer_id,
+                debit: self
+                    .get_account_events(account_id)?
+                    .into_iter()
+                    .find_map(|e| match e.event {
+                        AccountEvent::Withdrawn(debit)
+                            if debit.transfer_id == Some(transfer_id) =>
+                        {
+                            Some((e.sequence_number, debit))
+                        }
+                        _ => None,
+                    }),
+                reason: reason.to_string(),
+            };
+
+            let events = command.execute(account.clone())?;
+
+            self.commit(
+                &mut account,
+                version,
+                events,
+                metadata,
+                "Account ID is required for refund event",
+            )
+        })
     }
 
-    pub fn withdraw(&self, account_id: Ulid, amount: Decimal) -> Result<(), AccountServiceError> {
-        let events_envelopes = self
+    /// Lists the IDs of every account in the event store, in the order they were opened.
+    pub fn get_account_ids(&self) -> Result<Vec<Ulid>, AccountServiceError> {
+        Ok(self
             .event_store
-            .get_events_for_aggregate(account_id, ACCOUNT_AGGREGATE_TYPE)?;
-        let mut account = Account::from_history::<AccountEvent>(
-            events_envelopes.into_iter().map(|e| e.event).collect(),
-        )?;
+            .get_aggregates(Some(ACCOUNT_AGGREGATE_TYPE))?
+            .into_iter()
+            .map(|aggregate| aggregate.aggregate_id)
+            .collect())
+    }
 
-        let command = WithdrawCommand { amount };
+    pub fn get_account(&self, account_id: Ulid) -> Result<Account, AccountServiceError> {
+        self.repository.get(account_id).map_err(Into::into)
+    }
 
-        let events = command.execute(account.clone())?;
+    /// Rebuilds an account as it was at a point in its history by replaying its events up to
+    /// that point. Snapshots only hold the latest state, so the history is replayed from the
+    /// start, like [`Account::load`] replays them. An account opened after the point is not
+    /// found.
+    pub fn get_account_as_of(
+        &self,
+        account_id: Ulid,
+        as_of: AsOf,
+    ) -> Result<Account, AccountServiceError> {
+        let envelopes: Vec<EventEnvelope<Account, AccountEvent>> = self
+            .event_store
+            .get_events_for_aggregate::<Account, AccountEvent>(account_id, ACCOUNT_AGGREGATE_TYPE)?
+            .into_iter()
+            .take_while(|envelope| as_of.includes(envelope))
+            .collect();
 
-        for event in events {
-            event.apply(&mut account)?;
+        if envelopes.is_empty() {
+            return Err(RepositoryError::NotFound(account_id).into());
+        }
 
-            self.event_store.append_event(
-                account.account_id.ok_or_else(|| {
-                    AccountServiceError::OperationError(
-                        "Account ID is required for withdraw event".to_string(),
-                    )
-                })?,
-                ACCOUNT_AGGREGATE_TYPE,
-                event.clone(),
-            )?;
+        Ok(Account::from_snapshot_and_history(
+            Account::default(),
+            &envelopes,
+        )?)
+    }
 
-            self.event_bus.produce_event(event)?;
+    /// Returns the complete event stream of an account, straight from the event store.
+    pub fn get_account_events(
+        &self,
+        account_id: Ulid,
+    ) -> Result<Vec<EventEnvelope<Account, AccountEvent>>, AccountServiceError> {
+        Ok(self
+            .event_store
+            .get_events_for_aggregate(account_id, ACCOUNT_AGGREGATE_TYPE)?)
+    }
+
+    /// Applies the events produced by a command to `account`, then appends them to the event
+    /// store in a single transaction and snapshots the account if the policy asks for it.
+    fn commit<Ev: Event<Account> + Serialize>(
+        &self,
+        account: &mut Account,
+        expected_version: u64,
+        events: Vec<Ev>,
+        metadata: &EventMetadata,
+        missing_id_message: &str,
+    ) -> Result<(), AccountServiceError> {
+        // Checked against a copy, the account takes the events as recorded once they are stored
+        let mut checked = account.clone();
+        for event in &events {
+            event.apply(&mut checked)?;
+        }
+
+        let account_id = checked
+            .account_id
+            .ok_or_else(|| AccountServiceError::OperationError(missing_id_message.to_string()))?;
+
+        let envelopes = self.event_store.append_events(
+            account_id,
+            ACCOUNT_AGGREGATE_TYPE,
+            expected_version,
+            events,
+            metadata,
+        )?;
+        let version = envelopes.last().map_or(expected_version, |e| e.version());
+        for envelope in &envelopes {
+            envelope.event.apply_recorded(account, &envelope.metadata)?;
+        }
+
+        if self
+            .snapshot_policy
+            .should_snapshot(expected_version, version)
+        {
+            // The events are already stored, a missing snapshot only costs a longer replay
+            let snapshot = Snapshot {
+                aggregate_id: account_id,
+                aggregate_type: ACCOUNT_AGGREGATE_TYPE.to_string(),
+                version,
+                state: account.clone(),
+            };
+            if let Err(e) = self.event_store.save_snapshot(&snapshot) {
+                eprintln!("Failed to snapshot account {}: {}", account_id, e);
+            }
         }
 
         Ok(())
     }
 
-    pub fn get_account(&self, account_id: Ulid) -> Result<Account, AccountServiceError> {
-        self.repository.get(account_id).map_err(Into::into)
+    /// Runs `operation`, re-running it when the event store reports that another writer
+    /// appended to the aggregate first. Gives up after [`MAX_CONCURRENCY_RETRIES`] retries and
+    /// surfaces the conflict to the caller.
+    fn retry_on_conflict<O>(
+        &self,
+        operation: impl Fn() -> Result<O, AccountServiceError>,
+    ) -> Result<O, AccountServiceError> {
+        let mut retries = 0;
+        loop {
+            match operation() {
+                Err(AccountServiceError::EventStoreError(
+                    EventStoreError::ConcurrencyConflict { .. },
+                )) if retries < MAX_CONCURRENCY_RETRIES => retries += 1,
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<R: Repository<Account> + TransactionRepository, E: EventStore + SnapshotStore>
+    AccountService<R, E>
+{
+    /// Lists a page of an account's transactions from the accounts projection, oldest first.
+    pub fn get_transactions(
+        &self,
+        account_id: Ulid,
+        query: &TransactionQuery,
+    ) -> Result<TransactionPage, AccountServiceError> {
+        // Unknown accounts are not found rather than without transactions
+        self.repository.get(account_id)?;
+        Ok(self.repository.get_transactions(account_id, query)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::AccountHandler;
+    use crate::account::repositories::AccountRepositoryInMemory;
+    use crate::event_bus_in_memory::EventBusInMemory;
+    use crate::event_store_in_memory::EventStoreInMemory;
+    use crate::fx_rate_provider_in_memory::FxRateProviderInMemory;
+    use crate::money::{AmountError, Money};
+    use crate::outbox_relay::OutboxRelay;
+    use std::sync::Arc;
+
+    type TestService = AccountService<AccountRepositoryInMemory, EventStoreInMemory>;
+    type TestRelay = OutboxRelay<EventStoreInMemory, EventBusInMemory>;
+
+    fn setup() -> (TestService, TestRelay) {
+        setup_with(EventStoreInMemory::new())
+    }
+
+    /// A service whose events are all recorded on `day`, for tests that work on fixed dates.
+    fn setup_on(day: NaiveDate) -> (TestService, TestRelay) {
+        let recorded_at = day.and_hms_opt(10, 0, 0).unwrap().and_utc();
+        setup_with(EventStoreInMemory::new().with_recorded_at(recorded_at))
+    }
+
+    fn setup_with(event_store: EventStoreInMemory) -> (TestService, TestRelay) {
+        let event_bus = EventBusInMemory::new();
+        let repository = AccountRepositoryInMemory::new();
+
+        AccountHandler::new(repository.clone(), event_bus.clone(), event_store.clone()).listen();
+
+        (
+            AccountService::new(repository, event_store.clone()),
+            OutboxRelay::new(event_store, event_bus),
+        )
+    }
+
+    #[test]
+    fn commands_reach_the_projection_through_the_outbox() {
+        let (service, relay) = setup();
+
+        let account_id = service
+            .create_account(Decimal::from(100), Currency::EUR, &EventMetadata::new())
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .deposit(
+                account_id,
+                Decimal::from(50),
+                Currency::EUR,
+                &EventMetadata::new(),
+            )
+            .unwrap();
+        service
+            .withdraw(
+                account_id,
+                Decimal::from(30),
+                Currency::EUR,
+                &EventMetadata::new(),
+            )
+            .unwrap();
+
+        assert_eq!(relay.relay_pending().unwrap(), 3);
+        assert_eq!(
+            service.get_account(account_id).unwrap().balance,
+            Decimal::from(120)
+        );
+    }
+
+    #[test]
+    fn withdrawal_replays_as_withdrawal() {
+        let (service, _) = setup();
+
+        let account_id = service
+            .create_account(Decimal::from(100), Currency::EUR, &EventMetadata::new())
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .withdraw(
+                account_id,
+                Decimal::from(100),
+                Currency::EUR,
+                &EventMetadata::new(),
+            )
+            .unwrap();
+
+        assert!(matches!(
+            service.withdraw(
+                account_id,
+                Decimal::from(1),
+                Currency::EUR,
+                &EventMetadata::new()
+            ),
+            Err(AccountServiceError::WithdrawError(
+                WithdrawError::InsufficientBalance(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn overdrawn_accounts_replay_after_the_limit_is_lowered() {
+        let (service, relay) = setup();
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(10), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+
+        service
+            .set_overdraft_limit(account_id, Decimal::from(50), Currency::EUR, &metadata)
+            .unwrap();
+        service
+            .withdraw(account_id, Decimal::from(40), Currency::EUR, &metadata)
+            .unwrap();
+        assert!(matches!(
+            service.withdraw(account_id, Decimal::from(21), Currency::EUR, &metadata),
+            Err(AccountServiceError::WithdrawError(
+                WithdrawError::InsufficientBalance(_)
+            ))
+        ));
+
+        // Lowering the limit below the overdraft stops further withdrawals only
+        service
+            .set_overdraft_limit(account_id, Decimal::ZERO, Currency::EUR, &metadata)
+            .unwrap();
+        assert!(matches!(
+            service.withdraw(account_id, Decimal::from(1), Currency::EUR, &metadata),
+            Err(AccountServiceError::WithdrawError(
+                WithdrawError::InsufficientBalance(_)
+            ))
+        ));
+
+        let (account, _) =
+            Account::load::<_, AccountServiceError>(&service.event_store, account_id).unwrap();
+        assert_eq!(account.balance, Decimal::from(-30));
+        assert_eq!(account.overdraft_limit, Decimal::ZERO);
+
+        relay.relay_pending().unwrap();
+        assert_eq!(
+            service.get_account(account_id).unwrap().balance,
+            Decimal::from(-30)
+        );
+    }
+
+    #[test]
+    fn withdrawals_are_limited_per_transaction_and_rolling_day() {
+        let (service, _) = setup();
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(1000), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .set_withdrawal_limits(
+                account_id,
+                Some(Decimal::from(100)),
+                Some(Decimal::from(150)),
+                Currency::EUR,
+                &metadata,
+            )
+            .unwrap();
+
+        assert!(matches!(
+            service.withdraw(account_id, Decimal::from(101), Currency::EUR, &metadata),
+            Err(AccountServiceError::WithdrawError(
+                WithdrawError::TransactionLimitExceeded { .. }
+            ))
+        ));
+        service
+            .withdraw(account_id, Decimal::from(100), Currency::EUR, &metadata)
+            .unwrap();
+        match service.withdraw(account_id, Decimal::from(60), Currency::EUR, &metadata) {
+            Err(AccountServiceError::WithdrawError(WithdrawError::DailyLimitExceeded {
+                remaining,
+                ..
+            })) => assert_eq!(remaining.value(), Decimal::from(50)),
+            other => panic!("expected the daily limit to be exceeded, got {:?}", other),
+        }
+
+        // Withdrawals count from when they were recorded, a day later no longer
+        let (account, _) =
+            Account::load::<_, AccountServiceError>(&service.event_store, account_id).unwrap();
+        let withdrawn = service
+            .event_store
+            .get_events_for_aggregate::<Account, AccountEvent>(account_id, ACCOUNT_AGGREGATE_TYPE)
+            .unwrap()
+            .into_iter()
+            .find(|e| matches!(e.event, AccountEvent::Withdrawn(_)))
+            .unwrap();
+        assert_eq!(
+            account.recent_withdrawals[0].withdrawn_at,
+            withdrawn.metadata.recorded_at
+        );
+        let tomorrow = account.recent_withdrawals[0].withdrawn_at + chrono::TimeDelta::days(1);
+        let command = WithdrawCommand {
+            amount: Decimal::from(100),
+            currency: Currency::EUR,
+            at: tomorrow,
+            transfer_id: None,
+        };
+        assert!(command.execute(account).is_ok());
+    }
+
+    #[test]
+    fn holds_reserve_funds_until_captured_or_expired() {
+        let (service, relay) = setup();
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(100), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        let expires_at = Utc::now() + chrono::TimeDelta::hours(1);
+        let hold_id = service
+            .place_hold(
+                account_id,
+                Decimal::from(70),
+                Currency::EUR,
+                expires_at,
+                &metadata,
+            )
+            .unwrap();
+
+        assert!(matches!(
+            service.withdraw(account_id, Decimal::from(40), Currency::EUR, &metadata),
+            Err(AccountServiceError::WithdrawError(
+                WithdrawError::InsufficientBalance(_)
+            ))
+        ));
+
+        relay.relay_pending().unwrap();
+        let projected = service.get_account(account_id).unwrap();
+        assert_eq!(projected.balance, Decimal::from(100));
+        assert_eq!(projected.available_balance(Utc::now()), Decimal::from(30));
+        assert_eq!(projected.holds[0].hold_id, hold_id);
+
+        // Once expired, the hold neither reserves funds nor can be captured
+        let (account, _) =
+            Account::load::<_, AccountServiceError>(&service.event_store, account_id).unwrap();
+        assert_eq!(account.available_balance(expires_at), Decimal::from(100));
+        let late_capture = CaptureHoldCommand {
+            hold_id,
+            amount: None,
+            at: expires_at,
+        };
+        assert!(matches!(
+            late_capture.execute(account),
+            Err(CaptureHoldError::HoldExpired(_))
+        ));
+
+        service
+            .capture_hold(account_id, hold_id, Some(Decimal::from(50)), &metadata)
+            .unwrap();
+        service
+            .withdraw(account_id, Decimal::from(40), Currency::EUR, &metadata)
+            .unwrap();
+
+        relay.relay_pending().unwrap();
+        let projected = service.get_account(account_id).unwrap();
+        assert_eq!(projected.balance, Decimal::from(10));
+        assert!(projected.holds.is_empty());
+    }
+
+    #[test]
+    fn interest_accrues_daily_and_posts_once_per_month_end() {
+        let opened_on = NaiveDate::from_ymd_opt(2026, 3, 20).unwrap();
+        let month_end = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+        let (service, relay) = setup_on(opened_on);
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(1000), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .set_interest_rate(account_id, Decimal::new(365, 4), &metadata)
+            .unwrap();
+
+        // 1000 at 3.65% over 365 days earns 0.10 a day, from the 20th through the 31st
+        let accrued = service
+            .accrue_interest(account_id, month_end, DayCount::Act365, &metadata)
+            .unwrap();
+        assert_eq!(accrued, 12);
+        let version = service.get_account_events(account_id).unwrap().len();
+
+        // Re-running neither accrues nor posts again
+        let accrued = service
+            .accrue_interest(account_id, month_end, DayCount::Act365, &metadata)
+            .unwrap();
+        assert_eq!(accrued, 0);
+        assert_eq!(
+            service.get_account_events(account_id).unwrap().len(),
+            version
+        );
+
+        relay.relay_pending().unwrap();
+        let projected = service.get_account(account_id).unwrap();
+        assert_eq!(projected.balance, Decimal::new(100120, 2));
+        assert_eq!(projected.accrued_interest, Decimal::ZERO);
+        assert_eq!(projected.interest_rate, Decimal::new(365, 4));
+    }
+
+    #[test]
+    fn frozen_and_closed_accounts_reject_deposits_and_withdrawals() {
+        let (service, relay) = setup();
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(10), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+
+        service
+            .freeze_account(account_id, "suspicious activity", &metadata)
+            .unwrap();
+        assert!(matches!(
+            service.deposit(account_id, Decimal::from(1), Currency::EUR, &metadata),
+            Err(AccountServiceError::DepositError(
+                DepositError::AccountFrozen(_)
+            ))
+        ));
+        assert!(matches!(
+            service.withdraw(account_id, Decimal::from(1), Currency::EUR, &metadata),
+            Err(AccountServiceError::WithdrawError(
+                WithdrawError::AccountFrozen(_)
+            ))
+        ));
+
+        service
+            .unfreeze_account(account_id, "cleared", &metadata)
+            .unwrap();
+        assert!(matches!(
+            service.close_account(account_id, &metadata),
+            Err(AccountServiceError::CloseAccountError(
+                CloseAccountError::NonZeroBalance(_)
+            ))
+        ));
+
+        service
+            .withdraw(account_id, Decimal::from(10), Currency::EUR, &metadata)
+            .unwrap();
+        service.close_account(account_id, &metadata).unwrap();
+        assert!(matches!(
+            service.deposit(account_id, Decimal::from(1), Currency::EUR, &metadata),
+            Err(AccountServiceError::DepositError(
+                DepositError::AccountClosed(_)
+            ))
+        ));
+
+        relay.relay_pending().unwrap();
+        assert_eq!(
+            service.get_account(account_id).unwrap().status,
+            crate::account::AccountStatus::Closed
+        );
+    }
+
+    #[test]
+    fn accounts_close_only_without_open_holds() {
+        let (service, _relay) = setup();
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(0), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .deposit(account_id, Decimal::from(10), Currency::EUR, &metadata)
+            .unwrap();
+        let expires_at = Utc::now() + chrono::TimeDelta::hours(1);
+        let hold_id = service
+            .place_hold(
+                account_id,
+                Decimal::from(10),
+                Currency::EUR,
+                expires_at,
+                &metadata,
+            )
+            .unwrap();
+
+        // Only the hold keeps this account open
+        let (mut account, _) =
+            Account::load::<_, AccountServiceError>(&service.event_store, account_id).unwrap();
+        account.balance = Decimal::ZERO;
+        assert!(matches!(
+            CloseAccountCommand { at: Utc::now() }.execute(account.clone()),
+            Err(CloseAccountError::OpenHolds(_))
+        ));
+        // Expired holds no longer keep the account open
+        assert!(
+            CloseAccountCommand { at: expires_at }
+                .execute(account)
+                .is_ok()
+        );
+
+        service
+            .release_hold(account_id, hold_id, "cancelled", &metadata)
+            .unwrap();
+        service
+            .withdraw(account_id, Decimal::from(10), Currency::EUR, &metadata)
+            .unwrap();
+        service.close_account(account_id, &metadata).unwrap();
+    }
+
+    #[test]
+    fn accounts_close_once_their_interest_is_posted() {
+        let day = |day| {
+            NaiveDate::from_ymd_opt(2026, 3, day)
+                .unwrap()
+                .and_hms_opt(10, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let (service, _relay) = setup_on(day(20).date_naive());
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(1000), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .set_interest_rate(account_id, Decimal::new(1, 2), &metadata)
+            .unwrap();
+        service
+            .accrue_interest(
+                account_id,
+                day(25).date_naive(),
+                DayCount::Act365,
+                &metadata,
+            )
+            .unwrap();
+
+        // 0.16 accrued over six days is still owed to the account
+        service.event_store.set_recorded_at(day(26));
+        service
+            .withdraw(account_id, Decimal::from(1000), Currency::EUR, &metadata)
+            .unwrap();
+        assert!(matches!(
+            service.close_account(account_id, &metadata),
+            Err(AccountServiceError::CloseAccountError(
+                CloseAccountError::AccruedInterest(_)
+            ))
+        ));
+
+        // Posting leaves less than a cent accrued, which never reaches the balance
+        service
+            .accrue_interest(
+                account_id,
+                day(31).date_naive(),
+                DayCount::Act365,
+                &metadata,
+            )
+            .unwrap();
+        service
+            .event_store
+            .set_recorded_at(day(31) + chrono::TimeDelta::days(1));
+        service
+            .withdraw(account_id, Decimal::new(16, 2), Currency::EUR, &metadata)
+            .unwrap();
+        let (account, _) =
+            Account::load::<_, AccountServiceError>(&service.event_store, account_id).unwrap();
+        assert!(account.accrued_interest > Decimal::ZERO);
+        service.close_account(account_id, &metadata).unwrap();
+    }
+
+    #[test]
+    fn negative_deposits_are_rejected() {
+        let (service, _) = setup();
+
+        let account_id = service
+            .create_account(Decimal::from(10), Currency::EUR, &EventMetadata::new())
+            .unwrap()
+            .account_id
+            .unwrap();
+
+        assert!(matches!(
+            service.deposit(
+                account_id,
+                Decimal::from(-5),
+                Currency::EUR,
+                &EventMetadata::new()
+            ),
+            Err(AccountServiceError::DepositError(
+                DepositError::InvalidAmount(AmountError::NotPositive(_))
+            ))
+        ));
+    }
+
+    #[test]
+    fn deposits_must_match_the_account_currency() {
+        let (service, _) = setup();
+
+        let account_id = service
+            .create_account(Decimal::from(10), Currency::JPY, &EventMetadata::new())
+            .unwrap()
+            .account_id
+            .unwrap();
+
+        assert!(matches!(
+            service.deposit(
+                account_id,
+                Decimal::from(5),
+                Currency::EUR,
+                &EventMetadata::new()
+            ),
+            Err(AccountServiceError::DepositError(
+                DepositError::CurrencyMismatch(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn foreign_deposits_are_converted_at_the_recorded_rate() {
+        let (service, relay) = setup();
+        let rates = FxRateProviderInMemory::new()
+            .with_rate(Currency::EUR, Currency::USD, "1.25".parse().unwrap())
+            .unwrap();
+        assert!(matches!(
+            FxRateProviderInMemory::new().with_rate(Currency::EUR, Currency::USD, Decimal::ZERO),
+            Err(FxRateError::InvalidRate(_))
+        ));
+        let service =
+            service.with_fx_policy(FxPolicy::new(Arc::new(rates), "0.01".parse().unwrap()));
+
+        let account_id = service
+            .create_account(Decimal::ZERO, Currency::EUR, &EventMetadata::new())
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .deposit(
+                account_id,
+                Decimal::from(100),
+                Currency::USD,
+                &EventMetadata::new(),
+            )
+            .unwrap();
+
+        let deposit = service
+            .event_store
+            .get_events_for_aggregate::<Account, AccountEvent>(account_id, ACCOUNT_AGGREGATE_TYPE)
+            .unwrap()
+            .pop()
+            .unwrap();
+        let AccountEvent::ConvertedDeposit(event) = deposit.event else {
+            panic!("expected a converted deposit, got {:?}", deposit.event);
+        };
+        assert_eq!(
+            event.source,
+            Money::new(Decimal::from(100), Currency::USD).unwrap()
+        );
+        assert_eq!(event.rate, "0.8".parse::<Decimal>().unwrap());
+        assert_eq!(event.fee.value(), "0.80".parse::<Decimal>().unwrap());
+        assert_eq!(event.amount.value(), "79.20".parse::<Decimal>().unwrap());
+
+        // Replaying uses the stored rate, whatever the provider says today
+        let repriced = FxRateProviderInMemory::new()
+            .with_rate(Currency::EUR, Currency::USD, Decimal::from(2))
+            .unwrap();
+        let service = service.with_fx_policy(FxPolicy::new(Arc::new(repriced), Decimal::ZERO));
+        relay.relay_pending().unwrap();
+        assert_eq!(
+            service.get_account(account_id).unwrap().balance,
+            "79.20".parse::<Decimal>().unwrap()
+        );
+        assert_eq!(
+            Account::load::<_, AccountServiceError>(&service.event_store, account_id)
+                .unwrap()
+                .0
+                .balance,
+            "79.20".parse::<Decimal>().unwrap()
+        );
+    }
+
+    #[test]
+    fn events_stored_without_currency_are_in_the_default_currency() {
+        let account_id = Ulid::new();
+        let envelope = |event_type: &str, event: serde_json::Value| {
+            EventEnvelope::<Account, AccountEvent>::from_json(
+                serde_json::json!({
+                    "sequence_number": Ulid::new(),
+                    "version": 1,
+                    "aggregate_id": account_id,
+                    "aggregate_type": ACCOUNT_AGGREGATE_TYPE,
+                    "event_type": event_type,
+                    "event": event,
+                })
+                .to_string()
+                .as_bytes(),
+            )
+            .unwrap()
+        };
+
+        let opened = envelope(
+            "account_opened",
+            serde_json::json!({ "account_id": account_id, "balance": "10" }),
+        );
+        let deposited = envelope(
+            "deposit",
+            serde_json::json!({ "account_id": account_id, "amount": "5" }),
+        );
+
+        assert_eq!(opened.schema_version, 2);
+        let account = Account::from_history(vec![opened.event, deposited.event]).unwrap();
+        assert_eq!(account.balance, Decimal::from(15));
+        assert_eq!(account.currency, Currency::EUR);
+    }
+
+    #[test]
+    fn fees_are_charged_by_schedule_and_can_be_waived() {
+        let month_end = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+        let (service, relay) = setup_on(NaiveDate::from_ymd_opt(2026, 3, 20).unwrap());
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(100), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .set_overdraft_limit(account_id, Decimal::from(50), Currency::EUR, &metadata)
+            .unwrap();
+        let schedule = FeeSchedule {
+            product: "basic".to_string(),
+            monthly_maintenance: Decimal::from(3),
+            per_withdrawal: Decimal::from(1),
+            overdraft_usage: Decimal::from(5),
+            fx: Decimal::ZERO,
+        };
+        service
+            .set_fee_schedule(account_id, schedule.clone(), Currency::EUR, &metadata)
+            .unwrap();
+
+        // 100 - 60 - 1, then 39 - 50 - 1 - 5 once overdrawn
+        service
+            .withdraw(account_id, Decimal::from(60), Currency::EUR, &metadata)
+            .unwrap();
+        service
+            .withdraw(account_id, Decimal::from(50), Currency::EUR, &metadata)
+            .unwrap();
+
+        assert_eq!(
+            service
+                .charge_monthly_fees(account_id, month_end, &metadata)
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            service
+                .charge_monthly_fees(account_id, month_end, &metadata)
+                .unwrap(),
+            0
+        );
+
+        let (account, _) =
+            Account::load::<_, AccountServiceError>(&service.event_store, account_id).unwrap();
+        assert_eq!(account.balance, Decimal::from(-20));
+        let kinds: Vec<FeeKind> = account.recent_fees.iter().map(|fee| fee.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                FeeKind::Withdrawal,
+                FeeKind::Withdrawal,
+                FeeKind::Overdraft,
+                FeeKind::MonthlyMaintenance
+            ]
+        );
+
+        let overdraft_fee = account.recent_fees[2].fee_id;
+        service
+            .waive_fee(account_id, overdraft_fee, "first overdraft", &metadata)
+            .unwrap();
+        assert!(matches!(
+            service.waive_fee(account_id, overdraft_fee, "again", &metadata),
+            Err(AccountServiceError::WaiveFeeError(
+                WaiveFeeError::FeeNotFound(_)
+            ))
+        ));
+
+        relay.relay_pending().unwrap();
+        let projected = service.get_account(account_id).unwrap();
+        assert_eq!(projected.balance, Decimal::from(-15));
+        assert_eq!(projected.fee_schedule, Some(schedule));
+    }
+
+    #[test]
+    fn maintenance_is_charged_for_open_accounts_only() {
+        let month_end = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+        let (service, _relay) = setup_on(NaiveDate::from_ymd_opt(2026, 3, 20).unwrap());
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(100), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        let schedule = FeeSchedule {
+            product: "basic".to_string(),
+            monthly_maintenance: Decimal::from(3),
+            per_withdrawal: Decimal::ZERO,
+            overdraft_usage: Decimal::ZERO,
+            fx: Decimal::ZERO,
+        };
+        service
+            .set_fee_schedule(account_id, schedule, Currency::EUR, &metadata)
+            .unwrap();
+        service
+            .freeze_account(account_id, "suspicious activity", &metadata)
+            .unwrap();
+
+        assert_eq!(
+            service
+                .charge_monthly_fees(account_id, month_end, &metadata)
+                .unwrap(),
+            0
+        );
+
+        service
+            .unfreeze_account(account_id, "cleared", &metadata)
+            .unwrap();
+        assert_eq!(
+            service
+                .charge_monthly_fees(account_id, month_end, &metadata)
+                .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn transfers_count_towards_limits_but_pay_no_withdrawal_fee() {
+        let (service, _relay) = setup();
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(100), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        let schedule = FeeSchedule {
+            product: "basic".to_string(),
+            monthly_maintenance: Decimal::ZERO,
+            per_withdrawal: Decimal::from(1),
+            overdraft_usage: Decimal::ZERO,
+            fx: Decimal::ZERO,
+        };
+        service
+            .set_fee_schedule(account_id, schedule, Currency::EUR, &metadata)
+            .unwrap();
+        service
+            .set_withdrawal_limits(
+                account_id,
+                None,
+                Some(Decimal::from(50)),
+                Currency::EUR,
+                &metadata,
+            )
+            .unwrap();
+
+        service
+            .debit_transfer(
+                account_id,
+                Ulid::new(),
+                Decimal::from(40),
+                Currency::EUR,
+                &metadata,
+            )
+            .unwrap();
+        assert!(matches!(
+            service.withdraw(account_id, Decimal::from(20), Currency::EUR, &metadata),
+            Err(AccountServiceError::WithdrawError(
+                WithdrawError::DailyLimitExceeded { .. }
+            ))
+        ));
+        service
+            .withdraw(account_id, Decimal::from(10), Currency::EUR, &metadata)
+            .unwrap();
+
+        // Only the withdrawal paid the fee
+        let (account, _) =
+            Account::load::<_, AccountServiceError>(&service.event_store, account_id).unwrap();
+        assert_eq!(account.balance, Decimal::from(49));
+        assert_eq!(account.recent_fees.len(), 1);
+    }
+
+    #[test]
+    fn transactions_are_reversed_once_by_compensating_events() {
+        let (service, relay) = setup();
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(100), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .deposit(account_id, Decimal::from(50), Currency::EUR, &metadata)
+            .unwrap();
+        service
+            .withdraw(account_id, Decimal::from(30), Currency::EUR, &metadata)
+            .unwrap();
+
+        let history = service.get_account_events(account_id).unwrap();
+        let [opened, deposit, withdrawal] = [0, 1, 2].map(|index| history[index].sequence_number());
+
+        service
+            .reverse_transaction(account_id, deposit, "deposited twice", &metadata)
+            .unwrap();
+        service
+            .reverse_transaction(account_id, withdrawal, "disputed", &metadata)
+            .unwrap();
+
+        assert!(matches!(
+            service.reverse_transaction(account_id, deposit, "again", &metadata),
+            Err(AccountServiceError::ReverseTransactionError(
+                ReverseTransactionError::AlreadyReversed(_)
+            ))
+        ));
+        assert!(matches!(
+            service.reverse_transaction(account_id, opened, "opened", &metadata),
+            Err(AccountServiceError::ReverseTransactionError(
+                ReverseTransactionError::NotReversible(_)
+            ))
+        ));
+        assert!(matches!(
+            service.reverse_transaction(account_id, Ulid::new(), "unknown", &metadata),
+            Err(AccountServiceError::ReverseTransactionError(
+                ReverseTransactionError::TransactionNotFound(_)
+            ))
+        ));
+
+        // The originals stay in the history, the reversals name them
+        let history = service.get_account_events(account_id).unwrap();
+        assert_eq!(history.len(), 5);
+        assert!(matches!(
+            &history[3].event,
+            AccountEvent::TransactionReversed(e) if e.reversed_sequence_number == deposit
+        ));
+
+        relay.relay_pending().unwrap();
+        let projected = service.get_account(account_id).unwrap();
+        assert_eq!(projected.balance, Decimal::from(100));
+        let reversed: Vec<Ulid> = projected
+            .reversals
+            .iter()
+            .map(|reversal| reversal.reversed_sequence_number)
+            .collect();
+        assert_eq!(reversed, [deposit, withdrawal]);
+    }
+
+    #[test]
+    fn accounts_are_rebuilt_as_of_a_point_in_history() {
+        let (service, _) = setup();
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(100), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .deposit(account_id, Decimal::from(50), Currency::EUR, &metadata)
+            .unwrap();
+        service
+            .withdraw(account_id, Decimal::from(30), Currency::EUR, &metadata)
+            .unwrap();
+
+        let history = service.get_account_events(account_id).unwrap();
+        let opened_at = history[0].metadata.recorded_at;
+        let deposited_at = history[1].metadata.recorded_at;
+        let balance_as_of = |as_of| {
+            service
+                .get_account_as_of(account_id, as_of)
+                .map(|account| account.balance)
+        };
+        let nanosecond = chrono::TimeDelta::nanoseconds(1);
+
+        // An event recorded at the very moment asked for is included, one recorded a
+        // nanosecond later is not
+        assert_eq!(
+            balance_as_of(AsOf::Time(deposited_at)).unwrap(),
+            Decimal::from(150)
+        );
+        assert_eq!(
+            balance_as_of(AsOf::Time(deposited_at - nanosecond)).unwrap(),
+            Decimal::from(100)
+        );
+        assert_eq!(
+            balance_as_of(AsOf::Time(opened_at)).unwrap(),
+            Decimal::from(100)
+        );
+        assert!(matches!(
+            balance_as_of(AsOf::Time(opened_at - nanosecond)),
+            Err(AccountServiceError::RepositoryError(
+                RepositoryError::NotFound(_)
+            ))
+        ));
+        assert_eq!(
+            balance_as_of(AsOf::Time(Utc::now())).unwrap(),
+            Decimal::from(120)
+        );
+
+        assert_eq!(
+            balance_as_of(AsOf::Sequence(history[1].sequence_number())).unwrap(),
+            Decimal::from(150)
+        );
+        assert_eq!(
+            balance_as_of(AsOf::Sequence(history[0].sequence_number())).unwrap(),
+            Decimal::from(100)
+        );
+
+        // The withdrawal counts towards the daily limit as it did for commands
+        let (account, _) =
+            Account::load::<_, AccountServiceError>(&service.event_store, account_id).unwrap();
+        let as_of = service
+            .get_account_as_of(account_id, AsOf::Sequence(history[2].sequence_number()))
+            .unwrap();
+        assert_eq!(as_of.recent_withdrawals, account.recent_withdrawals);
+        assert_eq!(as_of.recent_withdrawals.len(), 1);
+    }
+
+    #[test]
+    fn snapshots_are_taken_every_n_events_and_resume_replay() {
+        let event_store = EventStoreInMemory::new();
+        let service = AccountService::new(AccountRepositoryInMemory::new(), event_store.clone())
+            .with_snapshot_policy(SnapshotPolicy::EveryNEvents(3));
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(100), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        let snapshot_version = || {
+            event_store
+                .get_snapshot::<Account>(account_id, ACCOUNT_AGGREGATE_TYPE)
+                .unwrap()
+                .map(|snapshot| snapshot.version)
+        };
+        service
+            .deposit(account_id, Decimal::from(10), Currency::EUR, &metadata)
+            .unwrap();
+        assert_eq!(snapshot_version(), None);
+
+        // The third event crosses the threshold, the two after it are replayed on top
+        for amount in [20, 30, 40] {
+            service
+                .deposit(account_id, Decimal::from(amount), Currency::EUR, &metadata)
+                .unwrap();
+        }
+        assert_eq!(snapshot_version(), Some(3));
+
+        let (loaded, version) =
+            Account::load::<_, AccountServiceError>(&event_store, account_id).unwrap();
+        let replayed = Account::from_history(
+            event_store
+                .get_events_for_aggregate::<Account, AccountEvent>(
+                    account_id,
+                    ACCOUNT_AGGREGATE_TYPE,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|e| e.event)
+                .collect(),
+        )
+        .unwrap();
+
+        assert_eq!(version, 5);
+        assert_eq!(loaded.balance, Decimal::from(200));
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&replayed).unwrap()
+        );
+    }
+
+    #[test]
+    fn stale_append_is_rejected() {
+        let event_store = EventStoreInMemory::new();
+        let account_id = Ulid::new();
+        let event = || crate::account::events::DepositEvent {
+            account_id,
+            amount: crate::money::Money::new(Decimal::from(1), Currency::EUR).unwrap(),
+            transfer_id: None,
+        };
+
+        event_store
+            .append_event(
+                account_id,
+                ACCOUNT_AGGREGATE_TYPE,
+                0,
+                event(),
+                &EventMetadata::new(),
+            )
+            .unwrap();
+
+        assert!(matches!(
+            event_store.append_event(
+                account_id,
+                ACCOUNT_AGGREGATE_TYPE,
+                0,
+                event(),
+                &EventMetadata::new(),
+            ),
+            Err(EventStoreError::ConcurrencyConflict {
+                expected: 0,
+                actual: 1
+            })
+        ));
     }
 }
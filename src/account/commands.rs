@@ -1,12 +1,63 @@
+pub mod accrue_interest_command;
+pub mod capture_hold_command;
+pub mod charge_fee_command;
+pub mod close_account_command;
+pub mod converted_deposit_command;
 pub mod deposit_command;
+pub mod freeze_account_command;
 pub mod open_account_command;
+pub mod place_hold_command;
+pub mod post_interest_command;
+pub mod refund_transfer_command;
+pub mod release_hold_command;
+pub mod reverse_transaction_command;
+pub mod set_fee_schedule_command;
+pub mod set_interest_rate_command;
+pub mod set_overdraft_limit_command;
+pub mod set_withdrawal_limits_command;
+pub mod unfreeze_account_command;
+pub mod waive_fee_command;
 pub mod withdraw_command;
 
+pub use accrue_interest_command::AccrueInterestCommand;
+pub use capture_hold_command::CaptureHoldCommand;
+pub use charge_fee_command::ChargeFeeCommand;
+pub use close_account_command::CloseAccountCommand;
+pub use converted_deposit_command::ConvertedDepositCommand;
 pub use deposit_command::DepositCommand;
+pub use freeze_account_command::FreezeAccountCommand;
 pub use open_account_command::OpenAccountCommand;
+pub use place_hold_command::PlaceHoldCommand;
+pub use post_interest_command::PostInterestCommand;
+pub use refund_transfer_command::RefundTransferCommand;
+pub use release_hold_command::ReleaseHoldCommand;
+pub use reverse_transaction_command::ReverseTransactionCommand;
+pub use set_fee_schedule_command::SetFeeScheduleCommand;
+pub use set_interest_rate_command::SetInterestRateCommand;
+pub use set_overdraft_limit_command::SetOverdraftLimitCommand;
+pub use set_withdrawal_limits_command::SetWithdrawalLimitsCommand;
+pub use unfreeze_account_command::UnfreezeAccountCommand;
+pub use waive_fee_command::WaiveFeeCommand;
 pub use withdraw_command::WithdrawCommand;
 
 // Re-export error types
+pub use accrue_interest_command::AccrueInterestError;
+pub use capture_hold_command::CaptureHoldError;
+pub use charge_fee_command::ChargeFeeError;
+pub use close_account_command::CloseAccountError;
+pub use converted_deposit_command::ConvertedDepositError;
 pub use deposit_command::DepositError;
+pub use freeze_account_command::FreezeAccountError;
 pub use open_account_command::OpenAccountError;
+pub use place_hold_command::PlaceHoldError;
+pub use post_interest_command::PostInterestError;
+pub use refund_transfer_command::RefundTransferError;
+pub use release_hold_command::ReleaseHoldError;
+pub use reverse_transaction_command::ReverseTransactionError;
+pub use set_fee_schedule_command::SetFeeScheduleError;
+pub use set_interest_rate_command::SetInterestRateError;
+pub use set_overdraft_limit_command::SetOverdraftLimitError;
+pub use set_withdrawal_limits_command::SetWithdrawalLimitsError;
+pub use unfreeze_account_command::UnfreezeAccountError;
+pub use waive_fee_command::WaiveFeeError;
 pub use withdraw_command::WithdrawError;
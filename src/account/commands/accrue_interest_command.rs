@@ -0,0 +1,52 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::{
+    account::{Account, events::InterestAccruedEvent},
+    interest::DayCount,
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum AccrueInterestError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+}
+
+/// Accrues a day's interest on the balance the account closed the day with, at the rate in
+/// force that day. Days already accrued produce no event, so re-running accruals is harmless.
+pub struct AccrueInterestCommand {
+    pub day: NaiveDate,
+    pub balance: Decimal,
+    pub annual_rate: Decimal,
+    pub day_count: DayCount,
+}
+
+impl Command<Account, InterestAccruedEvent, AccrueInterestError> for AccrueInterestCommand {
+    fn execute(&self, account: Account) -> Result<Vec<InterestAccruedEvent>, AccrueInterestError> {
+        let account_id = account.account_id.ok_or_else(|| {
+            AccrueInterestError::AccountNotOpened(
+                "Account ID is missing, cannot accrue interest.".to_string(),
+            )
+        })?;
+
+        if account
+            .interest_accrued_through
+            .is_some_and(|through| self.day <= through)
+        {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![InterestAccruedEvent {
+            account_id,
+            day: self.day,
+            balance: self.balance,
+            annual_rate: self.annual_rate,
+            day_count: self.day_count,
+            amount: self
+                .day_count
+                .daily_interest(self.balance, self.annual_rate, self.day),
+        }])
+    }
+}
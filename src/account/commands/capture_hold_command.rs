@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    account::{Account, AccountStatus, events::HoldCapturedEvent},
+    money::{AmountError, Money},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum CaptureHoldError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Account frozen: {0}")]
+    AccountFrozen(String),
+    #[error("Hold not found: {0}")]
+    HoldNotFound(String),
+    #[error("Hold expired: {0}")]
+    HoldExpired(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+    #[error("Capture exceeds hold: {0}")]
+    ExceedsHold(String),
+}
+
+/// Takes the funds of a hold from the account, all of them unless `amount` names less. The
+/// rest of the hold is released.
+pub struct CaptureHoldCommand {
+    pub hold_id: Ulid,
+    pub amount: Option<Decimal>,
+    /// When the hold is captured, it must not have expired by then.
+    pub at: DateTime<Utc>,
+}
+
+impl Command<Account, HoldCapturedEvent, CaptureHoldError> for CaptureHoldCommand {
+    fn execute(&self, account: Account) -> Result<Vec<HoldCapturedEvent>, CaptureHoldError> {
+        let account_id = account.account_id.ok_or_else(|| {
+            CaptureHoldError::AccountNotOpened(
+                "Account ID is missing, cannot capture hold.".to_string(),
+            )
+        })?;
+
+        match account.status {
+            AccountStatus::Open => {}
+            AccountStatus::Frozen => {
+                return Err(CaptureHoldError::AccountFrozen(format!(
+                    "Account {} is frozen, cannot capture hold.",
+                    account_id
+                )));
+            }
+            AccountStatus::Closed => {
+                return Err(CaptureHoldError::AccountClosed(format!(
+                    "Account {} is closed, cannot capture hold.",
+                    account_id
+                )));
+            }
+        }
+
+        let hold = account.hold(self.hold_id).ok_or_else(|| {
+            CaptureHoldError::HoldNotFound(format!(
+                "Account {} has no hold {}.",
+                account_id, self.hold_id
+            ))
+        })?;
+
+        if !hold.is_open_at(self.at) {
+            return Err(CaptureHoldError::HoldExpired(format!(
+                "Hold {} expired at {}.",
+                self.hold_id, hold.expires_at
+            )));
+        }
+
+        let amount = Money::new(self.amount.unwrap_or(hold.amount), account.currency)?;
+        if amount.value() > hold.amount {
+            return Err(CaptureHoldError::ExceedsHold(format!(
+                "Cannot capture {} from hold {} of {} {}.",
+                amount, self.hold_id, hold.amount, account.currency
+            )));
+        }
+
+        Ok(vec![HoldCapturedEvent {
+            account_id,
+            hold_id: self.hold_id,
+            amount,
+            captured_at: self.at,
+        }])
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    account::{Account, AccountStatus, events::FeeChargedEvent},
+    fee::FeeKind,
+    money::{AmountError, Currency, Money},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum ChargeFeeError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+    #[error("Currency mismatch: {0}")]
+    CurrencyMismatch(String),
+}
+
+/// Takes a fee from the account, frozen accounts included. Fees are not checked against the
+/// available balance.
+pub struct ChargeFeeCommand {
+    pub kind: FeeKind,
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub at: DateTime<Utc>,
+    /// Month end a monthly maintenance fee is charged for, nothing is charged when the account
+    /// was already charged for it.
+    pub period: Option<NaiveDate>,
+}
+
+impl Command<Account, FeeChargedEvent, ChargeFeeError> for ChargeFeeCommand {
+    fn execute(&self, account: Account) -> Result<Vec<FeeChargedEvent>, ChargeFeeError> {
+        let amount = Money::new(self.amount, self.currency)?;
+
+        let account_id = account.account_id.ok_or_else(|| {
+            ChargeFeeError::AccountNotOpened(
+                "Account ID is missing, cannot charge fee.".to_string(),
+            )
+        })?;
+
+        if account.status == AccountStatus::Closed {
+            return Err(ChargeFeeError::AccountClosed(format!(
+                "Account {} is closed, cannot charge fee.",
+                account_id
+            )));
+        }
+
+        if amount.currency() != account.currency {
+            return Err(ChargeFeeError::CurrencyMismatch(format!(
+                "Account {} is held in {}, cannot charge a fee of {}.",
+                account_id, account.currency, amount
+            )));
+        }
+
+        if let Some(period) = self.period
+            && account
+                .maintenance_charged_through
+                .is_some_and(|through| period <= through)
+        {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![FeeChargedEvent {
+            account_id,
+            fee_id: Ulid::new(),
+            kind: self.kind,
+            amount,
+            charged_at: self.at,
+            period: self.period,
+        }])
+    }
+}
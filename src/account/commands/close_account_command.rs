@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use thiserror::Error;
+
+use crate::{
+    account::{Account, AccountStatus, events::AccountClosedEvent},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum CloseAccountError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account already closed: {0}")]
+    AccountClosed(String),
+    #[error("Account balance is not zero: {0}")]
+    NonZeroBalance(String),
+    #[error("Account has open holds: {0}")]
+    OpenHolds(String),
+    #[error("Account has accrued interest: {0}")]
+    AccruedInterest(String),
+}
+
+pub struct CloseAccountCommand {
+    /// When the account is closed, holds still open by then keep it open.
+    pub at: DateTime<Utc>,
+}
+
+impl Command<Account, AccountClosedEvent, CloseAccountError> for CloseAccountCommand {
+    fn execute(&self, account: Account) -> Result<Vec<AccountClosedEvent>, CloseAccountError> {
+        let account_id = account.account_id.ok_or_else(|| {
+            CloseAccountError::AccountNotOpened(
+                "Account ID is missing, cannot close account.".to_string(),
+            )
+        })?;
+
+        if account.status == AccountStatus::Closed {
+            return Err(CloseAccountError::AccountClosed(format!(
+                "Account {} is already closed.",
+                account_id
+            )));
+        }
+
+        if account.balance != Decimal::from(0) {
+            return Err(CloseAccountError::NonZeroBalance(format!(
+                "Account {} still holds {}, withdraw it before closing.",
+                account_id, account.balance
+            )));
+        }
+
+        let held = account.held_at(self.at);
+        if held != Decimal::ZERO {
+            return Err(CloseAccountError::OpenHolds(format!(
+                "Account {} still holds {} for open holds, capture or release them before closing.",
+                account_id, held
+            )));
+        }
+
+        // Less than a minor unit is never posted, so only what posting would credit counts
+        let postable = account
+            .accrued_interest
+            .round_dp_with_strategy(account.currency.minor_units(), RoundingStrategy::ToZero);
+        if postable != Decimal::ZERO {
+            return Err(CloseAccountError::AccruedInterest(format!(
+                "Account {} has {} of interest accrued but not posted, post it before closing.",
+                account_id, postable
+            )));
+        }
+
+        Ok(vec![AccountClosedEvent { account_id }])
+    }
+}
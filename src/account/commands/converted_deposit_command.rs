@@ -0,0 +1,102 @@
+use rust_decimal::Decimal;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    account::{Account, AccountStatus, events::ConvertedDepositEvent},
+    money::{AmountError, Currency, Money},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum ConvertedDepositError {
+    #[error("Account ID is required: {0}")]
+    AccountIdMissing(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Account frozen: {0}")]
+    AccountFrozen(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+    #[error("Invalid exchange rate: {0}")]
+    InvalidRate(String),
+}
+
+/// Deposits an amount in a foreign currency, converting it into the account currency at
+/// `rate` less `spread`.
+pub struct ConvertedDepositCommand {
+    pub amount: Decimal,
+    pub currency: Currency,
+    pub rate: Decimal,
+    pub spread: Decimal,
+    /// Transfer the deposit is the credit of, see [`DepositCommand::transfer_id`].
+    ///
+    /// [`DepositCommand::transfer_id`]: super::DepositCommand::transfer_id
+    pub transfer_id: Option<Ulid>,
+}
+
+impl Command<Account, ConvertedDepositEvent, ConvertedDepositError> for ConvertedDepositCommand {
+    fn execute(
+        &self,
+        account: Account,
+    ) -> Result<Vec<ConvertedDepositEvent>, ConvertedDepositError> {
+        let source = Money::new(self.amount, self.currency)?;
+
+        let account_id = account.account_id.ok_or_else(|| {
+            ConvertedDepositError::AccountIdMissing(
+                "Account ID is required for deposit".to_string(),
+            )
+        })?;
+
+        if self.transfer_id.is_some_and(|id| account.has_transfer(id)) {
+            return Ok(vec![]);
+        }
+
+        match account.status {
+            AccountStatus::Open => {}
+            AccountStatus::Frozen => {
+                return Err(ConvertedDepositError::AccountFrozen(format!(
+                    "Account {} is frozen, cannot deposit.",
+                    account_id
+                )));
+            }
+            AccountStatus::Closed => {
+                return Err(ConvertedDepositError::AccountClosed(format!(
+                    "Account {} is closed, cannot deposit.",
+                    account_id
+                )));
+            }
+        }
+
+        if self.rate <= Decimal::ZERO {
+            return Err(ConvertedDepositError::InvalidRate(format!(
+                "Rate from {} to {} must be positive, got {}.",
+                source.currency(),
+                account.currency,
+                self.rate
+            )));
+        }
+
+        if self.spread < Decimal::ZERO || self.spread >= Decimal::ONE {
+            return Err(ConvertedDepositError::InvalidRate(format!(
+                "Spread must be at least 0 and below 1, got {}.",
+                self.spread
+            )));
+        }
+
+        // Both amounts are rounded to the account currency, the fee is what the spread cost
+        let currency = account.currency;
+        let at_mid_rate = currency.round(source.value() * self.rate);
+        let converted = currency.round(source.value() * self.rate * (Decimal::ONE - self.spread));
+
+        Ok(vec![ConvertedDepositEvent {
+            account_id,
+            source,
+            rate: self.rate,
+            spread: self.spread,
+            fee: Money::non_negative(at_mid_rate - converted, currency)?,
+            amount: Money::new(converted, currency)?,
+            transfer_id: self.transfer_id,
+        }])
+    }
+}
@@ -1,8 +1,10 @@
 use rust_decimal::Decimal;
 use thiserror::Error;
+use ulid::Ulid;
 
 use crate::{
-    account::{Account, events::DepositEvent},
+    account::{Account, AccountStatus, events::DepositEvent},
+    money::{AmountError, Currency, Money},
     traits::Command,
 };
 
@@ -10,19 +12,64 @@ use crate::{
 pub enum DepositError {
     #[error("Account ID is required: {0}")]
     AccountIdMissing(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Account frozen: {0}")]
+    AccountFrozen(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+    #[error("Currency mismatch: {0}")]
+    CurrencyMismatch(String),
 }
 
 pub struct DepositCommand {
     pub amount: Decimal,
+    pub currency: Currency,
+    /// Transfer the deposit is the credit of. A transfer that already credited the account is
+    /// not deposited again, the command then produces no events.
+    pub transfer_id: Option<Ulid>,
 }
 
 impl Command<Account, DepositEvent, DepositError> for DepositCommand {
     fn execute(&self, account: Account) -> Result<Vec<DepositEvent>, DepositError> {
+        // A negative deposit would be a withdrawal that skips the balance check
+        let amount = Money::new(self.amount, self.currency)?;
+
+        let account_id = account.account_id.ok_or_else(|| {
+            DepositError::AccountIdMissing("Account ID is required for deposit".to_string())
+        })?;
+
+        if self.transfer_id.is_some_and(|id| account.has_transfer(id)) {
+            return Ok(vec![]);
+        }
+
+        match account.status {
+            AccountStatus::Open => {}
+            AccountStatus::Frozen => {
+                return Err(DepositError::AccountFrozen(format!(
+                    "Account {} is frozen, cannot deposit.",
+                    account_id
+                )));
+            }
+            AccountStatus::Closed => {
+                return Err(DepositError::AccountClosed(format!(
+                    "Account {} is closed, cannot deposit.",
+                    account_id
+                )));
+            }
+        }
+
+        if amount.currency() != account.currency {
+            return Err(DepositError::CurrencyMismatch(format!(
+                "Account {} is held in {}, cannot deposit {}.",
+                account_id, account.currency, amount
+            )));
+        }
+
         Ok(vec![DepositEvent {
-            account_id: account.account_id.ok_or_else(|| {
-                DepositError::AccountIdMissing("Account ID is required for deposit".to_string())
-            })?,
-            amount: self.amount,
+            account_id,
+            amount,
+            transfer_id: self.transfer_id,
         }])
     }
 }
@@ -0,0 +1,45 @@
+use thiserror::Error;
+
+use crate::{
+    account::{Account, AccountStatus, events::AccountFrozenEvent},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum FreezeAccountError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Account already frozen: {0}")]
+    AccountFrozen(String),
+}
+
+pub struct FreezeAccountCommand {
+    pub reason: String,
+}
+
+impl Command<Account, AccountFrozenEvent, FreezeAccountError> for FreezeAccountCommand {
+    fn execute(&self, account: Account) -> Result<Vec<AccountFrozenEvent>, FreezeAccountError> {
+        let account_id = account.account_id.ok_or_else(|| {
+            FreezeAccountError::AccountNotOpened(
+                "Account ID is missing, cannot freeze account.".to_string(),
+            )
+        })?;
+
+        match account.status {
+            AccountStatus::Open => Ok(vec![AccountFrozenEvent {
+                account_id,
+                reason: self.reason.clone(),
+            }]),
+            AccountStatus::Frozen => Err(FreezeAccountError::AccountFrozen(format!(
+                "Account {} is already frozen.",
+                account_id
+            ))),
+            AccountStatus::Closed => Err(FreezeAccountError::AccountClosed(format!(
+                "Account {} is closed and cannot be frozen.",
+                account_id
+            ))),
+        }
+    }
+}
@@ -4,23 +4,26 @@ use ulid::Ulid;
 
 use crate::{
     account::{Account, events::AccountOpenedEvent},
+    money::{AmountError, Currency, Money},
     traits::Command,
 };
 
 #[derive(Debug, Error)]
 pub enum OpenAccountError {
-    // No variants defined yet, can be added if specific errors arise
+    #[error("Invalid opening balance: {0}")]
+    InvalidAmount(#[from] AmountError),
 }
 
 pub struct OpenAccountCommand {
     pub balance: Decimal,
+    pub currency: Currency,
 }
 
 impl Command<Account, AccountOpenedEvent, OpenAccountError> for OpenAccountCommand {
     fn execute(&self, _: Account) -> Result<Vec<AccountOpenedEvent>, OpenAccountError> {
         Ok(vec![AccountOpenedEvent {
             account_id: Ulid::new(),
-            balance: self.balance,
+            balance: Money::non_negative(self.balance, self.currency)?,
         }])
     }
 }
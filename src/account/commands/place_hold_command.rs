@@ -0,0 +1,95 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    account::{Account, AccountStatus, events::HoldPlacedEvent},
+    money::{AmountError, Currency, Money},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum PlaceHoldError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Account frozen: {0}")]
+    AccountFrozen(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+    #[error("Currency mismatch: {0}")]
+    CurrencyMismatch(String),
+    #[error("Insufficient balance: {0}")]
+    InsufficientBalance(String),
+    #[error("Invalid expiry: {0}")]
+    InvalidExpiry(String),
+}
+
+/// Reserves funds on the account until `expires_at`, for a later capture.
+pub struct PlaceHoldCommand {
+    pub amount: Decimal,
+    pub currency: Currency,
+    /// When the hold is placed.
+    pub at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Command<Account, HoldPlacedEvent, PlaceHoldError> for PlaceHoldCommand {
+    fn execute(&self, account: Account) -> Result<Vec<HoldPlacedEvent>, PlaceHoldError> {
+        let amount = Money::new(self.amount, self.currency)?;
+
+        let account_id = account.account_id.ok_or_else(|| {
+            PlaceHoldError::AccountNotOpened(
+                "Account ID is missing, cannot place hold.".to_string(),
+            )
+        })?;
+
+        match account.status {
+            AccountStatus::Open => {}
+            AccountStatus::Frozen => {
+                return Err(PlaceHoldError::AccountFrozen(format!(
+                    "Account {} is frozen, cannot place hold.",
+                    account_id
+                )));
+            }
+            AccountStatus::Closed => {
+                return Err(PlaceHoldError::AccountClosed(format!(
+                    "Account {} is closed, cannot place hold.",
+                    account_id
+                )));
+            }
+        }
+
+        if amount.currency() != account.currency {
+            return Err(PlaceHoldError::CurrencyMismatch(format!(
+                "Account {} is held in {}, cannot hold {}.",
+                account_id, account.currency, amount
+            )));
+        }
+
+        if self.expires_at <= self.at {
+            return Err(PlaceHoldError::InvalidExpiry(format!(
+                "Hold would expire at {}, before it is placed.",
+                self.expires_at
+            )));
+        }
+
+        let available = account.available_balance(self.at);
+        if amount.value() > available {
+            return Err(PlaceHoldError::InsufficientBalance(format!(
+                "Cannot hold {} on account {}, only {} {} is available.",
+                amount, account_id, available, account.currency
+            )));
+        }
+
+        Ok(vec![HoldPlacedEvent {
+            account_id,
+            hold_id: Ulid::new(),
+            amount,
+            placed_at: self.at,
+            expires_at: self.expires_at,
+        }])
+    }
+}
@@ -0,0 +1,54 @@
+use chrono::NaiveDate;
+use rust_decimal::{Decimal, RoundingStrategy};
+use thiserror::Error;
+
+use crate::{
+    account::{Account, events::InterestPostedEvent},
+    money::{AmountError, Money},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum PostInterestError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+}
+
+/// Credits the interest accrued up to a month end, rounded to the currency. Produces no event
+/// when that month end was already posted or less than a minor unit has accrued.
+pub struct PostInterestCommand {
+    pub posted_on: NaiveDate,
+}
+
+impl Command<Account, InterestPostedEvent, PostInterestError> for PostInterestCommand {
+    fn execute(&self, account: Account) -> Result<Vec<InterestPostedEvent>, PostInterestError> {
+        let account_id = account.account_id.ok_or_else(|| {
+            PostInterestError::AccountNotOpened(
+                "Account ID is missing, cannot post interest.".to_string(),
+            )
+        })?;
+
+        if account
+            .interest_posted_through
+            .is_some_and(|through| self.posted_on <= through)
+        {
+            return Ok(vec![]);
+        }
+
+        // Rounding down never posts more than was accrued
+        let amount = account
+            .accrued_interest
+            .round_dp_with_strategy(account.currency.minor_units(), RoundingStrategy::ToZero);
+        if amount <= Decimal::ZERO {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![InterestPostedEvent {
+            account_id,
+            posted_on: self.posted_on,
+            amount: Money::new(amount, account.currency)?,
+        }])
+    }
+}
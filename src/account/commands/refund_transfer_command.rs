@@ -0,0 +1,66 @@
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    account::{
+        Account,
+        events::{TransactionReversedEvent, WithdrawEvent},
+    },
+    traits::{Command, Event},
+};
+
+#[derive(Debug, Error)]
+pub enum RefundTransferError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Transfer debit not found: {0}")]
+    DebitNotFound(String),
+}
+
+/// Gives a transfer's money back to its source account by reversing the withdrawal that
+/// debited it. The transfer already left the account, so the refund is made whatever the
+/// account's status, and a debit that was already refunded produces no events.
+pub struct RefundTransferCommand {
+    pub transfer_id: Ulid,
+    /// Sequence number and event of the transfer's withdrawal, `None` if the account has none.
+    pub debit: Option<(Ulid, WithdrawEvent)>,
+    pub reason: String,
+}
+
+impl Command<Account, TransactionReversedEvent, RefundTransferError> for RefundTransferCommand {
+    fn execute(
+        &self,
+        account: Account,
+    ) -> Result<Vec<TransactionReversedEvent>, RefundTransferError> {
+        let account_id = account.account_id.ok_or_else(|| {
+            RefundTransferError::AccountNotOpened(
+                "Account ID is missing, cannot refund transfer.".to_string(),
+            )
+        })?;
+
+        let (sequence_number, debit) = self
+            .debit
+            .as_ref()
+            .filter(|(_, debit)| {
+                debit.account_id == account_id && debit.transfer_id == Some(self.transfer_id)
+            })
+            .ok_or_else(|| {
+                RefundTransferError::DebitNotFound(format!(
+                    "Transfer {} never debited account {}.",
+                    self.transfer_id, account_id
+                ))
+            })?;
+
+        if account.is_reversed(*sequence_number) {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![TransactionReversedEvent {
+            account_id,
+            reversed_sequence_number: *sequence_number,
+            reversed_event_type: debit.event_type().to_string(),
+            amount: debit.amount,
+            reason: self.reason.clone(),
+        }])
+    }
+}
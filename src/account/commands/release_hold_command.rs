@@ -0,0 +1,44 @@
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    account::{Account, events::HoldReleasedEvent},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum ReleaseHoldError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Hold not found: {0}")]
+    HoldNotFound(String),
+}
+
+/// Lifts a hold, expired or not, without taking any funds.
+pub struct ReleaseHoldCommand {
+    pub hold_id: Ulid,
+    pub reason: String,
+}
+
+impl Command<Account, HoldReleasedEvent, ReleaseHoldError> for ReleaseHoldCommand {
+    fn execute(&self, account: Account) -> Result<Vec<HoldReleasedEvent>, ReleaseHoldError> {
+        let account_id = account.account_id.ok_or_else(|| {
+            ReleaseHoldError::AccountNotOpened(
+                "Account ID is missing, cannot release hold.".to_string(),
+            )
+        })?;
+
+        if account.hold(self.hold_id).is_none() {
+            return Err(ReleaseHoldError::HoldNotFound(format!(
+                "Account {} has no hold {}.",
+                account_id, self.hold_id
+            )));
+        }
+
+        Ok(vec![HoldReleasedEvent {
+            account_id,
+            hold_id: self.hold_id,
+            reason: self.reason.clone(),
+        }])
+    }
+}
@@ -0,0 +1,96 @@
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    account::{
+        Account, AccountStatus,
+        events::{AccountEvent, TransactionReversedEvent},
+    },
+    traits::{Command, Event},
+};
+
+#[derive(Debug, Error)]
+pub enum ReverseTransactionError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Transaction not found: {0}")]
+    TransactionNotFound(String),
+    #[error("Transaction not reversible: {0}")]
+    NotReversible(String),
+    #[error("Transaction already reversed: {0}")]
+    AlreadyReversed(String),
+}
+
+/// Undoes a deposit, withdrawal or capture with a compensating event, leaving the original in
+/// place. The reversal may overdraw the account.
+pub struct ReverseTransactionCommand {
+    pub sequence_number: Ulid,
+    /// The event stored under `sequence_number`, `None` if the account has no such event.
+    pub original: Option<AccountEvent>,
+    pub reason: String,
+}
+
+impl Command<Account, TransactionReversedEvent, ReverseTransactionError>
+    for ReverseTransactionCommand
+{
+    fn execute(
+        &self,
+        account: Account,
+    ) -> Result<Vec<TransactionReversedEvent>, ReverseTransactionError> {
+        let account_id = account.account_id.ok_or_else(|| {
+            ReverseTransactionError::AccountNotOpened(
+                "Account ID is missing, cannot reverse transaction.".to_string(),
+            )
+        })?;
+
+        if account.status == AccountStatus::Closed {
+            return Err(ReverseTransactionError::AccountClosed(format!(
+                "Account {} is closed, cannot reverse transaction.",
+                account_id
+            )));
+        }
+
+        let original = self
+            .original
+            .as_ref()
+            .filter(|original| original.aggregate_id() == account_id)
+            .ok_or_else(|| {
+                ReverseTransactionError::TransactionNotFound(format!(
+                    "Account {} has no event {}.",
+                    account_id, self.sequence_number
+                ))
+            })?;
+
+        let amount = match original {
+            AccountEvent::Deposited(e) => e.amount,
+            AccountEvent::ConvertedDeposit(e) => e.amount,
+            AccountEvent::Withdrawn(e) => e.amount,
+            AccountEvent::HoldCaptured(e) => e.amount,
+            other => {
+                return Err(ReverseTransactionError::NotReversible(format!(
+                    "Event {} is a {} event, only deposits, withdrawals and captures can be \
+                     reversed.",
+                    self.sequence_number,
+                    other.event_type()
+                )));
+            }
+        };
+
+        if account.is_reversed(self.sequence_number) {
+            return Err(ReverseTransactionError::AlreadyReversed(format!(
+                "Event {} of account {} was already reversed.",
+                self.sequence_number, account_id
+            )));
+        }
+
+        Ok(vec![TransactionReversedEvent {
+            account_id,
+            reversed_sequence_number: self.sequence_number,
+            reversed_event_type: original.event_type().to_string(),
+            amount,
+            reason: self.reason.clone(),
+        }])
+    }
+}
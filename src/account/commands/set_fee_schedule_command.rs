@@ -0,0 +1,68 @@
+use thiserror::Error;
+
+use crate::{
+    account::{Account, AccountStatus, events::FeeScheduleChangedEvent},
+    fee::{FeeKind, FeeSchedule},
+    money::{AmountError, Currency, Money},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum SetFeeScheduleError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+    #[error("Currency mismatch: {0}")]
+    CurrencyMismatch(String),
+}
+
+/// Sets the fees charged to the account, in `currency`.
+pub struct SetFeeScheduleCommand {
+    pub schedule: FeeSchedule,
+    pub currency: Currency,
+}
+
+impl Command<Account, FeeScheduleChangedEvent, SetFeeScheduleError> for SetFeeScheduleCommand {
+    fn execute(
+        &self,
+        account: Account,
+    ) -> Result<Vec<FeeScheduleChangedEvent>, SetFeeScheduleError> {
+        for kind in [
+            FeeKind::MonthlyMaintenance,
+            FeeKind::Withdrawal,
+            FeeKind::Overdraft,
+            FeeKind::Fx,
+        ] {
+            Money::non_negative(self.schedule.fee(kind), self.currency)?;
+        }
+
+        let account_id = account.account_id.ok_or_else(|| {
+            SetFeeScheduleError::AccountNotOpened(
+                "Account ID is missing, cannot set fee schedule.".to_string(),
+            )
+        })?;
+
+        if account.status == AccountStatus::Closed {
+            return Err(SetFeeScheduleError::AccountClosed(format!(
+                "Account {} is closed, cannot set fee schedule.",
+                account_id
+            )));
+        }
+
+        if self.currency != account.currency {
+            return Err(SetFeeScheduleError::CurrencyMismatch(format!(
+                "Account {} is held in {}, cannot set a fee schedule in {}.",
+                account_id, account.currency, self.currency
+            )));
+        }
+
+        Ok(vec![FeeScheduleChangedEvent {
+            account_id,
+            schedule: self.schedule.clone(),
+            currency: self.currency,
+        }])
+    }
+}
@@ -0,0 +1,55 @@
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::{
+    account::{Account, AccountStatus, events::InterestRateChangedEvent},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum SetInterestRateError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Invalid interest rate: {0}")]
+    InvalidRate(String),
+}
+
+/// Sets the annual interest rate, as a fraction. A rate of zero stops the account earning
+/// interest.
+pub struct SetInterestRateCommand {
+    pub annual_rate: Decimal,
+}
+
+impl Command<Account, InterestRateChangedEvent, SetInterestRateError> for SetInterestRateCommand {
+    fn execute(
+        &self,
+        account: Account,
+    ) -> Result<Vec<InterestRateChangedEvent>, SetInterestRateError> {
+        let account_id = account.account_id.ok_or_else(|| {
+            SetInterestRateError::AccountNotOpened(
+                "Account ID is missing, cannot set interest rate.".to_string(),
+            )
+        })?;
+
+        if account.status == AccountStatus::Closed {
+            return Err(SetInterestRateError::AccountClosed(format!(
+                "Account {} is closed, cannot set interest rate.",
+                account_id
+            )));
+        }
+
+        if self.annual_rate < Decimal::ZERO || self.annual_rate >= Decimal::ONE {
+            return Err(SetInterestRateError::InvalidRate(format!(
+                "Annual rate must be at least 0 and below 1, got {}.",
+                self.annual_rate
+            )));
+        }
+
+        Ok(vec![InterestRateChangedEvent {
+            account_id,
+            annual_rate: self.annual_rate,
+        }])
+    }
+}
@@ -0,0 +1,59 @@
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::{
+    account::{Account, AccountStatus, events::OverdraftLimitChangedEvent},
+    money::{AmountError, Currency, Money},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum SetOverdraftLimitError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+    #[error("Currency mismatch: {0}")]
+    CurrencyMismatch(String),
+}
+
+/// Sets how far the account's balance may go below zero. A limit of zero removes the overdraft.
+pub struct SetOverdraftLimitCommand {
+    pub limit: Decimal,
+    pub currency: Currency,
+}
+
+impl Command<Account, OverdraftLimitChangedEvent, SetOverdraftLimitError>
+    for SetOverdraftLimitCommand
+{
+    fn execute(
+        &self,
+        account: Account,
+    ) -> Result<Vec<OverdraftLimitChangedEvent>, SetOverdraftLimitError> {
+        let limit = Money::non_negative(self.limit, self.currency)?;
+
+        let account_id = account.account_id.ok_or_else(|| {
+            SetOverdraftLimitError::AccountNotOpened(
+                "Account ID is missing, cannot set overdraft limit.".to_string(),
+            )
+        })?;
+
+        if account.status == AccountStatus::Closed {
+            return Err(SetOverdraftLimitError::AccountClosed(format!(
+                "Account {} is closed, cannot set overdraft limit.",
+                account_id
+            )));
+        }
+
+        if limit.currency() != account.currency {
+            return Err(SetOverdraftLimitError::CurrencyMismatch(format!(
+                "Account {} is held in {}, cannot set an overdraft limit of {}.",
+                account_id, account.currency, limit
+            )));
+        }
+
+        Ok(vec![OverdraftLimitChangedEvent { account_id, limit }])
+    }
+}
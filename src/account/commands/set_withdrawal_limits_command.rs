@@ -0,0 +1,72 @@
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::{
+    account::{Account, AccountStatus, events::WithdrawalLimitsChangedEvent},
+    money::{AmountError, Currency, Money},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum SetWithdrawalLimitsError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+    #[error("Currency mismatch: {0}")]
+    CurrencyMismatch(String),
+}
+
+/// Sets the largest single withdrawal and the largest total withdrawn over a rolling day,
+/// `None` lifts a limit.
+pub struct SetWithdrawalLimitsCommand {
+    pub per_transaction: Option<Decimal>,
+    pub daily: Option<Decimal>,
+    pub currency: Currency,
+}
+
+impl Command<Account, WithdrawalLimitsChangedEvent, SetWithdrawalLimitsError>
+    for SetWithdrawalLimitsCommand
+{
+    fn execute(
+        &self,
+        account: Account,
+    ) -> Result<Vec<WithdrawalLimitsChangedEvent>, SetWithdrawalLimitsError> {
+        let per_transaction = self
+            .per_transaction
+            .map(|limit| Money::new(limit, self.currency))
+            .transpose()?;
+        let daily = self
+            .daily
+            .map(|limit| Money::new(limit, self.currency))
+            .transpose()?;
+
+        let account_id = account.account_id.ok_or_else(|| {
+            SetWithdrawalLimitsError::AccountNotOpened(
+                "Account ID is missing, cannot set withdrawal limits.".to_string(),
+            )
+        })?;
+
+        if account.status == AccountStatus::Closed {
+            return Err(SetWithdrawalLimitsError::AccountClosed(format!(
+                "Account {} is closed, cannot set withdrawal limits.",
+                account_id
+            )));
+        }
+
+        if self.currency != account.currency {
+            return Err(SetWithdrawalLimitsError::CurrencyMismatch(format!(
+                "Account {} is held in {}, cannot set withdrawal limits in {}.",
+                account_id, account.currency, self.currency
+            )));
+        }
+
+        Ok(vec![WithdrawalLimitsChangedEvent {
+            account_id,
+            per_transaction,
+            daily,
+        }])
+    }
+}
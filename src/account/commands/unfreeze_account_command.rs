@@ -0,0 +1,40 @@
+use thiserror::Error;
+
+use crate::{
+    account::{Account, AccountStatus, events::AccountUnfrozenEvent},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum UnfreezeAccountError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account not frozen: {0}")]
+    AccountNotFrozen(String),
+}
+
+pub struct UnfreezeAccountCommand {
+    pub reason: String,
+}
+
+impl Command<Account, AccountUnfrozenEvent, UnfreezeAccountError> for UnfreezeAccountCommand {
+    fn execute(&self, account: Account) -> Result<Vec<AccountUnfrozenEvent>, UnfreezeAccountError> {
+        let account_id = account.account_id.ok_or_else(|| {
+            UnfreezeAccountError::AccountNotOpened(
+                "Account ID is missing, cannot unfreeze account.".to_string(),
+            )
+        })?;
+
+        if account.status != AccountStatus::Frozen {
+            return Err(UnfreezeAccountError::AccountNotFrozen(format!(
+                "Account {} is {}, only frozen accounts can be unfrozen.",
+                account_id, account.status
+            )));
+        }
+
+        Ok(vec![AccountUnfrozenEvent {
+            account_id,
+            reason: self.reason.clone(),
+        }])
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    account::{Account, AccountStatus, FEE_WAIVER_WINDOW, events::FeeWaivedEvent},
+    money::{AmountError, Money},
+    traits::Command,
+};
+
+#[derive(Debug, Error)]
+pub enum WaiveFeeError {
+    #[error("Account not opened or ID missing: {0}")]
+    AccountNotOpened(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Fee not found: {0}")]
+    FeeNotFound(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+}
+
+/// Refunds a fee charged within the [`FEE_WAIVER_WINDOW`].
+pub struct WaiveFeeCommand {
+    pub fee_id: Ulid,
+    pub reason: String,
+    pub at: DateTime<Utc>,
+}
+
+impl Command<Account, FeeWaivedEvent, WaiveFeeError> for WaiveFeeCommand {
+    fn execute(&self, account: Account) -> Result<Vec<FeeWaivedEvent>, WaiveFeeError> {
+        let account_id = account.account_id.ok_or_else(|| {
+            WaiveFeeError::AccountNotOpened("Account ID is missing, cannot waive fee.".to_string())
+        })?;
+
+        if account.status == AccountStatus::Closed {
+            return Err(WaiveFeeError::AccountClosed(format!(
+                "Account {} is closed, cannot waive fee.",
+                account_id
+            )));
+        }
+
+        let fee = account
+            .recent_fee(self.fee_id)
+            .filter(|fee| fee.charged_at > self.at - FEE_WAIVER_WINDOW)
+            .ok_or_else(|| {
+                WaiveFeeError::FeeNotFound(format!(
+                    "No fee {} was charged to account {} in the last {} days.",
+                    self.fee_id,
+                    account_id,
+                    FEE_WAIVER_WINDOW.num_days()
+                ))
+            })?;
+
+        Ok(vec![FeeWaivedEvent {
+            account_id,
+            fee_id: self.fee_id,
+            amount: Money::new(fee.amount, account.currency)?,
+            reason: self.reason.clone(),
+        }])
+    }
+}
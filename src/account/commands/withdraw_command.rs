@@ -1,8 +1,11 @@
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use thiserror::Error;
+use ulid::Ulid;
 
 use crate::{
-    account::{Account, events::WithdrawEvent},
+    account::{Account, AccountStatus, events::WithdrawEvent},
+    money::{AmountError, Currency, Money},
     traits::Command,
 };
 
@@ -12,27 +15,98 @@ pub enum WithdrawError {
     InsufficientBalance(String),
     #[error("Account not opened or ID missing: {0}")]
     AccountNotOpened(String),
+    #[error("Account closed: {0}")]
+    AccountClosed(String),
+    #[error("Account frozen: {0}")]
+    AccountFrozen(String),
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+    #[error("Currency mismatch: {0}")]
+    CurrencyMismatch(String),
+    #[error("Withdrawal of {requested} exceeds the per-transaction limit of {limit}")]
+    TransactionLimitExceeded { requested: Money, limit: Money },
+    #[error("Withdrawal of {requested} exceeds the daily limit, {remaining} remaining")]
+    DailyLimitExceeded { requested: Money, remaining: Money },
 }
 
 pub struct WithdrawCommand {
     pub amount: Decimal,
+    pub currency: Currency,
+    /// When the withdrawal is made, the daily limit covers the day before it.
+    pub at: DateTime<Utc>,
+    /// Transfer the withdrawal is the debit of. A transfer that already debited the account
+    /// is not withdrawn again, the command then produces no events.
+    pub transfer_id: Option<Ulid>,
 }
 
 impl Command<Account, WithdrawEvent, WithdrawError> for WithdrawCommand {
     fn execute(&self, state: Account) -> Result<Vec<WithdrawEvent>, WithdrawError> {
-        if (state.balance - self.amount) < Decimal::from(0) {
-            return Err(WithdrawError::InsufficientBalance(
-                "Cannot withdraw an amount greater than the current balance.".to_string(),
-            ));
+        let amount = Money::new(self.amount, self.currency)?;
+
+        let account_id = state.account_id.ok_or_else(|| {
+            WithdrawError::AccountNotOpened(
+                "Account ID is missing, cannot process withdrawal.".to_string(),
+            )
+        })?;
+
+        if self.transfer_id.is_some_and(|id| state.has_transfer(id)) {
+            return Ok(vec![]);
+        }
+
+        match state.status {
+            AccountStatus::Open => {}
+            AccountStatus::Frozen => {
+                return Err(WithdrawError::AccountFrozen(format!(
+                    "Account {} is frozen, cannot withdraw.",
+                    account_id
+                )));
+            }
+            AccountStatus::Closed => {
+                return Err(WithdrawError::AccountClosed(format!(
+                    "Account {} is closed, cannot withdraw.",
+                    account_id
+                )));
+            }
+        }
+
+        if amount.currency() != state.currency {
+            return Err(WithdrawError::CurrencyMismatch(format!(
+                "Account {} is held in {}, cannot withdraw {}.",
+                account_id, state.currency, amount
+            )));
+        }
+
+        let available = state.available_balance(self.at);
+        if amount.value() > available {
+            return Err(WithdrawError::InsufficientBalance(format!(
+                "Cannot withdraw {} from account {}, only {} {} is available.",
+                amount, account_id, available, state.currency
+            )));
+        }
+
+        if let Some(limit) = state.per_transaction_limit
+            && amount.value() > limit
+        {
+            return Err(WithdrawError::TransactionLimitExceeded {
+                requested: amount,
+                limit: Money::non_negative(limit, state.currency)?,
+            });
+        }
+
+        if let Some(limit) = state.daily_withdrawal_limit {
+            let remaining = (limit - state.withdrawn_in_day_before(self.at)).max(Decimal::ZERO);
+            if amount.value() > remaining {
+                return Err(WithdrawError::DailyLimitExceeded {
+                    requested: amount,
+                    remaining: Money::non_negative(remaining, state.currency)?,
+                });
+            }
         }
 
         Ok(vec![WithdrawEvent {
-            account_id: state.account_id.ok_or_else(|| {
-                WithdrawError::AccountNotOpened(
-                    "Account ID is missing, cannot process withdrawal.".to_string(),
-                )
-            })?,
-            amount: self.amount,
+            account_id,
+            amount,
+            transfer_id: self.transfer_id,
         }])
     }
 }
@@ -1,22 +1,94 @@
+pub mod account_closed_event;
+pub mod account_frozen_event;
 pub mod account_opened_event;
+pub mod account_unfrozen_event;
+pub mod converted_deposit_event;
 pub mod deposit_event;
+pub mod fee_charged_event;
+pub mod fee_schedule_changed_event;
+pub mod fee_waived_event;
+pub mod hold_captured_event;
+pub mod hold_placed_event;
+pub mod hold_released_event;
+pub mod interest_accrued_event;
+pub mod interest_posted_event;
+pub mod interest_rate_changed_event;
+pub mod overdraft_limit_changed_event;
+pub mod transaction_reversed_event;
 pub mod withdraw_event;
+pub mod withdrawal_limits_changed_event;
 
+pub use account_closed_event::AccountClosedEvent;
+pub use account_frozen_event::AccountFrozenEvent;
 pub use account_opened_event::AccountOpenedEvent;
+pub use account_unfrozen_event::AccountUnfrozenEvent;
+pub use converted_deposit_event::ConvertedDepositEvent;
 pub use deposit_event::DepositEvent;
+pub use fee_charged_event::FeeChargedEvent;
+pub use fee_schedule_changed_event::FeeScheduleChangedEvent;
+pub use fee_waived_event::FeeWaivedEvent;
+pub use hold_captured_event::HoldCapturedEvent;
+pub use hold_placed_event::HoldPlacedEvent;
+pub use hold_released_event::HoldReleasedEvent;
+pub use interest_accrued_event::InterestAccruedEvent;
+pub use interest_posted_event::InterestPostedEvent;
+pub use interest_rate_changed_event::InterestRateChangedEvent;
+pub use overdraft_limit_changed_event::OverdraftLimitChangedEvent;
 use serde::{Deserialize, Serialize};
+pub use transaction_reversed_event::TransactionReversedEvent;
 use ulid::Ulid;
 pub use withdraw_event::WithdrawEvent;
+pub use withdrawal_limits_changed_event::WithdrawalLimitsChangedEvent;
 
-use crate::{Account, traits::Event, traits::event::ApplyError};
+use crate::{
+    Account,
+    traits::Event,
+    traits::event::{ApplyError, EventMetadata},
+    traits::upcaster::UpcasterRegistry,
+};
 
-// Define this where you have your event types
+// Tagged with `EVENT_TYPE_TAG`, the variant names must match each event's `event_type()`
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
+#[serde(tag = "event_type")]
 pub enum AccountEvent {
+    #[serde(rename = "account_opened")]
     Opened(AccountOpenedEvent),
+    #[serde(rename = "deposit")]
     Deposited(DepositEvent),
+    #[serde(rename = "withdraw")]
     Withdrawn(WithdrawEvent),
+    #[serde(rename = "account_closed")]
+    Closed(AccountClosedEvent),
+    #[serde(rename = "account_frozen")]
+    Frozen(AccountFrozenEvent),
+    #[serde(rename = "account_unfrozen")]
+    Unfrozen(AccountUnfrozenEvent),
+    #[serde(rename = "converted_deposit")]
+    ConvertedDeposit(ConvertedDepositEvent),
+    #[serde(rename = "overdraft_limit_changed")]
+    OverdraftLimitChanged(OverdraftLimitChangedEvent),
+    #[serde(rename = "withdrawal_limits_changed")]
+    WithdrawalLimitsChanged(WithdrawalLimitsChangedEvent),
+    #[serde(rename = "hold_placed")]
+    HoldPlaced(HoldPlacedEvent),
+    #[serde(rename = "hold_captured")]
+    HoldCaptured(HoldCapturedEvent),
+    #[serde(rename = "hold_released")]
+    HoldReleased(HoldReleasedEvent),
+    #[serde(rename = "interest_rate_changed")]
+    InterestRateChanged(InterestRateChangedEvent),
+    #[serde(rename = "interest_accrued")]
+    InterestAccrued(InterestAccruedEvent),
+    #[serde(rename = "interest_posted")]
+    InterestPosted(InterestPostedEvent),
+    #[serde(rename = "fee_schedule_changed")]
+    FeeScheduleChanged(FeeScheduleChangedEvent),
+    #[serde(rename = "fee_charged")]
+    FeeCharged(FeeChargedEvent),
+    #[serde(rename = "fee_waived")]
+    FeeWaived(FeeWaivedEvent),
+    #[serde(rename = "transaction_reversed")]
+    TransactionReversed(TransactionReversedEvent),
 }
 
 pub const ACCOUNT_AGGREGATE_TYPE: &str = "account";
@@ -27,6 +99,33 @@ impl Event<Account> for AccountEvent {
             AccountEvent::Opened(e) => e.apply(state),
             AccountEvent::Deposited(e) => e.apply(state),
             AccountEvent::Withdrawn(e) => e.apply(state),
+            AccountEvent::Closed(e) => e.apply(state),
+            AccountEvent::Frozen(e) => e.apply(state),
+            AccountEvent::Unfrozen(e) => e.apply(state),
+            AccountEvent::ConvertedDeposit(e) => e.apply(state),
+            AccountEvent::OverdraftLimitChanged(e) => e.apply(state),
+            AccountEvent::WithdrawalLimitsChanged(e) => e.apply(state),
+            AccountEvent::HoldPlaced(e) => e.apply(state),
+            AccountEvent::HoldCaptured(e) => e.apply(state),
+            AccountEvent::HoldReleased(e) => e.apply(state),
+            AccountEvent::InterestRateChanged(e) => e.apply(state),
+            AccountEvent::InterestAccrued(e) => e.apply(state),
+            AccountEvent::InterestPosted(e) => e.apply(state),
+            AccountEvent::FeeScheduleChanged(e) => e.apply(state),
+            AccountEvent::FeeCharged(e) => e.apply(state),
+            AccountEvent::FeeWaived(e) => e.apply(state),
+            AccountEvent::TransactionReversed(e) => e.apply(state),
+        }
+    }
+
+    fn apply_recorded(
+        &self,
+        state: &mut Account,
+        metadata: &EventMetadata,
+    ) -> Result<(), ApplyError> {
+        match self {
+            AccountEvent::Withdrawn(e) => e.apply_recorded(state, metadata),
+            _ => self.apply(state),
         }
     }
 
@@ -35,6 +134,22 @@ impl Event<Account> for AccountEvent {
             AccountEvent::Opened(e) => e.aggregate_id(),
             AccountEvent::Deposited(e) => e.aggregate_id(),
             AccountEvent::Withdrawn(e) => e.aggregate_id(),
+            AccountEvent::Closed(e) => e.aggregate_id(),
+            AccountEvent::Frozen(e) => e.aggregate_id(),
+            AccountEvent::Unfrozen(e) => e.aggregate_id(),
+            AccountEvent::ConvertedDeposit(e) => e.aggregate_id(),
+            AccountEvent::OverdraftLimitChanged(e) => e.aggregate_id(),
+            AccountEvent::WithdrawalLimitsChanged(e) => e.aggregate_id(),
+            AccountEvent::HoldPlaced(e) => e.aggregate_id(),
+            AccountEvent::HoldCaptured(e) => e.aggregate_id(),
+            AccountEvent::HoldReleased(e) => e.aggregate_id(),
+            AccountEvent::InterestRateChanged(e) => e.aggregate_id(),
+            AccountEvent::InterestAccrued(e) => e.aggregate_id(),
+            AccountEvent::InterestPosted(e) => e.aggregate_id(),
+            AccountEvent::FeeScheduleChanged(e) => e.aggregate_id(),
+            AccountEvent::FeeCharged(e) => e.aggregate_id(),
+            AccountEvent::FeeWaived(e) => e.aggregate_id(),
+            AccountEvent::TransactionReversed(e) => e.aggregate_id(),
         }
     }
 
@@ -47,6 +162,69 @@ impl Event<Account> for AccountEvent {
             AccountEvent::Opened(e) => e.event_type(),
             AccountEvent::Deposited(e) => e.event_type(),
             AccountEvent::Withdrawn(e) => e.event_type(),
+            AccountEvent::Closed(e) => e.event_type(),
+            AccountEvent::Frozen(e) => e.event_type(),
+            AccountEvent::Unfrozen(e) => e.event_type(),
+            AccountEvent::ConvertedDeposit(e) => e.event_type(),
+            AccountEvent::OverdraftLimitChanged(e) => e.event_type(),
+            AccountEvent::WithdrawalLimitsChanged(e) => e.event_type(),
+            AccountEvent::HoldPlaced(e) => e.event_type(),
+            AccountEvent::HoldCaptured(e) => e.event_type(),
+            AccountEvent::HoldReleased(e) => e.event_type(),
+            AccountEvent::InterestRateChanged(e) => e.event_type(),
+            AccountEvent::InterestAccrued(e) => e.event_type(),
+            AccountEvent::InterestPosted(e) => e.event_type(),
+            AccountEvent::FeeScheduleChanged(e) => e.event_type(),
+            AccountEvent::FeeCharged(e) => e.event_type(),
+            AccountEvent::FeeWaived(e) => e.event_type(),
+            AccountEvent::TransactionReversed(e) => e.event_type(),
+        }
+    }
+
+    fn schema_version(&self) -> u32 {
+        match self {
+            AccountEvent::Opened(e) => e.schema_version(),
+            AccountEvent::Deposited(e) => e.schema_version(),
+            AccountEvent::Withdrawn(e) => e.schema_version(),
+            AccountEvent::Closed(e) => e.schema_version(),
+            AccountEvent::Frozen(e) => e.schema_version(),
+            AccountEvent::Unfrozen(e) => e.schema_version(),
+            AccountEvent::ConvertedDeposit(e) => e.schema_version(),
+            AccountEvent::OverdraftLimitChanged(e) => e.schema_version(),
+            AccountEvent::WithdrawalLimitsChanged(e) => e.schema_version(),
+            AccountEvent::HoldPlaced(e) => e.schema_version(),
+            AccountEvent::HoldCaptured(e) => e.schema_version(),
+            AccountEvent::HoldReleased(e) => e.schema_version(),
+            AccountEvent::InterestRateChanged(e) => e.schema_version(),
+            AccountEvent::InterestAccrued(e) => e.schema_version(),
+            AccountEvent::InterestPosted(e) => e.schema_version(),
+            AccountEvent::FeeScheduleChanged(e) => e.schema_version(),
+            AccountEvent::FeeCharged(e) => e.schema_version(),
+            AccountEvent::FeeWaived(e) => e.schema_version(),
+            AccountEvent::TransactionReversed(e) => e.schema_version(),
         }
     }
+
+    fn upcasters() -> UpcasterRegistry {
+        UpcasterRegistry::new()
+            .merge(AccountOpenedEvent::upcasters())
+            .merge(DepositEvent::upcasters())
+            .merge(WithdrawEvent::upcasters())
+            .merge(AccountClosedEvent::upcasters())
+            .merge(AccountFrozenEvent::upcasters())
+            .merge(AccountUnfrozenEvent::upcasters())
+            .merge(ConvertedDepositEvent::upcasters())
+            .merge(OverdraftLimitChangedEvent::upcasters())
+            .merge(WithdrawalLimitsChangedEvent::upcasters())
+            .merge(HoldPlacedEvent::upcasters())
+            .merge(HoldCapturedEvent::upcasters())
+            .merge(HoldReleasedEvent::upcasters())
+            .merge(InterestRateChangedEvent::upcasters())
+            .merge(InterestAccruedEvent::upcasters())
+            .merge(InterestPostedEvent::upcasters())
+            .merge(FeeScheduleChangedEvent::upcasters())
+            .merge(FeeChargedEvent::upcasters())
+            .merge(FeeWaivedEvent::upcasters())
+            .merge(TransactionReversedEvent::upcasters())
+    }
 }
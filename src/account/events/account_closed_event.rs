@@ -0,0 +1,39 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{
+    account::{Account, AccountStatus},
+    traits::Event,
+    traits::event::ApplyError,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountClosedEvent {
+    pub account_id: Ulid,
+}
+
+impl Event<Account> for AccountClosedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        if state.balance != Decimal::from(0) {
+            return Err(ApplyError::InvariantViolated(
+                "Account balance must be zero to close".to_string(),
+            ));
+        }
+        state.status = AccountStatus::Closed;
+
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "account_closed"
+    }
+}
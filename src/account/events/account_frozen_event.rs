@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{
+    account::{Account, AccountStatus},
+    traits::Event,
+    traits::event::ApplyError,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountFrozenEvent {
+    pub account_id: Ulid,
+    pub reason: String,
+}
+
+impl Event<Account> for AccountFrozenEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        state.status = AccountStatus::Frozen;
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "account_frozen"
+    }
+}
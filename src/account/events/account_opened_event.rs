@@ -1,20 +1,39 @@
-use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use ulid::Ulid;
 
-use crate::{account::Account, traits::Event, traits::event::ApplyError};
+use crate::{
+    account::Account,
+    money::{Money, add_default_currency},
+    traits::Event,
+    traits::event::ApplyError,
+    traits::upcaster::UpcasterRegistry,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountOpenedEvent {
     pub account_id: Ulid,
-    pub balance: Decimal,
+    /// Opening balance, its currency is the currency the account is held in.
+    #[serde(flatten)]
+    pub balance: Money,
+}
+
+/// Version 1 stored the opening balance as `balance`, without a currency.
+fn v1_to_v2(mut event: Value) -> Value {
+    if let Some(object) = event.as_object_mut()
+        && let Some(balance) = object.remove("balance")
+    {
+        object.insert("amount".to_string(), balance);
+    }
+    add_default_currency(event)
 }
 
 impl Event<Account> for AccountOpenedEvent {
     fn apply(&self, account: &mut Account) -> Result<(), ApplyError> {
         account.account_id = Some(self.account_id);
-        account.balance = self.balance;
+        account.balance = self.balance.value();
+        account.currency = self.balance.currency();
         Ok(())
     }
 
@@ -29,4 +48,12 @@ impl Event<Account> for AccountOpenedEvent {
     fn event_type(&self) -> &str {
         "account_opened"
     }
+
+    fn schema_version(&self) -> u32 {
+        2
+    }
+
+    fn upcasters() -> UpcasterRegistry {
+        UpcasterRegistry::new().register("account_opened", 1, v1_to_v2)
+    }
 }
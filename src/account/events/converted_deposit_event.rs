@@ -0,0 +1,53 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{account::Account, money::Money, traits::Event, traits::event::ApplyError};
+
+/// A deposit received in a foreign currency and converted into the account currency. Records
+/// the rate it was converted at, so replaying it never depends on the rates of the day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConvertedDepositEvent {
+    pub account_id: Ulid,
+    /// Amount received, in the currency it was sent in.
+    pub source: Money,
+    /// Mid-market rate from the source currency into the account currency.
+    pub rate: Decimal,
+    /// Fraction of the rate kept by the bank.
+    pub spread: Decimal,
+    /// What the spread cost, in the account currency.
+    pub fee: Money,
+    /// Amount credited, in the account currency.
+    #[serde(flatten)]
+    pub amount: Money,
+    /// Transfer the deposit credited the account for, `None` for a direct deposit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer_id: Option<Ulid>,
+}
+
+impl Event<Account> for ConvertedDepositEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        if self.amount.currency() != state.currency {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Cannot credit {} to an account held in {}",
+                self.amount, state.currency
+            )));
+        }
+
+        state.record_transfer(self.transfer_id)?;
+        state.balance += self.amount.value();
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "converted_deposit"
+    }
+}
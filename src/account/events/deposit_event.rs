@@ -1,18 +1,35 @@
-use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use ulid::Ulid;
 
-use crate::{account::Account, traits::Event, traits::event::ApplyError};
+use crate::{
+    account::Account,
+    money::{Money, add_default_currency},
+    traits::Event,
+    traits::event::ApplyError,
+    traits::upcaster::UpcasterRegistry,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DepositEvent {
     pub account_id: Ulid,
-    pub amount: Decimal,
+    #[serde(flatten)]
+    pub amount: Money,
+    /// Transfer the deposit credited the account for, `None` for a direct deposit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer_id: Option<Ulid>,
 }
 
 impl Event<Account> for DepositEvent {
     fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
-        let new_balance = state.balance + self.amount;
+        if self.amount.currency() != state.currency {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Cannot deposit {} into an account held in {}",
+                self.amount, state.currency
+            )));
+        }
+
+        state.record_transfer(self.transfer_id)?;
+        let new_balance = state.balance + self.amount.value();
         state.balance = new_balance;
         Ok(())
     }
@@ -28,4 +45,12 @@ impl Event<Account> for DepositEvent {
     fn event_type(&self) -> &str {
         "deposit"
     }
+
+    fn schema_version(&self) -> u32 {
+        2
+    }
+
+    fn upcasters() -> UpcasterRegistry {
+        UpcasterRegistry::new().register("deposit", 1, add_default_currency)
+    }
 }
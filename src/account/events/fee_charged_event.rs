@@ -0,0 +1,70 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{
+    account::{Account, ChargedFee},
+    fee::FeeKind,
+    money::Money,
+    traits::Event,
+    traits::event::ApplyError,
+};
+
+/// A fee was taken from the balance. Fees may overdraw the account beyond its overdraft limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeChargedEvent {
+    pub account_id: Ulid,
+    pub fee_id: Ulid,
+    pub kind: FeeKind,
+    #[serde(flatten)]
+    pub amount: Money,
+    pub charged_at: DateTime<Utc>,
+    /// Month end a monthly maintenance fee was charged for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub period: Option<NaiveDate>,
+}
+
+impl Event<Account> for FeeChargedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        if self.amount.currency() != state.currency {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Cannot charge a fee of {} to an account held in {}",
+                self.amount, state.currency
+            )));
+        }
+
+        if let Some(period) = self.period {
+            if state
+                .maintenance_charged_through
+                .is_some_and(|through| period <= through)
+            {
+                return Err(ApplyError::InvariantViolated(format!(
+                    "Monthly fee for {} was already charged",
+                    period
+                )));
+            }
+            state.maintenance_charged_through = Some(period);
+        }
+
+        state.balance -= self.amount.value();
+        state.record_fee(ChargedFee {
+            fee_id: self.fee_id,
+            kind: self.kind,
+            amount: self.amount.value(),
+            charged_at: self.charged_at,
+        });
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "fee_charged"
+    }
+}
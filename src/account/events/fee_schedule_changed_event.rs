@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{
+    account::Account, fee::FeeSchedule, money::Currency, traits::Event, traits::event::ApplyError,
+};
+
+/// The fee schedule was set. Its fees are charged from then on, those already charged stay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeScheduleChangedEvent {
+    pub account_id: Ulid,
+    pub schedule: FeeSchedule,
+    /// Currency the fees of the schedule are in.
+    pub currency: Currency,
+}
+
+impl Event<Account> for FeeScheduleChangedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        if self.currency != state.currency {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Cannot set a fee schedule in {} on an account held in {}",
+                self.currency, state.currency
+            )));
+        }
+
+        state.fee_schedule = Some(self.schedule.clone());
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "fee_schedule_changed"
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{account::Account, money::Money, traits::Event, traits::event::ApplyError};
+
+/// A fee was refunded to the balance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeWaivedEvent {
+    pub account_id: Ulid,
+    pub fee_id: Ulid,
+    #[serde(flatten)]
+    pub amount: Money,
+    pub reason: String,
+}
+
+impl Event<Account> for FeeWaivedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        if state
+            .recent_fee(self.fee_id)
+            .is_none_or(|fee| fee.amount != self.amount.value())
+        {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Fee {} of {} cannot be waived",
+                self.fee_id, self.amount
+            )));
+        }
+
+        state.balance += self.amount.value();
+        state.recent_fees.retain(|fee| fee.fee_id != self.fee_id);
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "fee_waived"
+    }
+}
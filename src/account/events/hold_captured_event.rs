@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{account::Account, money::Money, traits::Event, traits::event::ApplyError};
+
+/// A hold was settled: `amount`, at most the amount held, left the account and the rest of the
+/// hold was released.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldCapturedEvent {
+    pub account_id: Ulid,
+    pub hold_id: Ulid,
+    #[serde(flatten)]
+    pub amount: Money,
+    pub captured_at: DateTime<Utc>,
+}
+
+impl Event<Account> for HoldCapturedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        let hold = state.hold(self.hold_id).ok_or_else(|| {
+            ApplyError::InvariantViolated(format!("Hold {} is not placed", self.hold_id))
+        })?;
+
+        if self.amount.currency() != state.currency || self.amount.value() > hold.amount {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Cannot capture {} from a hold of {} {}",
+                self.amount, hold.amount, state.currency
+            )));
+        }
+
+        // The funds were reserved when the hold was placed, no balance check is due here
+        state.holds.retain(|hold| hold.hold_id != self.hold_id);
+        state.balance -= self.amount.value();
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "hold_captured"
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{
+    account::{Account, Hold},
+    money::Money,
+    traits::Event,
+    traits::event::ApplyError,
+};
+
+/// Funds were reserved until `expires_at`, leaving the balance untouched until the hold is
+/// captured.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldPlacedEvent {
+    pub account_id: Ulid,
+    pub hold_id: Ulid,
+    #[serde(flatten)]
+    pub amount: Money,
+    pub placed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Event<Account> for HoldPlacedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        if self.amount.currency() != state.currency {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Cannot hold {} on an account held in {}",
+                self.amount, state.currency
+            )));
+        }
+
+        if state.hold(self.hold_id).is_some() {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Hold {} already placed",
+                self.hold_id
+            )));
+        }
+
+        // Holds that expired before this one was placed reserve nothing anymore
+        state.holds.retain(|hold| hold.is_open_at(self.placed_at));
+        state.holds.push(Hold {
+            hold_id: self.hold_id,
+            amount: self.amount.value(),
+            placed_at: self.placed_at,
+            expires_at: self.expires_at,
+        });
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "hold_placed"
+    }
+}
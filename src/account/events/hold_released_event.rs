@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{account::Account, traits::Event, traits::event::ApplyError};
+
+/// A hold was lifted without taking any funds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldReleasedEvent {
+    pub account_id: Ulid,
+    pub hold_id: Ulid,
+    pub reason: String,
+}
+
+impl Event<Account> for HoldReleasedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        if state.hold(self.hold_id).is_none() {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Hold {} is not placed",
+                self.hold_id
+            )));
+        }
+
+        state.holds.retain(|hold| hold.hold_id != self.hold_id);
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "hold_released"
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{account::Account, interest::DayCount, traits::Event, traits::event::ApplyError};
+
+/// A day's interest was accrued. Records the closing balance, rate and convention it was
+/// computed from, the interest only reaches the balance when it is posted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestAccruedEvent {
+    pub account_id: Ulid,
+    pub day: NaiveDate,
+    pub balance: Decimal,
+    pub annual_rate: Decimal,
+    pub day_count: DayCount,
+    pub amount: Decimal,
+}
+
+impl Event<Account> for InterestAccruedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        if state
+            .interest_accrued_through
+            .is_some_and(|through| self.day <= through)
+        {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Interest for {} was already accrued",
+                self.day
+            )));
+        }
+
+        state.accrued_interest += self.amount;
+        state.interest_accrued_through = Some(self.day);
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "interest_accrued"
+    }
+}
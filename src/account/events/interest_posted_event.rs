@@ -0,0 +1,53 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{account::Account, money::Money, traits::Event, traits::event::ApplyError};
+
+/// Accrued interest, rounded to the currency, was credited at a month end. What rounding left
+/// over stays accrued for the next month.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestPostedEvent {
+    pub account_id: Ulid,
+    pub posted_on: NaiveDate,
+    #[serde(flatten)]
+    pub amount: Money,
+}
+
+impl Event<Account> for InterestPostedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        if self.amount.currency() != state.currency {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Cannot post {} of interest to an account held in {}",
+                self.amount, state.currency
+            )));
+        }
+
+        if state
+            .interest_posted_through
+            .is_some_and(|through| self.posted_on <= through)
+        {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Interest for {} was already posted",
+                self.posted_on
+            )));
+        }
+
+        state.balance += self.amount.value();
+        state.accrued_interest -= self.amount.value();
+        state.interest_posted_through = Some(self.posted_on);
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "interest_posted"
+    }
+}
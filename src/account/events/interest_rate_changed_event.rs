@@ -0,0 +1,31 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{account::Account, traits::Event, traits::event::ApplyError};
+
+/// The account's annual interest rate was set, it applies from the day it was set on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestRateChangedEvent {
+    pub account_id: Ulid,
+    pub annual_rate: Decimal,
+}
+
+impl Event<Account> for InterestRateChangedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        state.interest_rate = self.annual_rate;
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "interest_rate_changed"
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{account::Account, money::Money, traits::Event, traits::event::ApplyError};
+
+/// The overdraft limit was set. It applies to withdrawals made after it, an account already
+/// overdrawn beyond a lowered limit keeps its balance but cannot withdraw any further.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverdraftLimitChangedEvent {
+    pub account_id: Ulid,
+    pub limit: Money,
+}
+
+impl Event<Account> for OverdraftLimitChangedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        if self.limit.currency() != state.currency {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Cannot set an overdraft limit of {} on an account held in {}",
+                self.limit, state.currency
+            )));
+        }
+
+        state.overdraft_limit = self.limit.value();
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "overdraft_limit_changed"
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{
+    account::{Account, Reversal},
+    money::Money,
+    traits::Event,
+    traits::event::ApplyError,
+};
+
+/// A deposit, withdrawal or capture was undone: its amount left the account again if it was
+/// credited, or returned to it if it was debited. Fees charged with it are not reversed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionReversedEvent {
+    pub account_id: Ulid,
+    /// Sequence number of the reversed event.
+    pub reversed_sequence_number: Ulid,
+    /// Type of the reversed event, deciding whether the amount is debited or credited.
+    pub reversed_event_type: String,
+    #[serde(flatten)]
+    pub amount: Money,
+    pub reason: String,
+}
+
+impl TransactionReversedEvent {
+    /// Whether the reversed event credited the account, so its reversal debits it.
+    pub fn reverses_credit(event_type: &str) -> Option<bool> {
+        match event_type {
+            "deposit" | "converted_deposit" => Some(true),
+            "withdraw" | "hold_captured" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl Event<Account> for TransactionReversedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        if self.amount.currency() != state.currency {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Cannot reverse {} on an account held in {}",
+                self.amount, state.currency
+            )));
+        }
+
+        if state.is_reversed(self.reversed_sequence_number) {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Transaction {} was already reversed",
+                self.reversed_sequence_number
+            )));
+        }
+
+        match Self::reverses_credit(&self.reversed_event_type) {
+            Some(true) => state.balance -= self.amount.value(),
+            Some(false) => state.balance += self.amount.value(),
+            None => {
+                return Err(ApplyError::InvariantViolated(format!(
+                    "Events of type {} cannot be reversed",
+                    self.reversed_event_type
+                )));
+            }
+        }
+
+        state.reversals.push(Reversal {
+            reversed_sequence_number: self.reversed_sequence_number,
+            event_type: self.reversed_event_type.clone(),
+            amount: self.amount.value(),
+            reason: self.reason.clone(),
+        });
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "transaction_reversed"
+    }
+}
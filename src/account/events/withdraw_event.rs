@@ -1,26 +1,53 @@
-use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use ulid::Ulid;
 
-use crate::{account::Account, traits::Event, traits::event::ApplyError};
+use crate::{
+    account::Account,
+    money::{Money, add_default_currency},
+    traits::Event,
+    traits::event::{ApplyError, EventMetadata},
+    traits::upcaster::UpcasterRegistry,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WithdrawEvent {
     pub account_id: Ulid,
-    pub amount: Decimal,
+    #[serde(flatten)]
+    pub amount: Money,
+    /// Transfer the withdrawal debited the account for, `None` for a direct withdrawal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer_id: Option<Ulid>,
 }
 
 impl Event<Account> for WithdrawEvent {
     fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
-        let new_balance = state.balance - self.amount;
+        if self.amount.currency() != state.currency {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Cannot withdraw {} from an account held in {}",
+                self.amount, state.currency
+            )));
+        }
 
-        if new_balance < Decimal::from(0) {
+        // Against the overdraft limit in force when the withdrawal was made
+        if self.amount.value() > state.balance_with_overdraft() {
             return Err(ApplyError::InvariantViolated(
                 "Insufficient balance".to_string(),
             ));
         }
-        state.balance = new_balance;
+        state.record_transfer(self.transfer_id)?;
+        state.balance -= self.amount.value();
+
+        Ok(())
+    }
 
+    /// Counts the withdrawal towards the daily limit from when it was recorded.
+    fn apply_recorded(
+        &self,
+        state: &mut Account,
+        metadata: &EventMetadata,
+    ) -> Result<(), ApplyError> {
+        self.apply(state)?;
+        state.record_withdrawal(metadata.recorded_at, self.amount.value());
         Ok(())
     }
 
@@ -35,4 +62,12 @@ impl Event<Account> for WithdrawEvent {
     fn event_type(&self) -> &str {
         "withdraw"
     }
+
+    fn schema_version(&self) -> u32 {
+        2
+    }
+
+    fn upcasters() -> UpcasterRegistry {
+        UpcasterRegistry::new().register("withdraw", 1, add_default_currency)
+    }
 }
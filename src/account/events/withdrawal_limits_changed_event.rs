@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{account::Account, money::Money, traits::Event, traits::event::ApplyError};
+
+/// The withdrawal limits were set, replacing the previous ones. A missing limit means none.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithdrawalLimitsChangedEvent {
+    pub account_id: Ulid,
+    pub per_transaction: Option<Money>,
+    pub daily: Option<Money>,
+}
+
+impl Event<Account> for WithdrawalLimitsChangedEvent {
+    fn apply(&self, state: &mut Account) -> Result<(), ApplyError> {
+        for limit in self.per_transaction.iter().chain(self.daily.iter()) {
+            if limit.currency() != state.currency {
+                return Err(ApplyError::InvariantViolated(format!(
+                    "Cannot set a withdrawal limit of {} on an account held in {}",
+                    limit, state.currency
+                )));
+            }
+        }
+
+        state.per_transaction_limit = self.per_transaction.map(|limit| limit.value());
+        state.daily_withdrawal_limit = self.daily.map(|limit| limit.value());
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.account_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "account"
+    }
+
+    fn event_type(&self) -> &str {
+        "withdrawal_limits_changed"
+    }
+}
@@ -1,3 +1,5 @@
+pub mod account_repository_in_memory;
 pub mod account_repository_sqlite;
 
+pub use account_repository_in_memory::AccountRepositoryInMemory;
 pub use account_repository_sqlite::AccountRepositorySqlite;
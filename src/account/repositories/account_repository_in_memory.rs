@@ -0,0 +1,170 @@
+use crate::{
+    account::{
+        Account,
+        transactions::{Transaction, TransactionPage, TransactionQuery},
+    },
+    traits::{Projection, Repository, TransactionRepository, repository::RepositoryError},
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+use ulid::Ulid;
+
+/// Account projection kept in process memory, for tests and single-process development.
+/// Clones share the same underlying projection.
+#[derive(Debug, Clone, Default)]
+pub struct AccountRepositoryInMemory {
+    accounts: Arc<Mutex<HashMap<Ulid, Account>>>,
+    /// Transactions of every account, ordered by sequence number.
+    transactions: Arc<Mutex<BTreeMap<Ulid, Transaction>>>,
+    checkpoint: Arc<Mutex<Option<Ulid>>>,
+}
+
+impl AccountRepositoryInMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn save_checkpoint_value(&self, checkpoint: Option<Ulid>) -> Result<(), RepositoryError> {
+        *self
+            .checkpoint
+            .lock()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))? = checkpoint;
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<Ulid, Account>>, RepositoryError> {
+        self.accounts
+            .lock()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))
+    }
+
+    fn lock_transactions(
+        &self,
+    ) -> Result<MutexGuard<'_, BTreeMap<Ulid, Transaction>>, RepositoryError> {
+        self.transactions
+            .lock()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))
+    }
+}
+
+impl Repository<Account> for AccountRepositoryInMemory {
+    fn create(&self, aggregate: Account) -> Result<(), RepositoryError> {
+        let account_id = aggregate
+            .account_id
+            .ok_or(RepositoryError::RepositoryError(
+                "Account ID is required".to_string(),
+            ))?;
+
+        let mut accounts = self.lock()?;
+        if accounts.contains_key(&account_id) {
+            return Err(RepositoryError::RepositoryError(format!(
+                "Account {} already exists",
+                account_id
+            )));
+        }
+        accounts.insert(account_id, aggregate);
+
+        Ok(())
+    }
+
+    fn update(&self, aggregate: Account) -> Result<(), RepositoryError> {
+        let account_id = aggregate
+            .account_id
+            .ok_or(RepositoryError::RepositoryError(
+                "Account ID is required".to_string(),
+            ))?;
+
+        // Mirrors the SQL UPDATE, which silently ignores unknown accounts
+        if let Some(account) = self.lock()?.get_mut(&account_id) {
+            *account = aggregate;
+        }
+
+        Ok(())
+    }
+
+    fn delete(&self, id: Ulid) -> Result<(), RepositoryError> {
+        self.lock()?.remove(&id);
+        self.lock_transactions()?
+            .retain(|_, transaction| transaction.account_id != id);
+        Ok(())
+    }
+
+    fn get(&self, id: Ulid) -> Result<Account, RepositoryError> {
+        self.lock()?
+            .get(&id)
+            .cloned()
+            .ok_or(RepositoryError::NotFound(id))
+    }
+}
+
+impl TransactionRepository for AccountRepositoryInMemory {
+    fn record_transaction(&self, transaction: Transaction) -> Result<(), RepositoryError> {
+        let mut transactions = self.lock_transactions()?;
+        if transactions.contains_key(&transaction.sequence_number) {
+            return Ok(());
+        }
+
+        if let Some(reversed) = transaction
+            .reversal_of
+            .and_then(|sequence_number| transactions.get_mut(&sequence_number))
+        {
+            reversed.reversed_by = Some(transaction.sequence_number);
+        }
+        transactions.insert(transaction.sequence_number, transaction);
+
+        Ok(())
+    }
+
+    fn get_last_transaction(
+        &self,
+        account_id: Ulid,
+    ) -> Result<Option<Transaction>, RepositoryError> {
+        Ok(self
+            .lock_transactions()?
+            .values()
+            .rev()
+            .find(|transaction| transaction.account_id == account_id)
+            .cloned())
+    }
+
+    fn get_transactions(
+        &self,
+        account_id: Ulid,
+        query: &TransactionQuery,
+    ) -> Result<TransactionPage, RepositoryError> {
+        let transactions = self
+            .lock_transactions()?
+            .values()
+            .filter(|transaction| {
+                transaction.account_id == account_id
+                    && query
+                        .after
+                        .is_none_or(|after| transaction.sequence_number > after)
+                    && query.matches(transaction)
+            })
+            .take(query.limit + 1)
+            .cloned()
+            .collect();
+
+        Ok(TransactionPage::new(transactions, query.limit))
+    }
+}
+
+impl Projection for AccountRepositoryInMemory {
+    fn reset(&self) -> Result<(), RepositoryError> {
+        self.lock()?.clear();
+        self.lock_transactions()?.clear();
+        self.save_checkpoint_value(None)
+    }
+
+    fn get_checkpoint(&self) -> Result<Option<Ulid>, RepositoryError> {
+        self.checkpoint
+            .lock()
+            .map(|checkpoint| *checkpoint)
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))
+    }
+
+    fn save_checkpoint(&self, sequence_number: Ulid) -> Result<(), RepositoryError> {
+        self.save_checkpoint_value(Some(sequence_number))
+    }
+}
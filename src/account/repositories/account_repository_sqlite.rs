@@ -1,11 +1,76 @@
 use crate::{
-    account::Account,
-    traits::{Repository, repository::RepositoryError},
+    account::{
+        Account, Hold, Reversal,
+        transactions::{Transaction, TransactionPage, TransactionQuery},
+    },
+    money::CurrencyError,
+    traits::{Projection, Repository, TransactionRepository, repository::RepositoryError},
 };
+use chrono::{DateTime, SecondsFormat, Utc};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::named_params;
+use rusqlite::{OptionalExtension, named_params};
 use std::str::FromStr;
+use ulid::Ulid;
+
+/// Name the accounts projection's checkpoint is stored under.
+const PROJECTION_NAME: &str = "accounts";
+
+const CREATE_ACCOUNTS_TABLE: &str = "CREATE TABLE IF NOT EXISTS accounts (
+    account_id TEXT PRIMARY KEY NOT NULL,
+    balance TEXT NOT NULL,
+    currency TEXT NOT NULL DEFAULT 'EUR',
+    status TEXT NOT NULL DEFAULT 'open',
+    overdraft_limit TEXT NOT NULL DEFAULT '0',
+    per_transaction_limit TEXT,
+    daily_withdrawal_limit TEXT,
+    interest_rate TEXT NOT NULL DEFAULT '0',
+    accrued_interest TEXT NOT NULL DEFAULT '0',
+    fee_schedule TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);";
+
+/// Open holds of the accounts, rewritten with every update of their account.
+const CREATE_ACCOUNT_HOLDS_TABLE: &str = "CREATE TABLE IF NOT EXISTS account_holds (
+    hold_id TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    placed_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS account_holds_account_id ON account_holds (account_id);";
+
+/// Transactions reversed on the accounts, linked to the reversed event by its sequence number.
+const CREATE_ACCOUNT_REVERSALS_TABLE: &str = "CREATE TABLE IF NOT EXISTS account_reversals (
+    reversed_sequence_number TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    event_type TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    reason TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS account_reversals_account_id ON account_reversals (account_id);";
+
+/// Transactions of the accounts, recorded once per event that changed a balance. Recording times
+/// are stored in one fixed format so they compare as text.
+const CREATE_ACCOUNT_TRANSACTIONS_TABLE: &str = "CREATE TABLE IF NOT EXISTS account_transactions (
+    sequence_number TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    balance TEXT NOT NULL,
+    description TEXT NOT NULL,
+    recorded_at TEXT NOT NULL,
+    reversal_of TEXT,
+    reversed_by TEXT
+);
+CREATE INDEX IF NOT EXISTS account_transactions_account_id
+    ON account_transactions (account_id, sequence_number);";
+
+const TRANSACTION_COLUMNS: &str = "sequence_number, account_id, kind, amount, currency, balance,
+    description, recorded_at, reversal_of, reversed_by";
 
 #[derive(Debug, Clone)]
 pub struct AccountRepositorySqlite {
@@ -19,17 +84,298 @@ impl AccountRepositorySqlite {
 
         // Apply migrations
         let conn = pool.get().expect("Failed to get connection");
+        conn.execute_batch(CREATE_ACCOUNTS_TABLE)
+            .expect("Failed to create accounts table");
+        conn.execute_batch(CREATE_ACCOUNT_HOLDS_TABLE)
+            .expect("Failed to create account holds table");
+        conn.execute_batch(CREATE_ACCOUNT_REVERSALS_TABLE)
+            .expect("Failed to create account reversals table");
+        let has_transactions: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master
+                WHERE type = 'table' AND name = 'account_transactions'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("Failed to inspect projection tables");
+        conn.execute_batch(CREATE_ACCOUNT_TRANSACTIONS_TABLE)
+            .expect("Failed to create account transactions table");
+
+        // Projections created before accounts had a lifecycle, a currency, an overdraft,
+        // withdrawal limits, interest or fees lack those columns, every account in them is still
+        // open, held in the default currency, cannot be overdrawn, has no withdrawal limits, earns
+        // no interest and pays no fees
+        for (column, definition) in [
+            ("status", "TEXT NOT NULL DEFAULT 'open'"),
+            ("currency", "TEXT NOT NULL DEFAULT 'EUR'"),
+            ("overdraft_limit", "TEXT NOT NULL DEFAULT '0'"),
+            ("per_transaction_limit", "TEXT"),
+            ("daily_withdrawal_limit", "TEXT"),
+            ("interest_rate", "TEXT NOT NULL DEFAULT '0'"),
+            ("accrued_interest", "TEXT NOT NULL DEFAULT '0'"),
+            ("fee_schedule", "TEXT"),
+        ] {
+            let has_column: bool = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM pragma_table_info('accounts') WHERE name = :name",
+                    named_params! { ":name": column },
+                    |row| row.get(0),
+                )
+                .expect("Failed to inspect accounts table");
+
+            if !has_column {
+                conn.execute_batch(&format!(
+                    "ALTER TABLE accounts ADD COLUMN {column} {definition};"
+                ))
+                .expect("Failed to add column to accounts table");
+            }
+        }
+
         conn.execute_batch(
-            "CREATE TABLE IF NOT EXISTS accounts (
-                account_id TEXT PRIMARY KEY NOT NULL,
-                balance TEXT NOT NULL,
-                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+            "CREATE TABLE IF NOT EXISTS projection_checkpoints (
+                projection TEXT PRIMARY KEY NOT NULL,
+                sequence_number TEXT NOT NULL,
                 updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
             );",
         )
-        .expect("Failed to create accounts table");
+        .expect("Failed to create projection checkpoints table");
+
+        let repository = Self { pool };
+
+        // Running balances of transactions continue from the previous transaction, so a
+        // projection created before transactions were recorded is replayed from the start
+        if !has_transactions {
+            repository
+                .reset()
+                .expect("Failed to reset accounts projection");
+        }
+
+        repository
+    }
+
+    fn save_holds(conn: &rusqlite::Connection, account: &Account) -> Result<(), RepositoryError> {
+        let account_id = account.account_id.ok_or(RepositoryError::RepositoryError(
+            "Account ID is required".to_string(),
+        ))?;
+
+        conn.execute(
+            "DELETE FROM account_holds WHERE account_id = :account_id",
+            named_params! { ":account_id": account_id.to_string() },
+        )
+        .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        for hold in &account.holds {
+            conn.execute(
+                "INSERT INTO account_holds (hold_id, account_id, amount, placed_at, expires_at)
+                VALUES (:hold_id, :account_id, :amount, :placed_at, :expires_at)",
+                named_params! {
+                    ":hold_id": hold.hold_id.to_string(),
+                    ":account_id": account_id.to_string(),
+                    ":amount": hold.amount.to_string(),
+                    ":placed_at": hold.placed_at.to_rfc3339(),
+                    ":expires_at": hold.expires_at.to_rfc3339(),
+                },
+            )
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Reversals are never undone, only the ones the projection has not stored yet are added.
+    fn save_reversals(
+        conn: &rusqlite::Connection,
+        account: &Account,
+    ) -> Result<(), RepositoryError> {
+        let account_id = account.account_id.ok_or(RepositoryError::RepositoryError(
+            "Account ID is required".to_string(),
+        ))?;
+
+        for (position, reversal) in account.reversals.iter().enumerate() {
+            conn.execute(
+                "INSERT OR IGNORE INTO account_reversals
+                    (reversed_sequence_number, account_id, position, event_type, amount, reason)
+                VALUES (:reversed_sequence_number, :account_id, :position, :event_type, :amount,
+                    :reason)",
+                named_params! {
+                    ":reversed_sequence_number": reversal.reversed_sequence_number.to_string(),
+                    ":account_id": account_id.to_string(),
+                    ":position": position as i64,
+                    ":event_type": reversal.event_type,
+                    ":amount": reversal.amount.to_string(),
+                    ":reason": reversal.reason,
+                },
+            )
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    fn get_reversals(
+        conn: &rusqlite::Connection,
+        id: Ulid,
+    ) -> Result<Vec<Reversal>, RepositoryError> {
+        let mut statement = conn
+            .prepare(
+                "SELECT reversed_sequence_number, event_type, amount, reason
+                FROM account_reversals
+                WHERE account_id = :account_id
+                ORDER BY position",
+            )
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        let rows = statement
+            .query_map(named_params! { ":account_id": id.to_string() }, |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        rows.map(|row| {
+            let (sequence_number, event_type, amount, reason) =
+                row.map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+            let invalid = |e: &dyn std::fmt::Display| {
+                RepositoryError::RepositoryError(format!(
+                    "Invalid reversal of {}: {}",
+                    sequence_number, e
+                ))
+            };
+
+            Ok(Reversal {
+                reversed_sequence_number: Ulid::from_string(&sequence_number)
+                    .map_err(|e| invalid(&e))?,
+                event_type,
+                amount: rust_decimal::Decimal::from_str(&amount).map_err(|e| invalid(&e))?,
+                reason,
+            })
+        })
+        .collect()
+    }
+
+    fn get_transaction_rows(
+        conn: &rusqlite::Connection,
+        condition: &str,
+        params: &[(&str, &dyn rusqlite::ToSql)],
+    ) -> Result<Vec<Transaction>, RepositoryError> {
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT {TRANSACTION_COLUMNS} FROM account_transactions WHERE {condition}"
+            ))
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        let rows = statement
+            .query_map(params, |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, String>(6)?,
+                    row.get::<_, String>(7)?,
+                    row.get::<_, Option<String>>(8)?,
+                    row.get::<_, Option<String>>(9)?,
+                ))
+            })
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        rows.map(|row| {
+            let (
+                sequence_number,
+                account_id,
+                kind,
+                amount,
+                currency,
+                balance,
+                description,
+                recorded_at,
+                reversal_of,
+                reversed_by,
+            ) = row.map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+            let invalid = |e: &dyn std::fmt::Display| {
+                RepositoryError::RepositoryError(format!(
+                    "Invalid transaction {}: {}",
+                    sequence_number, e
+                ))
+            };
+            let ulid = |s: Option<String>| {
+                s.map(|s| Ulid::from_string(&s))
+                    .transpose()
+                    .map_err(|e| invalid(&e))
+            };
+
+            Ok(Transaction {
+                sequence_number: Ulid::from_string(&sequence_number).map_err(|e| invalid(&e))?,
+                account_id: Ulid::from_string(&account_id).map_err(|e| invalid(&e))?,
+                kind: kind.parse().map_err(|e: String| invalid(&e))?,
+                amount: rust_decimal::Decimal::from_str(&amount).map_err(|e| invalid(&e))?,
+                currency: currency.parse().map_err(|e: CurrencyError| invalid(&e))?,
+                balance: rust_decimal::Decimal::from_str(&balance).map_err(|e| invalid(&e))?,
+                description,
+                recorded_at: DateTime::parse_from_rfc3339(&recorded_at)
+                    .map_err(|e| invalid(&e))?
+                    .with_timezone(&Utc),
+                reversal_of: ulid(reversal_of)?,
+                reversed_by: ulid(reversed_by)?,
+            })
+        })
+        .collect()
+    }
+
+    fn fee_schedule_json(account: &Account) -> Result<Option<String>, RepositoryError> {
+        account
+            .fee_schedule
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))
+    }
+
+    fn get_holds(conn: &rusqlite::Connection, id: Ulid) -> Result<Vec<Hold>, RepositoryError> {
+        let mut statement = conn
+            .prepare(
+                "SELECT hold_id, amount, placed_at, expires_at FROM account_holds
+                WHERE account_id = :account_id
+                ORDER BY placed_at",
+            )
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        let rows = statement
+            .query_map(named_params! { ":account_id": id.to_string() }, |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
 
-        Self { pool }
+        rows.map(|row| {
+            let (hold_id, amount, placed_at, expires_at) =
+                row.map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+            let invalid = |e: &dyn std::fmt::Display| {
+                RepositoryError::RepositoryError(format!("Invalid hold {}: {}", hold_id, e))
+            };
+
+            Ok(Hold {
+                hold_id: Ulid::from_string(&hold_id).map_err(|e| invalid(&e))?,
+                amount: rust_decimal::Decimal::from_str(&amount).map_err(|e| invalid(&e))?,
+                placed_at: DateTime::parse_from_rfc3339(&placed_at)
+                    .map_err(|e| invalid(&e))?
+                    .with_timezone(&Utc),
+                expires_at: DateTime::parse_from_rfc3339(&expires_at)
+                    .map_err(|e| invalid(&e))?
+                    .with_timezone(&Utc),
+            })
+        })
+        .collect()
     }
 }
 
@@ -47,15 +393,32 @@ impl Repository<Account> for AccountRepositorySqlite {
             .get()
             .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
         let mut statement = conn
-            .prepare("INSERT INTO accounts (account_id, balance) VALUES (:account_id, :balance)")
+            .prepare(
+                "INSERT INTO accounts (account_id, balance, currency, status, overdraft_limit,
+                    per_transaction_limit, daily_withdrawal_limit, interest_rate, accrued_interest,
+                    fee_schedule)
+                VALUES (:account_id, :balance, :currency, :status, :overdraft_limit,
+                    :per_transaction_limit, :daily_withdrawal_limit, :interest_rate,
+                    :accrued_interest, :fee_schedule)",
+            )
             .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
 
         statement
             .execute(named_params! {
                 ":account_id": account_id.to_string(),
                 ":balance": balance.to_string(),
+                ":currency": aggregate.currency.code(),
+                ":status": aggregate.status.as_str(),
+                ":overdraft_limit": aggregate.overdraft_limit.to_string(),
+                ":per_transaction_limit": aggregate.per_transaction_limit.map(|l| l.to_string()),
+                ":daily_withdrawal_limit": aggregate.daily_withdrawal_limit.map(|l| l.to_string()),
+                ":interest_rate": aggregate.interest_rate.to_string(),
+                ":accrued_interest": aggregate.accrued_interest.to_string(),
+                ":fee_schedule": Self::fee_schedule_json(&aggregate)?,
             })
             .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        Self::save_holds(&conn, &aggregate)?;
+        Self::save_reversals(&conn, &aggregate)?;
 
         println!("Account created in projection: {:?}", account_id);
 
@@ -75,19 +438,36 @@ impl Repository<Account> for AccountRepositorySqlite {
             .get()
             .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
         let mut statement = conn
-            .prepare("UPDATE accounts SET balance = :balance WHERE account_id = :account_id")
+            .prepare(
+                "UPDATE accounts
+                SET balance = :balance, status = :status, overdraft_limit = :overdraft_limit,
+                    per_transaction_limit = :per_transaction_limit,
+                    daily_withdrawal_limit = :daily_withdrawal_limit,
+                    interest_rate = :interest_rate, accrued_interest = :accrued_interest,
+                    fee_schedule = :fee_schedule, updated_at = CURRENT_TIMESTAMP
+                WHERE account_id = :account_id",
+            )
             .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
 
         statement
             .execute(named_params! {
                 ":account_id": account_id.to_string(),
                 ":balance": balance.to_string(),
+                ":status": aggregate.status.as_str(),
+                ":overdraft_limit": aggregate.overdraft_limit.to_string(),
+                ":per_transaction_limit": aggregate.per_transaction_limit.map(|l| l.to_string()),
+                ":daily_withdrawal_limit": aggregate.daily_withdrawal_limit.map(|l| l.to_string()),
+                ":interest_rate": aggregate.interest_rate.to_string(),
+                ":accrued_interest": aggregate.accrued_interest.to_string(),
+                ":fee_schedule": Self::fee_schedule_json(&aggregate)?,
             })
             .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        Self::save_holds(&conn, &aggregate)?;
+        Self::save_reversals(&conn, &aggregate)?;
 
         println!(
-            "Account ID {:?} balance updated in projection: {:?}",
-            account_id, balance
+            "Account ID {:?} updated in projection: balance {:?}, status {}, overdraft limit {}",
+            account_id, balance, aggregate.status, aggregate.overdraft_limit
         );
 
         Ok(())
@@ -107,6 +487,21 @@ impl Repository<Account> for AccountRepositorySqlite {
                 ":account_id": id.to_string(),
             })
             .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM account_holds WHERE account_id = :account_id",
+            named_params! { ":account_id": id.to_string() },
+        )
+        .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM account_reversals WHERE account_id = :account_id",
+            named_params! { ":account_id": id.to_string() },
+        )
+        .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        conn.execute(
+            "DELETE FROM account_transactions WHERE account_id = :account_id",
+            named_params! { ":account_id": id.to_string() },
+        )
+        .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
 
         println!("Account ID {:?} deleted from projection", id);
 
@@ -119,7 +514,13 @@ impl Repository<Account> for AccountRepositorySqlite {
             .get()
             .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
         let mut statement = conn
-            .prepare("SELECT account_id, balance FROM accounts WHERE account_id = :account_id")
+            .prepare(
+                "SELECT account_id, balance, status, currency, overdraft_limit,
+                    per_transaction_limit, daily_withdrawal_limit, interest_rate, accrued_interest,
+                    fee_schedule
+                FROM accounts
+                WHERE account_id = :account_id",
+            )
             .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
 
         let account = statement
@@ -154,14 +555,294 @@ impl Repository<Account> for AccountRepositorySqlite {
                         })
                         .expect("Failed to parse Decimal");
 
+                    let status = row.get::<_, String>(2)?.parse().map_err(|e: String| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            2,
+                            rusqlite::types::Type::Text,
+                            e.into(),
+                        )
+                    })?;
+
+                    let currency =
+                        row.get::<_, String>(3)?
+                            .parse()
+                            .map_err(|e: CurrencyError| {
+                                rusqlite::Error::FromSqlConversionFailure(
+                                    3,
+                                    rusqlite::types::Type::Text,
+                                    Box::new(e),
+                                )
+                            })?;
+
+                    let overdraft_limit = row.get::<_, String>(4).and_then(|s| {
+                        rust_decimal::Decimal::from_str(&s).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                4,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })
+                    })?;
+
+                    let decimal = |index: usize| {
+                        row.get::<_, Option<String>>(index)?
+                            .map(|s| {
+                                rust_decimal::Decimal::from_str(&s).map_err(|e| {
+                                    rusqlite::Error::FromSqlConversionFailure(
+                                        index,
+                                        rusqlite::types::Type::Text,
+                                        Box::new(e),
+                                    )
+                                })
+                            })
+                            .transpose()
+                    };
+
+                    let fee_schedule = row
+                        .get::<_, Option<String>>(9)?
+                        .map(|s| {
+                            serde_json::from_str(&s).map_err(|e| {
+                                rusqlite::Error::FromSqlConversionFailure(
+                                    9,
+                                    rusqlite::types::Type::Text,
+                                    Box::new(e),
+                                )
+                            })
+                        })
+                        .transpose()?;
+
+                    // Recent withdrawals and fees, the days interest was accrued, posted and
+                    // fees charged through, and the transfers applied only matter to commands,
+                    // which load the account from the event store
                     Ok(Account {
                         account_id: Some(account_id),
                         balance,
+                        currency,
+                        status,
+                        overdraft_limit,
+                        per_transaction_limit: decimal(5)?,
+                        daily_withdrawal_limit: decimal(6)?,
+                        recent_withdrawals: Vec::new(),
+                        interest_rate: decimal(7)?.unwrap_or_default(),
+                        accrued_interest: decimal(8)?.unwrap_or_default(),
+                        interest_accrued_through: None,
+                        interest_posted_through: None,
+                        fee_schedule,
+                        maintenance_charged_through: None,
+                        recent_fees: Vec::new(),
+                        reversals: Vec::new(),
+                        holds: Vec::new(),
+                        transfers: Default::default(),
                     })
                 },
             )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => RepositoryError::NotFound(id),
+                e => RepositoryError::RepositoryError(e.to_string()),
+            })?;
+
+        Ok(Account {
+            holds: Self::get_holds(&conn, id)?,
+            reversals: Self::get_reversals(&conn, id)?,
+            ..account
+        })
+    }
+}
+
+impl TransactionRepository for AccountRepositorySqlite {
+    fn record_transaction(&self, transaction: Transaction) -> Result<(), RepositoryError> {
+        let mut conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        let db_transaction = conn
+            .transaction()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        let inserted = db_transaction
+            .execute(
+                &format!(
+                    "INSERT OR IGNORE INTO account_transactions ({TRANSACTION_COLUMNS})
+                    VALUES (:sequence_number, :account_id, :kind, :amount, :currency, :balance,
+                        :description, :recorded_at, :reversal_of, NULL)"
+                ),
+                named_params! {
+                    ":sequence_number": transaction.sequence_number.to_string(),
+                    ":account_id": transaction.account_id.to_string(),
+                    ":kind": transaction.kind.as_str(),
+                    ":amount": transaction.amount.to_string(),
+                    ":currency": transaction.currency.code(),
+                    ":balance": transaction.balance.to_string(),
+                    ":description": transaction.description,
+                    ":recorded_at": transaction
+                        .recorded_at
+                        .to_rfc3339_opts(SecondsFormat::Micros, true),
+                    ":reversal_of": transaction.reversal_of.map(|id| id.to_string()),
+                },
+            )
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        if inserted > 0
+            && let Some(reversal_of) = transaction.reversal_of
+        {
+            db_transaction
+                .execute(
+                    "UPDATE account_transactions SET reversed_by = :reversed_by
+                    WHERE sequence_number = :sequence_number",
+                    named_params! {
+                        ":reversed_by": transaction.sequence_number.to_string(),
+                        ":sequence_number": reversal_of.to_string(),
+                    },
+                )
+                .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        }
+
+        db_transaction
+            .commit()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn get_last_transaction(
+        &self,
+        account_id: Ulid,
+    ) -> Result<Option<Transaction>, RepositoryError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        Ok(Self::get_transaction_rows(
+            &conn,
+            "account_id = :account_id ORDER BY sequence_number DESC LIMIT 1",
+            named_params! { ":account_id": account_id.to_string() },
+        )?
+        .pop())
+    }
+
+    fn get_transactions(
+        &self,
+        account_id: Ulid,
+        query: &TransactionQuery,
+    ) -> Result<TransactionPage, RepositoryError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        // Types are written into the statement, they are a fixed set of identifiers
+        let kinds = if query.kinds.is_empty() {
+            String::new()
+        } else {
+            let kinds: Vec<String> = query
+                .kinds
+                .iter()
+                .map(|kind| format!("'{}'", kind.as_str()))
+                .collect();
+            format!("AND kind IN ({})", kinds.join(", "))
+        };
+        let timestamp = |at: Option<DateTime<Utc>>| {
+            at.map(|at| at.to_rfc3339_opts(SecondsFormat::Micros, true))
+        };
+
+        let transactions = Self::get_transaction_rows(
+            &conn,
+            &format!(
+                "account_id = :account_id
+                AND (:after IS NULL OR sequence_number > :after)
+                AND (:from IS NULL OR recorded_at >= :from)
+                AND (:to IS NULL OR recorded_at < :to)
+                {kinds}
+                ORDER BY sequence_number
+                LIMIT :limit"
+            ),
+            named_params! {
+                ":account_id": account_id.to_string(),
+                ":after": query.after.map(|after| after.to_string()),
+                ":from": timestamp(query.from),
+                ":to": timestamp(query.to),
+                ":limit": (query.limit + 1) as i64,
+            },
+        )?;
+
+        Ok(TransactionPage::new(transactions, query.limit))
+    }
+}
+
+impl Projection for AccountRepositorySqlite {
+    fn reset(&self) -> Result<(), RepositoryError> {
+        let mut conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        let transaction = conn
+            .transaction()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        transaction
+            .execute_batch(&format!(
+                "DROP TABLE IF EXISTS accounts; {CREATE_ACCOUNTS_TABLE}
+                DROP TABLE IF EXISTS account_holds; {CREATE_ACCOUNT_HOLDS_TABLE}
+                DROP TABLE IF EXISTS account_reversals; {CREATE_ACCOUNT_REVERSALS_TABLE}
+                DROP TABLE IF EXISTS account_transactions; {CREATE_ACCOUNT_TRANSACTIONS_TABLE}"
+            ))
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        transaction
+            .execute(
+                "DELETE FROM projection_checkpoints WHERE projection = :projection",
+                named_params! { ":projection": PROJECTION_NAME },
+            )
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        transaction
+            .commit()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        println!("Accounts projection reset");
+
+        Ok(())
+    }
+
+    fn get_checkpoint(&self) -> Result<Option<Ulid>, RepositoryError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        let checkpoint = conn
+            .query_row(
+                "SELECT sequence_number FROM projection_checkpoints WHERE projection = :projection",
+                named_params! { ":projection": PROJECTION_NAME },
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
             .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
 
-        Ok(account)
+        checkpoint
+            .map(|s| Ulid::from_string(&s))
+            .transpose()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))
+    }
+
+    fn save_checkpoint(&self, sequence_number: Ulid) -> Result<(), RepositoryError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        conn.execute(
+            "INSERT INTO projection_checkpoints (projection, sequence_number)
+             VALUES (:projection, :sequence_number)
+             ON CONFLICT (projection) DO UPDATE
+             SET sequence_number = excluded.sequence_number, updated_at = CURRENT_TIMESTAMP",
+            named_params! {
+                ":projection": PROJECTION_NAME,
+                ":sequence_number": sequence_number.to_string(),
+            },
+        )
+        .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        Ok(())
     }
 }
@@ -0,0 +1,189 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::money::Currency;
+
+/// Transactions returned per page unless a query asks for fewer.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+/// Most transactions returned per page, whatever a query asks for.
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// What moved money in or out of an account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionKind {
+    /// The balance the account was opened with.
+    Opening,
+    Deposit,
+    Withdrawal,
+    /// A deposit converted from another currency.
+    ConvertedDeposit,
+    /// Funds taken by capturing a hold.
+    HoldCapture,
+    /// Accrued interest posted to the balance.
+    Interest,
+    Fee,
+    FeeWaiver,
+    /// A compensating transaction undoing an earlier one.
+    Reversal,
+}
+
+impl TransactionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionKind::Opening => "opening",
+            TransactionKind::Deposit => "deposit",
+            TransactionKind::Withdrawal => "withdrawal",
+            TransactionKind::ConvertedDeposit => "converted_deposit",
+            TransactionKind::HoldCapture => "hold_capture",
+            TransactionKind::Interest => "interest",
+            TransactionKind::Fee => "fee",
+            TransactionKind::FeeWaiver => "fee_waiver",
+            TransactionKind::Reversal => "reversal",
+        }
+    }
+}
+
+impl fmt::Display for TransactionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TransactionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "opening" => Ok(TransactionKind::Opening),
+            "deposit" => Ok(TransactionKind::Deposit),
+            "withdrawal" => Ok(TransactionKind::Withdrawal),
+            "converted_deposit" => Ok(TransactionKind::ConvertedDeposit),
+            "hold_capture" => Ok(TransactionKind::HoldCapture),
+            "interest" => Ok(TransactionKind::Interest),
+            "fee" => Ok(TransactionKind::Fee),
+            "fee_waiver" => Ok(TransactionKind::FeeWaiver),
+            "reversal" => Ok(TransactionKind::Reversal),
+            other => Err(format!("Unknown transaction type: {}", other)),
+        }
+    }
+}
+
+/// An event that changed an account's balance, as listed on a statement.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    /// Sequence number of the event, which also orders an account's transactions.
+    pub sequence_number: Ulid,
+    pub account_id: Ulid,
+    #[serde(rename = "type")]
+    pub kind: TransactionKind,
+    /// Positive when the account was credited, negative when it was debited.
+    pub amount: Decimal,
+    pub currency: Currency,
+    /// Balance right after the transaction.
+    pub balance: Decimal,
+    pub description: String,
+    pub recorded_at: DateTime<Utc>,
+    /// Sequence number of the transaction a reversal undid.
+    pub reversal_of: Option<Ulid>,
+    /// Sequence number of the reversal that undid the transaction.
+    pub reversed_by: Option<Ulid>,
+}
+
+/// Filters and page of an account's transactions. Transactions come oldest first, a page
+/// continues after the sequence number the previous one ended with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionQuery {
+    /// Earliest recording time, inclusive.
+    pub from: Option<DateTime<Utc>>,
+    /// Latest recording time, exclusive.
+    pub to: Option<DateTime<Utc>>,
+    /// Types to list, all of them when empty.
+    pub kinds: Vec<TransactionKind>,
+    pub after: Option<Ulid>,
+    pub limit: usize,
+}
+
+impl Default for TransactionQuery {
+    fn default() -> Self {
+        Self {
+            from: None,
+            to: None,
+            kinds: Vec::new(),
+            after: None,
+            limit: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+impl TransactionQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_from(mut self, from: DateTime<Utc>) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    pub fn with_to(mut self, to: DateTime<Utc>) -> Self {
+        self.to = Some(to);
+        self
+    }
+
+    pub fn with_kind(mut self, kind: TransactionKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+
+    pub fn with_after(mut self, after: Ulid) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    /// Limits the page size, to at least one and at most [`MAX_PAGE_SIZE`] transactions.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = limit.clamp(1, MAX_PAGE_SIZE);
+        self
+    }
+
+    /// Whether a transaction passes the filters, regardless of the page.
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        self.from.is_none_or(|from| transaction.recorded_at >= from)
+            && self.to.is_none_or(|to| transaction.recorded_at < to)
+            && (self.kinds.is_empty() || self.kinds.contains(&transaction.kind))
+    }
+}
+
+/// A page of transactions, with the cursor of the next page if there is one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TransactionPage {
+    pub transactions: Vec<Transaction>,
+    /// Pass as `after` to get the next page.
+    pub next_after: Option<Ulid>,
+}
+
+impl TransactionPage {
+    /// Pages transactions read with one more than the limit, the extra one telling that
+    /// another page follows.
+    pub fn new(mut transactions: Vec<Transaction>, limit: usize) -> Self {
+        let next_after = if transactions.len() > limit {
+            transactions.truncate(limit);
+            transactions
+                .last()
+                .map(|transaction| transaction.sequence_number)
+        } else {
+            None
+        };
+
+        Self {
+            transactions,
+            next_after,
+        }
+    }
+}
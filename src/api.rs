@@ -0,0 +1,1031 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::account::commands::{
+    CaptureHoldError, ChargeFeeError, CloseAccountError, ConvertedDepositError, DepositError,
+    FreezeAccountError, OpenAccountError, PlaceHoldError, ReleaseHoldError,
+    ReverseTransactionError, SetFeeScheduleError, SetInterestRateError, SetOverdraftLimitError,
+    SetWithdrawalLimitsError, UnfreezeAccountError, WaiveFeeError, WithdrawError,
+};
+use crate::account::transactions::TransactionQuery;
+use crate::account::{
+    Account, AccountService, AsOf, DEFAULT_HOLD_EXPIRY, account_service::AccountServiceError,
+};
+use crate::fee::{FeeKind, FeeSchedule};
+use crate::money::Currency;
+use crate::traits::{
+    EventStore, Repository, SnapshotStore, TransactionRepository,
+    event::{ApplyError, EventMetadata},
+    event_store::EventStoreError,
+    fx_rate_provider::FxRateError,
+    repository::RepositoryError,
+};
+
+/// Request headers recorded as event metadata.
+const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+const REQUEST_ID_HEADER: &str = "x-request-id";
+const ACTOR_HEADER: &str = "x-actor";
+/// Headers starting with this prefix are stored on the events with the prefix removed.
+const METADATA_HEADER_PREFIX: &str = "x-meta-";
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("No route for {0}")]
+    RouteNotFound(String),
+    #[error("Method {0} is not allowed on this resource")]
+    MethodNotAllowed(String),
+    #[error("{0}")]
+    AccountServiceError(#[from] AccountServiceError),
+}
+
+impl ApiError {
+    pub fn status(&self) -> u16 {
+        match self {
+            ApiError::BadRequest(_) => 400,
+            ApiError::RouteNotFound(_) => 404,
+            ApiError::MethodNotAllowed(_) => 405,
+            ApiError::AccountServiceError(e) => match e {
+                AccountServiceError::RepositoryError(RepositoryError::NotFound(_))
+                | AccountServiceError::DepositError(DepositError::AccountIdMissing(_))
+                | AccountServiceError::ConvertedDepositError(
+                    ConvertedDepositError::AccountIdMissing(_),
+                )
+                | AccountServiceError::WithdrawError(WithdrawError::AccountNotOpened(_))
+                | AccountServiceError::CloseAccountError(CloseAccountError::AccountNotOpened(_))
+                | AccountServiceError::FreezeAccountError(FreezeAccountError::AccountNotOpened(
+                    _,
+                ))
+                | AccountServiceError::UnfreezeAccountError(
+                    UnfreezeAccountError::AccountNotOpened(_),
+                )
+                | AccountServiceError::SetOverdraftLimitError(
+                    SetOverdraftLimitError::AccountNotOpened(_),
+                )
+                | AccountServiceError::SetWithdrawalLimitsError(
+                    SetWithdrawalLimitsError::AccountNotOpened(_),
+                )
+                | AccountServiceError::SetInterestRateError(
+                    SetInterestRateError::AccountNotOpened(_),
+                )
+                | AccountServiceError::SetFeeScheduleError(
+                    SetFeeScheduleError::AccountNotOpened(_),
+                )
+                | AccountServiceError::ChargeFeeError(ChargeFeeError::AccountNotOpened(_))
+                | AccountServiceError::ReverseTransactionError(
+                    ReverseTransactionError::AccountNotOpened(_)
+                    | ReverseTransactionError::TransactionNotFound(_),
+                )
+                | AccountServiceError::WaiveFeeError(
+                    WaiveFeeError::AccountNotOpened(_) | WaiveFeeError::FeeNotFound(_),
+                )
+                | AccountServiceError::PlaceHoldError(PlaceHoldError::AccountNotOpened(_))
+                | AccountServiceError::CaptureHoldError(
+                    CaptureHoldError::AccountNotOpened(_) | CaptureHoldError::HoldNotFound(_),
+                )
+                | AccountServiceError::ReleaseHoldError(
+                    ReleaseHoldError::AccountNotOpened(_) | ReleaseHoldError::HoldNotFound(_),
+                ) => 404,
+                AccountServiceError::EventStoreError(EventStoreError::ConcurrencyConflict {
+                    ..
+                })
+                | AccountServiceError::DepositError(
+                    DepositError::AccountClosed(_) | DepositError::AccountFrozen(_),
+                )
+                | AccountServiceError::WithdrawError(
+                    WithdrawError::AccountClosed(_) | WithdrawError::AccountFrozen(_),
+                )
+                | AccountServiceError::ConvertedDepositError(
+                    ConvertedDepositError::AccountClosed(_)
+                    | ConvertedDepositError::AccountFrozen(_),
+                )
+                | AccountServiceError::CloseAccountError(CloseAccountError::AccountClosed(_))
+                | AccountServiceError::FreezeAccountError(
+                    FreezeAccountError::AccountClosed(_) | FreezeAccountError::AccountFrozen(_),
+                )
+                | AccountServiceError::UnfreezeAccountError(
+                    UnfreezeAccountError::AccountNotFrozen(_),
+                )
+                | AccountServiceError::SetOverdraftLimitError(
+                    SetOverdraftLimitError::AccountClosed(_),
+                )
+                | AccountServiceError::SetWithdrawalLimitsError(
+                    SetWithdrawalLimitsError::AccountClosed(_),
+                )
+                | AccountServiceError::SetInterestRateError(SetInterestRateError::AccountClosed(
+                    _,
+                ))
+                | AccountServiceError::SetFeeScheduleError(SetFeeScheduleError::AccountClosed(_))
+                | AccountServiceError::ChargeFeeError(ChargeFeeError::AccountClosed(_))
+                | AccountServiceError::WaiveFeeError(WaiveFeeError::AccountClosed(_))
+                | AccountServiceError::ReverseTransactionError(
+                    ReverseTransactionError::AccountClosed(_)
+                    | ReverseTransactionError::AlreadyReversed(_),
+                )
+                | AccountServiceError::PlaceHoldError(
+                    PlaceHoldError::AccountClosed(_) | PlaceHoldError::AccountFrozen(_),
+                )
+                | AccountServiceError::CaptureHoldError(
+                    CaptureHoldError::AccountClosed(_)
+                    | CaptureHoldError::AccountFrozen(_)
+                    | CaptureHoldError::HoldExpired(_),
+                ) => 409,
+                AccountServiceError::WithdrawError(
+                    WithdrawError::InsufficientBalance(_)
+                    | WithdrawError::InvalidAmount(_)
+                    | WithdrawError::CurrencyMismatch(_)
+                    | WithdrawError::TransactionLimitExceeded { .. }
+                    | WithdrawError::DailyLimitExceeded { .. },
+                )
+                | AccountServiceError::DepositError(
+                    DepositError::InvalidAmount(_) | DepositError::CurrencyMismatch(_),
+                )
+                | AccountServiceError::ConvertedDepositError(
+                    ConvertedDepositError::InvalidAmount(_) | ConvertedDepositError::InvalidRate(_),
+                )
+                | AccountServiceError::SetOverdraftLimitError(
+                    SetOverdraftLimitError::InvalidAmount(_)
+                    | SetOverdraftLimitError::CurrencyMismatch(_),
+                )
+                | AccountServiceError::SetWithdrawalLimitsError(
+                    SetWithdrawalLimitsError::InvalidAmount(_)
+                    | SetWithdrawalLimitsError::CurrencyMismatch(_),
+                )
+                | AccountServiceError::PlaceHoldError(
+                    PlaceHoldError::InvalidAmount(_)
+                    | PlaceHoldError::CurrencyMismatch(_)
+                    | PlaceHoldError::InsufficientBalance(_)
+                    | PlaceHoldError::InvalidExpiry(_),
+                )
+                | AccountServiceError::CaptureHoldError(
+                    CaptureHoldError::InvalidAmount(_) | CaptureHoldError::ExceedsHold(_),
+                )
+                | AccountServiceError::SetInterestRateError(SetInterestRateError::InvalidRate(_))
+                | AccountServiceError::SetFeeScheduleError(
+                    SetFeeScheduleError::InvalidAmount(_)
+                    | SetFeeScheduleError::CurrencyMismatch(_),
+                )
+                | AccountServiceError::ChargeFeeError(
+                    ChargeFeeError::InvalidAmount(_) | ChargeFeeError::CurrencyMismatch(_),
+                )
+                | AccountServiceError::ReverseTransactionError(
+                    ReverseTransactionError::NotReversible(_),
+                )
+                | AccountServiceError::FxRateError(FxRateError::RateNotFound { .. })
+                | AccountServiceError::OpenAccountError(OpenAccountError::InvalidAmount(_))
+                | AccountServiceError::CloseAccountError(
+                    CloseAccountError::NonZeroBalance(_)
+                    | CloseAccountError::OpenHolds(_)
+                    | CloseAccountError::AccruedInterest(_),
+                )
+                | AccountServiceError::ApplyError(
+                    ApplyError::Validation(_) | ApplyError::InvariantViolated(_),
+                ) => 422,
+                _ => 500,
+            },
+        }
+    }
+}
+
+/// Response produced by the API, independent of the HTTP server carrying it.
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    fn json<T: Serialize>(status: u16, body: &T) -> Self {
+        match serde_json::to_string(body) {
+            Ok(body) => Self {
+                status,
+                content_type: "application/json",
+                body,
+            },
+            Err(e) => Self::problem(500, &e.to_string()),
+        }
+    }
+
+    fn no_content() -> Self {
+        Self {
+            status: 204,
+            content_type: "application/json",
+            body: String::new(),
+        }
+    }
+
+    /// Error response following RFC 9457 (problem details for HTTP APIs).
+    fn problem(status: u16, detail: &str) -> Self {
+        Self {
+            status,
+            content_type: "application/problem+json",
+            body: json!({
+                "type": "about:blank",
+                "title": reason_phrase(status),
+                "status": status,
+                "detail": detail,
+            })
+            .to_string(),
+        }
+    }
+}
+
+impl From<ApiError> for Response {
+    fn from(error: ApiError) -> Self {
+        let status = error.status();
+        if status >= 500 {
+            eprintln!("Error handling request: {}", error);
+        }
+        Self::problem(status, &error.to_string())
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        422 => "Unprocessable Entity",
+        _ => "Internal Server Error",
+    }
+}
+
+/// An account as returned by the API, along with what is left of its balance once its open
+/// holds are deducted.
+#[derive(Debug, Serialize)]
+struct AccountResponse {
+    #[serde(flatten)]
+    account: Account,
+    available_balance: Decimal,
+}
+
+impl AccountResponse {
+    fn at(account: Account, at: DateTime<Utc>) -> Self {
+        Self {
+            available_balance: account.available_balance(at),
+            account,
+        }
+    }
+}
+
+impl From<Account> for AccountResponse {
+    fn from(account: Account) -> Self {
+        Self::at(account, Utc::now())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAccountRequest {
+    #[serde(default)]
+    balance: Decimal,
+    currency: Currency,
+}
+
+#[derive(Debug, Deserialize)]
+struct AmountRequest {
+    amount: Decimal,
+    currency: Currency,
+}
+
+#[derive(Debug, Deserialize)]
+struct OverdraftRequest {
+    limit: Decimal,
+    currency: Currency,
+}
+
+#[derive(Debug, Deserialize)]
+struct InterestRateRequest {
+    annual_rate: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+struct WithdrawalLimitsRequest {
+    #[serde(default)]
+    per_transaction: Option<Decimal>,
+    #[serde(default)]
+    daily: Option<Decimal>,
+    currency: Currency,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlaceHoldRequest {
+    amount: Decimal,
+    currency: Currency,
+    /// Defaults to [`DEFAULT_HOLD_EXPIRY`] from now.
+    #[serde(default)]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CaptureHoldRequest {
+    #[serde(default)]
+    amount: Option<Decimal>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReasonRequest {
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReversalRequest {
+    sequence_number: Ulid,
+    reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FeeScheduleRequest {
+    #[serde(flatten)]
+    schedule: FeeSchedule,
+    currency: Currency,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChargeFeeRequest {
+    kind: FeeKind,
+    amount: Decimal,
+    currency: Currency,
+}
+
+/// REST endpoints for accounts:
+///
+/// * `POST /accounts` opens an account, `{"balance": "100", "currency": "EUR"}`
+/// * `GET /accounts/{id}` reads an account from the accounts projection, or with
+///   `?as_of=2026-03-31T23:59:00Z` or `?as_of={sequence_number}` replays its events up to that
+///   point in its history
+/// * `POST /accounts/{id}/deposits` and `POST /accounts/{id}/withdrawals`,
+///   `{"amount": "50", "currency": "EUR"}`
+/// * `POST /accounts/{id}/freeze` and `POST /accounts/{id}/unfreeze`, `{"reason": "..."}`
+/// * `POST /accounts/{id}/close` closes an account whose balance is zero
+/// * `PUT /accounts/{id}/overdraft` sets the overdraft limit, `{"limit": "500", "currency": "EUR"}`
+/// * `PUT /accounts/{id}/withdrawal-limits` sets the withdrawal limits,
+///   `{"per_transaction": "500", "daily": "1000", "currency": "EUR"}`, a missing limit lifts it
+/// * `PUT /accounts/{id}/interest-rate` sets the annual interest rate, `{"annual_rate": "0.02"}`
+/// * `PUT /accounts/{id}/fee-schedule` sets the fees,
+///   `{"product": "standard", "monthly_maintenance": "5", "per_withdrawal": "0.5",
+///   "overdraft_usage": "10", "fx": "1", "currency": "EUR"}`, a missing fee is not charged
+/// * `POST /accounts/{id}/fees` charges a fee by hand,
+///   `{"kind": "other", "amount": "5", "currency": "EUR"}`
+/// * `POST /accounts/{id}/fees/{fee_id}/waive` refunds a fee, `{"reason": "..."}`
+/// * `POST /accounts/{id}/holds` reserves funds,
+///   `{"amount": "50", "currency": "EUR", "expires_at": "2030-01-01T00:00:00Z"}`, the expiry
+///   defaulting to a week
+/// * `POST /accounts/{id}/holds/{hold_id}/capture` takes the held funds, `{"amount": "40"}` to
+///   take less than held
+/// * `POST /accounts/{id}/holds/{hold_id}/release` lifts a hold, `{"reason": "..."}`
+/// * `POST /accounts/{id}/reversals` undoes a deposit, withdrawal or capture,
+///   `{"sequence_number": "...", "reason": "..."}` naming the event to reverse
+/// * `GET /accounts/{id}/events` lists the account's events from the event store
+/// * `GET /accounts/{id}/transactions` lists a page of the account's transactions with their
+///   running balance, filtered by `from` (inclusive) and `to` (exclusive) RFC 3339 timestamps
+///   and by `type`, comma-separated, and paged by `limit` and `after`, the `next_after` of the
+///   previous page
+///
+/// The projection is updated asynchronously, so an account may briefly be missing or behind
+/// right after a command.
+///
+/// Events record the `X-Correlation-Id` (generated when missing), `X-Request-Id` and `X-Actor`
+/// request headers as metadata, along with any `X-Meta-*` headers.
+pub struct AccountApi<R: Repository<Account>, S: EventStore + SnapshotStore> {
+    account_service: AccountService<R, S>,
+}
+
+impl<R: Repository<Account> + TransactionRepository, S: EventStore + SnapshotStore>
+    AccountApi<R, S>
+{
+    pub fn new(account_service: AccountService<R, S>) -> Self {
+        Self { account_service }
+    }
+
+    pub fn handle(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &[(&str, &str)],
+        body: &str,
+    ) -> Response {
+        let metadata = request_metadata(headers);
+        self.route(method, path, &metadata, body)
+            .unwrap_or_else(Response::from)
+    }
+
+    /// Responds to a request whose body could not be read.
+    pub fn handle_bad_request(&self, detail: &str) -> Response {
+        ApiError::BadRequest(detail.to_string()).into()
+    }
+
+    fn route(
+        &self,
+        method: &str,
+        path: &str,
+        metadata: &EventMetadata,
+        body: &str,
+    ) -> Result<Response, ApiError> {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        match segments.as_slice() {
+            ["accounts"] => match method {
+                "POST" => {
+                    let request: OpenAccountRequest = parse_body(body)?;
+                    let account = self.account_service.create_account(
+                        request.balance,
+                        request.currency,
+                        metadata,
+                    )?;
+                    Ok(Response::json(201, &AccountResponse::from(account)))
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id] => match method {
+                "GET" => {
+                    let account_id = parse_account_id(account_id)?;
+                    match parse_as_of(query)? {
+                        Some(as_of) => {
+                            let account =
+                                self.account_service.get_account_as_of(account_id, as_of)?;
+                            Ok(Response::json(
+                                200,
+                                &AccountResponse::at(account, as_of.at()),
+                            ))
+                        }
+                        None => {
+                            let account = self.account_service.get_account(account_id)?;
+                            Ok(Response::json(200, &AccountResponse::from(account)))
+                        }
+                    }
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "deposits"] => match method {
+                "POST" => {
+                    let request: AmountRequest = parse_body(body)?;
+                    self.account_service.deposit(
+                        parse_account_id(account_id)?,
+                        request.amount,
+                        request.currency,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "withdrawals"] => match method {
+                "POST" => {
+                    let request: AmountRequest = parse_body(body)?;
+                    self.account_service.withdraw(
+                        parse_account_id(account_id)?,
+                        request.amount,
+                        request.currency,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "close"] => match method {
+                "POST" => {
+                    self.account_service
+                        .close_account(parse_account_id(account_id)?, metadata)?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "freeze"] => match method {
+                "POST" => {
+                    let request: ReasonRequest = parse_body(body)?;
+                    self.account_service.freeze_account(
+                        parse_account_id(account_id)?,
+                        &request.reason,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "unfreeze"] => match method {
+                "POST" => {
+                    let request: ReasonRequest = parse_body(body)?;
+                    self.account_service.unfreeze_account(
+                        parse_account_id(account_id)?,
+                        &request.reason,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "overdraft"] => match method {
+                "PUT" => {
+                    let request: OverdraftRequest = parse_body(body)?;
+                    self.account_service.set_overdraft_limit(
+                        parse_account_id(account_id)?,
+                        request.limit,
+                        request.currency,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "interest-rate"] => match method {
+                "PUT" => {
+                    let request: InterestRateRequest = parse_body(body)?;
+                    self.account_service.set_interest_rate(
+                        parse_account_id(account_id)?,
+                        request.annual_rate,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "withdrawal-limits"] => match method {
+                "PUT" => {
+                    let request: WithdrawalLimitsRequest = parse_body(body)?;
+                    self.account_service.set_withdrawal_limits(
+                        parse_account_id(account_id)?,
+                        request.per_transaction,
+                        request.daily,
+                        request.currency,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "holds"] => match method {
+                "POST" => {
+                    let request: PlaceHoldRequest = parse_body(body)?;
+                    let hold_id = self.account_service.place_hold(
+                        parse_account_id(account_id)?,
+                        request.amount,
+                        request.currency,
+                        request
+                            .expires_at
+                            .unwrap_or_else(|| Utc::now() + DEFAULT_HOLD_EXPIRY),
+                        metadata,
+                    )?;
+                    Ok(Response::json(201, &json!({ "hold_id": hold_id })))
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "holds", hold_id, "capture"] => match method {
+                "POST" => {
+                    let request: CaptureHoldRequest = parse_body(body)?;
+                    self.account_service.capture_hold(
+                        parse_account_id(account_id)?,
+                        parse_hold_id(hold_id)?,
+                        request.amount,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "holds", hold_id, "release"] => match method {
+                "POST" => {
+                    let request: ReasonRequest = parse_body(body)?;
+                    self.account_service.release_hold(
+                        parse_account_id(account_id)?,
+                        parse_hold_id(hold_id)?,
+                        &request.reason,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "fee-schedule"] => match method {
+                "PUT" => {
+                    let request: FeeScheduleRequest = parse_body(body)?;
+                    self.account_service.set_fee_schedule(
+                        parse_account_id(account_id)?,
+                        request.schedule,
+                        request.currency,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "fees"] => match method {
+                "POST" => {
+                    let request: ChargeFeeRequest = parse_body(body)?;
+                    let fee_id = self.account_service.charge_fee(
+                        parse_account_id(account_id)?,
+                        request.kind,
+                        request.amount,
+                        request.currency,
+                        metadata,
+                    )?;
+                    Ok(Response::json(201, &json!({ "fee_id": fee_id })))
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "fees", fee_id, "waive"] => match method {
+                "POST" => {
+                    let request: ReasonRequest = parse_body(body)?;
+                    self.account_service.waive_fee(
+                        parse_account_id(account_id)?,
+                        parse_fee_id(fee_id)?,
+                        &request.reason,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "reversals"] => match method {
+                "POST" => {
+                    let request: ReversalRequest = parse_body(body)?;
+                    self.account_service.reverse_transaction(
+                        parse_account_id(account_id)?,
+                        request.sequence_number,
+                        &request.reason,
+                        metadata,
+                    )?;
+                    Ok(Response::no_content())
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "events"] => match method {
+                "GET" => {
+                    let account_id = parse_account_id(account_id)?;
+                    let events = self.account_service.get_account_events(account_id)?;
+                    if events.is_empty() {
+                        return Err(RepositoryError::NotFound(account_id).into());
+                    }
+                    Ok(Response::json(200, &events))
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            ["accounts", account_id, "transactions"] => match method {
+                "GET" => {
+                    let page = self.account_service.get_transactions(
+                        parse_account_id(account_id)?,
+                        &parse_transaction_query(query)?,
+                    )?;
+                    Ok(Response::json(200, &page))
+                }
+                _ => Err(ApiError::MethodNotAllowed(method.to_string())),
+            },
+            _ => Err(ApiError::RouteNotFound(path.to_string())),
+        }
+    }
+}
+
+impl From<RepositoryError> for ApiError {
+    fn from(error: RepositoryError) -> Self {
+        ApiError::AccountServiceError(error.into())
+    }
+}
+
+fn request_metadata(headers: &[(&str, &str)]) -> EventMetadata {
+    let mut metadata = EventMetadata::new();
+
+    for (name, value) in headers {
+        let name = name.to_ascii_lowercase();
+        match name.as_str() {
+            CORRELATION_ID_HEADER => metadata = metadata.with_correlation_id(*value),
+            REQUEST_ID_HEADER => metadata = metadata.with_client_request_id(*value),
+            ACTOR_HEADER => metadata = metadata.with_actor(*value),
+            _ => {
+                if let Some(header) = name.strip_prefix(METADATA_HEADER_PREFIX) {
+                    metadata = metadata.with_header(header, *value);
+                }
+            }
+        }
+    }
+
+    // Every request starts an interaction, even when the client does not name it
+    if metadata.correlation_id.is_none() {
+        metadata = metadata.with_correlation_id(Ulid::new().to_string());
+    }
+
+    metadata
+}
+
+fn parse_account_id(account_id: &str) -> Result<Ulid, ApiError> {
+    Ulid::from_string(account_id)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid account ID {}: {}", account_id, e)))
+}
+
+fn parse_hold_id(hold_id: &str) -> Result<Ulid, ApiError> {
+    Ulid::from_string(hold_id)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid hold ID {}: {}", hold_id, e)))
+}
+
+fn parse_fee_id(fee_id: &str) -> Result<Ulid, ApiError> {
+    Ulid::from_string(fee_id)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid fee ID {}: {}", fee_id, e)))
+}
+
+fn parse_as_of(query: &str) -> Result<Option<AsOf>, ApiError> {
+    let mut as_of = None;
+
+    for parameter in query.split('&').filter(|parameter| !parameter.is_empty()) {
+        match parameter.split_once('=').unwrap_or((parameter, "")) {
+            ("as_of", value) => {
+                as_of = Some(
+                    percent_decode(value)?
+                        .parse()
+                        .map_err(|e: String| ApiError::BadRequest(e))?,
+                );
+            }
+            (other, _) => {
+                return Err(ApiError::BadRequest(format!(
+                    "Unknown query parameter {}",
+                    other
+                )));
+            }
+        }
+    }
+
+    Ok(as_of)
+}
+
+fn parse_transaction_query(query: &str) -> Result<TransactionQuery, ApiError> {
+    let mut transaction_query = TransactionQuery::new();
+
+    for parameter in query.split('&').filter(|parameter| !parameter.is_empty()) {
+        let (name, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+        let value = percent_decode(value)?;
+        let invalid = |e: &dyn std::fmt::Display| {
+            ApiError::BadRequest(format!("Invalid {} {}: {}", name, value, e))
+        };
+
+        transaction_query = match name {
+            "from" | "to" => {
+                let at = DateTime::parse_from_rfc3339(&value)
+                    .map_err(|e| invalid(&e))?
+                    .with_timezone(&Utc);
+                if name == "from" {
+                    transaction_query.with_from(at)
+                } else {
+                    transaction_query.with_to(at)
+                }
+            }
+            "type" => value
+                .split(',')
+                .try_fold(transaction_query, |query, kind| {
+                    Ok::<_, ApiError>(
+                        query.with_kind(kind.parse().map_err(|e: String| invalid(&e))?),
+                    )
+                })?,
+            "after" => {
+                transaction_query.with_after(Ulid::from_string(&value).map_err(|e| invalid(&e))?)
+            }
+            "limit" => transaction_query.with_limit(value.parse().map_err(|e| invalid(&e))?),
+            other => {
+                return Err(ApiError::BadRequest(format!(
+                    "Unknown query parameter {}",
+                    other
+                )));
+            }
+        };
+    }
+
+    Ok(transaction_query)
+}
+
+/// Decodes the `%XX` escapes of a query parameter value, `+` in timestamps stays a plus sign.
+fn percent_decode(value: &str) -> Result<String, ApiError> {
+    let invalid = || ApiError::BadRequest(format!("Invalid query parameter value {}", value));
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2).ok_or_else(invalid)?;
+            let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+            bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str(body)
+        .map_err(|e| ApiError::BadRequest(format!("Invalid request body: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::repositories::AccountRepositoryInMemory;
+    use crate::event_store_in_memory::EventStoreInMemory;
+    use serde_json::Value;
+
+    fn api() -> AccountApi<AccountRepositoryInMemory, EventStoreInMemory> {
+        AccountApi::new(AccountService::new(
+            AccountRepositoryInMemory::new(),
+            EventStoreInMemory::new(),
+        ))
+    }
+
+    fn open_account(api: &AccountApi<AccountRepositoryInMemory, EventStoreInMemory>) -> String {
+        let response = api.handle(
+            "POST",
+            "/accounts",
+            &[],
+            r#"{"balance": "100", "currency": "EUR"}"#,
+        );
+        assert_eq!(response.status, 201);
+        let account: Value = serde_json::from_str(&response.body).unwrap();
+        account["account_id"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn insufficient_balance_is_unprocessable() {
+        let api = api();
+        let account_id = open_account(&api);
+
+        let response = api.handle(
+            "POST",
+            &format!("/accounts/{account_id}/withdrawals"),
+            &[],
+            r#"{"amount": "150", "currency": "EUR"}"#,
+        );
+
+        assert_eq!(response.status, 422);
+        assert_eq!(response.content_type, "application/problem+json");
+        let problem: Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(problem["status"], 422);
+        assert_eq!(problem["title"], "Unprocessable Entity");
+    }
+
+    #[test]
+    fn frozen_accounts_conflict() {
+        let api = api();
+        let account_id = open_account(&api);
+
+        let response = api.handle(
+            "POST",
+            &format!("/accounts/{account_id}/freeze"),
+            &[],
+            r#"{"reason": "court order"}"#,
+        );
+        assert_eq!(response.status, 204);
+
+        let response = api.handle(
+            "POST",
+            &format!("/accounts/{account_id}/deposits"),
+            &[],
+            r#"{"amount": "1", "currency": "EUR"}"#,
+        );
+        assert_eq!(response.status, 409);
+    }
+
+    #[test]
+    fn unknown_accounts_are_not_found() {
+        let api = api();
+        let account_id = Ulid::new();
+
+        for (method, path, body) in [
+            ("GET", format!("/accounts/{account_id}"), ""),
+            ("GET", format!("/accounts/{account_id}/events"), ""),
+            (
+                "POST",
+                format!("/accounts/{account_id}/deposits"),
+                r#"{"amount": "1", "currency": "EUR"}"#,
+            ),
+        ] {
+            assert_eq!(
+                api.handle(method, &path, &[], body).status,
+                404,
+                "{method} {path}"
+            );
+        }
+    }
+
+    #[test]
+    fn events_list_the_account_history() {
+        let api = api();
+        let account_id = open_account(&api);
+        let path = format!("/accounts/{account_id}/deposits");
+        let headers = [
+            ("X-Correlation-Id", "checkout-42"),
+            ("X-Actor", "alice"),
+            ("X-Meta-Channel", "web"),
+        ];
+        assert_eq!(
+            api.handle(
+                "POST",
+                &path,
+                &headers,
+                r#"{"amount": 5, "currency": "EUR"}"#
+            )
+            .status,
+            204
+        );
+
+        let response = api.handle("GET", &format!("/accounts/{account_id}/events"), &[], "");
+
+        assert_eq!(response.status, 200);
+        let events: Vec<Value> = serde_json::from_str(&response.body).unwrap();
+        let event_types: Vec<&str> = events
+            .iter()
+            .map(|e| e["event_type"].as_str().unwrap())
+            .collect();
+        assert_eq!(event_types, ["account_opened", "deposit"]);
+
+        let metadata = &events[1]["metadata"];
+        assert_eq!(metadata["correlation_id"], "checkout-42");
+        assert_eq!(metadata["actor"], "alice");
+        assert_eq!(metadata["headers"]["channel"], "web");
+    }
+
+    #[test]
+    fn transaction_queries_are_validated() {
+        let api = api();
+        let account_id = Ulid::new();
+        let path = format!("/accounts/{account_id}/transactions");
+
+        assert_eq!(
+            api.handle("GET", &format!("{path}?type=deposit,fee"), &[], "")
+                .status,
+            404
+        );
+        for query in [
+            "type=loan",
+            "limit=many",
+            "from=yesterday",
+            "to=2026-03-31T23:59:00%2",
+            "sort=desc",
+        ] {
+            assert_eq!(
+                api.handle("GET", &format!("{path}?{query}"), &[], "")
+                    .status,
+                400,
+                "{query}"
+            );
+        }
+
+        let query =
+            parse_transaction_query("from=2026-03-31T23%3A59%3A00%2B02%3A00&limit=1000").unwrap();
+        assert_eq!(
+            query.from,
+            Some(
+                DateTime::parse_from_rfc3339("2026-03-31T21:59:00Z")
+                    .unwrap()
+                    .to_utc()
+            )
+        );
+        assert_eq!(query.limit, crate::account::transactions::MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn accounts_are_read_as_of_a_point_in_history() {
+        let api = api();
+        let account_id = open_account(&api);
+        let path = format!("/accounts/{account_id}");
+        api.handle(
+            "POST",
+            &format!("{path}/deposits"),
+            &[],
+            r#"{"amount": "50", "currency": "EUR"}"#,
+        );
+        let response = api.handle("GET", &format!("{path}/events"), &[], "");
+        let events: Vec<Value> = serde_json::from_str(&response.body).unwrap();
+        let opened = events[0]["sequence_number"].as_str().unwrap();
+        let deposited_at = events[1]["metadata"]["recorded_at"].as_str().unwrap();
+
+        let balance = |query: &str| {
+            let response = api.handle("GET", &format!("{path}?{query}"), &[], "");
+            assert_eq!(response.status, 200, "{query}");
+            let account: Value = serde_json::from_str(&response.body).unwrap();
+            account["balance"].as_str().unwrap().to_string()
+        };
+        assert_eq!(balance(&format!("as_of={opened}")), "100");
+        assert_eq!(
+            balance(&format!("as_of={}", deposited_at.replace('+', "%2B"))),
+            "150"
+        );
+
+        for (query, status) in [
+            ("as_of=2000-01-01T00:00:00Z", 404),
+            ("as_of=yesterday", 400),
+            ("at=2000-01-01T00:00:00Z", 400),
+        ] {
+            assert_eq!(
+                api.handle("GET", &format!("{path}?{query}"), &[], "")
+                    .status,
+                status,
+                "{query}"
+            );
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+
+use rust_decimal::Decimal;
+
+use crate::account::{Account, AccountHandler, AccountService};
+use crate::fee::MonthlyFeeJob;
+use crate::fx_rate_provider_in_memory::FxRateProviderInMemory;
+use crate::fx_rate_provider_sqlite::FxRateProviderSqlite;
+use crate::interest::{DayCount, InterestAccrualJob};
+use crate::ledger::LedgerHandler;
+use crate::outbox_relay::OutboxRelay;
+use crate::traits::{
+    EventBus, EventStore, FxRateProvider, Ledger, Outbox, Projection, Repository, SnapshotStore,
+    TransactionRepository, fx_rate_provider::FxPolicy, snapshot_store::SnapshotPolicy,
+};
+use crate::transfer::{TransferProcessManager, TransferService};
+
+pub struct Config {
+    pub event_store_path: String,
+    pub projection_database_path: String,
+    pub kafka_bootstrap_servers: String,
+    pub snapshot_interval: u64,
+    pub http_address: String,
+    /// CSV file the exchange rates are loaded from on startup.
+    pub fx_rates_path: String,
+    /// Fraction of the exchange rate kept by the bank on conversions.
+    pub fx_spread: Decimal,
+    /// Day-count convention daily interest is accrued with.
+    pub interest_day_count: DayCount,
+}
+
+impl Config {
+    pub fn new(
+        event_store_path: String,
+        projection_database_path: String,
+        kafka_bootstrap_servers: String,
+        snapshot_interval: u64,
+        http_address: String,
+        fx_rates_path: String,
+        fx_spread: Decimal,
+    ) -> Self {
+        Self {
+            event_store_path,
+            projection_database_path,
+            kafka_bootstrap_servers,
+            snapshot_interval,
+            http_address,
+            fx_rates_path,
+            fx_spread,
+            interest_day_count: DayCount::default(),
+        }
+    }
+
+    pub fn with_interest_day_count(mut self, interest_day_count: DayCount) -> Self {
+        self.interest_day_count = interest_day_count;
+        self
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new(
+            "data.db".to_string(),
+            "data.db".to_string(),
+            "localhost:9092".to_string(),
+            100,
+            "127.0.0.1:8080".to_string(),
+            "fx_rates.csv".to_string(),
+            Decimal::new(5, 3),
+        )
+    }
+}
+
+/// Exchange rates stored next to the event store, refreshed from `fx_rates_path` when the file
+/// exists. Conversions fail without rates, other commands are unaffected.
+pub fn load_fx_rates_sqlite(config: &Config) -> FxRateProviderSqlite {
+    let fx_rates = FxRateProviderSqlite::new(&config.event_store_path);
+    if Path::new(&config.fx_rates_path).exists() {
+        fx_rates
+            .load_csv(&config.fx_rates_path)
+            .expect("Failed to load exchange rates");
+    } else {
+        eprintln!("No exchange rates file at {}", config.fx_rates_path);
+    }
+    fx_rates
+}
+
+/// Exchange rates read from `fx_rates_path` into memory, none when the file is missing.
+pub fn load_fx_rates_in_memory(config: &Config) -> FxRateProviderInMemory {
+    if Path::new(&config.fx_rates_path).exists() {
+        FxRateProviderInMemory::from_csv(&config.fx_rates_path)
+            .expect("Failed to load exchange rates")
+    } else {
+        eprintln!("No exchange rates file at {}", config.fx_rates_path);
+        FxRateProviderInMemory::new()
+    }
+}
+
+/// Services of a started application, used to issue commands and read its state.
+pub struct App<R: Repository<Account>, S: EventStore + SnapshotStore> {
+    pub account_service: AccountService<R, S>,
+    pub transfer_service: TransferService<S>,
+}
+
+/// Wires the account and transfer components and the ledger onto the given infrastructure,
+/// seeds the projections and starts the subscribers, the outbox relay, the interest accrual job and the
+/// monthly fee job in the background.
+///
+/// With `rebuild_projections` the projections are dropped and replayed from the complete event
+/// history instead of catching up from their checkpoints.
+pub fn start<S, B, R, L>(
+    config: &Config,
+    event_store: S,
+    event_bus: B,
+    account_repository: R,
+    ledger: L,
+    fx_rates: Arc<dyn FxRateProvider + Send + Sync>,
+    rebuild_projections: bool,
+) -> App<R, S>
+where
+    S: EventStore + SnapshotStore + Outbox + Send + Sync + Clone + 'static,
+    B: EventBus + Send + Sync + Clone + 'static,
+    R: Repository<Account> + TransactionRepository + Projection + Send + Sync + Clone + 'static,
+    L: Ledger + Projection + Send + Sync + Clone + 'static,
+{
+    let fx_policy = FxPolicy::new(fx_rates, config.fx_spread);
+
+    // account components
+    let account_service = AccountService::new(account_repository.clone(), event_store.clone())
+        .with_snapshot_policy(SnapshotPolicy::EveryNEvents(config.snapshot_interval))
+        .with_fx_policy(fx_policy.clone());
+    let account_handler = AccountHandler::new(
+        account_repository.clone(),
+        event_bus.clone(),
+        event_store.clone(),
+    );
+
+    let ledger_handler = LedgerHandler::new(ledger, event_bus.clone(), event_store.clone());
+
+    let interest_accrual_job = InterestAccrualJob::new(
+        AccountService::new(account_repository.clone(), event_store.clone())
+            .with_snapshot_policy(SnapshotPolicy::EveryNEvents(config.snapshot_interval)),
+        config.interest_day_count,
+    );
+    let monthly_fee_job = MonthlyFeeJob::new(
+        AccountService::new(account_repository.clone(), event_store.clone())
+            .with_snapshot_policy(SnapshotPolicy::EveryNEvents(config.snapshot_interval)),
+    );
+
+    // transfer components
+    let transfer_service = TransferService::new(event_store.clone());
+    let transfer_process_manager =
+        TransferProcessManager::new(account_repository, event_bus.clone(), event_store.clone())
+            .with_fx_policy(fx_policy);
+
+    let outbox_relay = OutboxRelay::new(event_store, event_bus);
+
+    // seed the projection from the event store
+    let replayed = if rebuild_projections {
+        account_handler.rebuild()
+    } else {
+        account_handler.catch_up()
+    }
+    .expect("Failed to seed accounts projection");
+    println!("Accounts projection seeded with {} events", replayed);
+    let posted = if rebuild_projections {
+        ledger_handler.rebuild()
+    } else {
+        ledger_handler.catch_up()
+    }
+    .expect("Failed to seed ledger");
+    println!("Ledger seeded with {} events", posted);
+
+    // subscribing never blocks so handlers are in place before relaying
+    account_handler.listen();
+    ledger_handler.listen();
+    transfer_process_manager.listen();
+    thread::spawn(move || {
+        outbox_relay.run();
+    });
+    thread::spawn(move || {
+        interest_accrual_job.run();
+    });
+    thread::spawn(move || {
+        monthly_fee_job.run();
+    });
+
+    App {
+        account_service,
+        transfer_service,
+    }
+}
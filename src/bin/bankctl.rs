@@ -0,0 +1,1091 @@
+use std::sync::Arc;
+
+use bankingv2::account::account_handler::AccountHandlerError;
+use bankingv2::account::account_service::AccountServiceError;
+use bankingv2::account::events::{ACCOUNT_AGGREGATE_TYPE, AccountEvent};
+use bankingv2::account::repositories::AccountRepositorySqlite;
+use bankingv2::account::transactions::{Transaction, TransactionKind, TransactionQuery};
+use bankingv2::account::{Account, AccountHandler, AccountService, DEFAULT_HOLD_EXPIRY};
+use bankingv2::app::Config;
+use bankingv2::event_bus_in_memory::EventBusInMemory;
+use bankingv2::event_bus_kafka::EventBusKafka;
+use bankingv2::event_store_sqlite::EventStoreSqlite;
+use bankingv2::fee::{FeeKind, FeeSchedule, MonthlyFeeJob};
+use bankingv2::fx_rate_provider_sqlite::FxRateProviderSqlite;
+use bankingv2::interest::{DayCount, InterestAccrualJob};
+use bankingv2::ledger::ledger_handler::LedgerHandlerError;
+use bankingv2::ledger::repositories::LedgerRepositorySqlite;
+use bankingv2::ledger::{JournalEntry, JournalLine, LedgerError, LedgerHandler, TrialBalanceRow};
+use bankingv2::money::Currency;
+use bankingv2::outbox_relay::{OutboxRelay, OutboxRelayError};
+use bankingv2::traits::{
+    Event, EventStore, FxRateProvider, Ledger, Outbox, TransactionRepository,
+    event::{EventEnvelope, EventMetadata},
+    event_store::{AggregateSummary, EventStoreError},
+    fx_rate_provider::{FxPolicy, FxRateError},
+    outbox::OutboxError,
+    repository::RepositoryError,
+    snapshot_store::SnapshotPolicy,
+};
+use bankingv2::transfer::Transfer;
+use bankingv2::transfer::events::{TRANSFER_AGGREGATE_TYPE, TransferEvent};
+use chrono::{DateTime, NaiveDate, SecondsFormat, TimeDelta, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use thiserror::Error;
+use ulid::Ulid;
+
+#[derive(Debug, Error)]
+enum BankctlError {
+    #[error("Account service error: {0}")]
+    AccountServiceError(#[from] AccountServiceError),
+    #[error("Account handler error: {0}")]
+    AccountHandlerError(#[from] AccountHandlerError),
+    #[error("Ledger handler error: {0}")]
+    LedgerHandlerError(#[from] LedgerHandlerError),
+    #[error("Ledger error: {0}")]
+    LedgerError(#[from] LedgerError),
+    #[error("Repository error: {0}")]
+    RepositoryError(#[from] RepositoryError),
+    #[error("Event store error: {0}")]
+    EventStoreError(#[from] EventStoreError),
+    #[error("Outbox error: {0}")]
+    OutboxError(#[from] OutboxError),
+    #[error("Outbox relay error: {0}")]
+    OutboxRelayError(#[from] OutboxRelayError),
+    #[error("FX rate error: {0}")]
+    FxRateError(#[from] FxRateError),
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+    #[error("No events found for aggregate {0}")]
+    AggregateNotFound(Ulid),
+    #[error("Aggregate type {0} cannot be decoded")]
+    UnknownAggregateType(String),
+}
+
+/// Inspect and operate the banking event store.
+#[derive(Debug, Parser)]
+#[command(name = "bankctl")]
+struct Cli {
+    /// Path of the SQLite event store
+    #[arg(long, global = true)]
+    database: Option<String>,
+
+    /// Path of the SQLite projection database
+    #[arg(long, global = true)]
+    projection_database: Option<String>,
+
+    /// Kafka bootstrap servers, used when replaying events
+    #[arg(long, global = true)]
+    kafka_bootstrap_servers: Option<String>,
+
+    /// Recorded as the actor of the events appended by account commands
+    #[arg(long, global = true, default_value = "bankctl")]
+    actor: String,
+
+    /// Recorded as the correlation ID of the events appended by account commands
+    #[arg(long, global = true)]
+    correlation_id: Option<String>,
+
+    #[arg(long, global = true, value_enum, default_value_t = Output::Table)]
+    output: Output,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Output {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ProjectionName {
+    Accounts,
+    Ledger,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List aggregates with their current version
+    Aggregates {
+        /// Only list aggregates of this type
+        #[arg(long = "type")]
+        aggregate_type: Option<String>,
+    },
+    /// Dump the decoded event stream of an aggregate
+    Events { aggregate_id: Ulid },
+    /// Drop a projection and replay the complete event history into it
+    RebuildProjection { projection: ProjectionName },
+    /// Publish stored events to the event bus again; consumers skip events they already handled
+    Replay {
+        /// Only replay events stored after this sequence number
+        #[arg(long)]
+        after: Option<Ulid>,
+    },
+    /// Issue account commands
+    #[command(subcommand)]
+    Account(AccountCommand),
+    /// Manage the exchange rates deposits in a foreign currency are converted with
+    #[command(subcommand)]
+    Fx(FxCommand),
+    /// List an account's transactions with their running balance, oldest first
+    Transactions {
+        account_id: Ulid,
+        /// Only list transactions recorded at or after this time
+        #[arg(long)]
+        from: Option<DateTime<Utc>>,
+        /// Only list transactions recorded before this time
+        #[arg(long)]
+        to: Option<DateTime<Utc>>,
+        /// Only list transactions of these types, comma-separated
+        #[arg(long = "type", value_delimiter = ',')]
+        kinds: Vec<TransactionKind>,
+        /// Continue after this sequence number, the last one of the previous page
+        #[arg(long)]
+        after: Option<Ulid>,
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Inspect the double-entry ledger posted from account events
+    #[command(subcommand)]
+    Ledger(LedgerCommand),
+    /// Accrue interest on every account and post it at month ends, skipping days already accrued
+    AccrueInterest {
+        /// Last day to accrue, defaults to yesterday
+        #[arg(long)]
+        through: Option<NaiveDate>,
+        /// Day-count convention, ACT/365, ACT/360 or ACT/ACT
+        #[arg(long)]
+        day_count: Option<DayCount>,
+    },
+    /// Charge the monthly maintenance fee of every account, skipping month ends already charged
+    ChargeFees {
+        /// Last day to charge for, defaults to yesterday
+        #[arg(long)]
+        through: Option<NaiveDate>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum FxCommand {
+    /// Import rates from a CSV file of base,quote,rate lines
+    Import { path: String },
+    /// Show the rate converting one unit of a currency into another
+    Rate { from: Currency, to: Currency },
+}
+
+#[derive(Debug, Subcommand)]
+enum LedgerCommand {
+    /// Show the debits and credits of every ledger account, failing if they do not balance
+    TrialBalance,
+    /// Show the journal entries posted for an account's events
+    Entries { account_id: Ulid },
+}
+
+#[derive(Debug, Subcommand)]
+enum AccountCommand {
+    /// Open an account
+    Open {
+        #[arg(long, default_value_t = Decimal::ZERO)]
+        balance: Decimal,
+        /// ISO 4217 currency the account is held in
+        #[arg(long)]
+        currency: Currency,
+    },
+    /// Deposit into an account
+    Deposit {
+        account_id: Ulid,
+        amount: Decimal,
+        currency: Currency,
+    },
+    /// Withdraw from an account
+    Withdraw {
+        account_id: Ulid,
+        amount: Decimal,
+        currency: Currency,
+    },
+    /// Freeze an account, rejecting deposits and withdrawals until it is unfrozen
+    Freeze {
+        account_id: Ulid,
+        #[arg(long)]
+        reason: String,
+    },
+    /// Unfreeze a frozen account
+    Unfreeze {
+        account_id: Ulid,
+        #[arg(long)]
+        reason: String,
+    },
+    /// Close an account whose balance is zero
+    Close { account_id: Ulid },
+    /// Set how far an account may be overdrawn, 0 removes the overdraft
+    Overdraft {
+        account_id: Ulid,
+        limit: Decimal,
+        currency: Currency,
+    },
+    /// Set the annual interest rate of an account, as a fraction (0.02 for 2%)
+    InterestRate { account_id: Ulid, rate: Decimal },
+    /// Set the fees charged to an account, a fee left out is not charged
+    FeeSchedule {
+        account_id: Ulid,
+        /// Name of the product the fees belong to
+        #[arg(long, default_value = "")]
+        product: String,
+        #[arg(long, default_value_t = Decimal::ZERO)]
+        monthly_maintenance: Decimal,
+        #[arg(long, default_value_t = Decimal::ZERO)]
+        per_withdrawal: Decimal,
+        /// Charged with every withdrawal leaving the account overdrawn
+        #[arg(long, default_value_t = Decimal::ZERO)]
+        overdraft_usage: Decimal,
+        /// Charged with every deposit converted from another currency
+        #[arg(long, default_value_t = Decimal::ZERO)]
+        fx: Decimal,
+        #[arg(long)]
+        currency: Currency,
+    },
+    /// Charge a fee by hand, printing its ID
+    ChargeFee {
+        account_id: Ulid,
+        /// monthly_maintenance, withdrawal, overdraft, fx or other
+        kind: FeeKind,
+        amount: Decimal,
+        currency: Currency,
+    },
+    /// Refund a fee charged in the last 90 days
+    WaiveFee {
+        account_id: Ulid,
+        fee_id: Ulid,
+        #[arg(long)]
+        reason: String,
+    },
+    /// Undo a deposit, withdrawal or capture, naming its event by sequence number
+    Reverse {
+        account_id: Ulid,
+        sequence_number: Ulid,
+        #[arg(long)]
+        reason: String,
+    },
+    /// Reserve funds on an account, printing the hold's ID
+    Hold {
+        account_id: Ulid,
+        amount: Decimal,
+        currency: Currency,
+        /// Hours until the hold expires
+        #[arg(long, default_value_t = DEFAULT_HOLD_EXPIRY.num_hours())]
+        expires_in: i64,
+    },
+    /// Take the funds of a hold, all of them unless --amount names less
+    Capture {
+        account_id: Ulid,
+        hold_id: Ulid,
+        #[arg(long)]
+        amount: Option<Decimal>,
+    },
+    /// Lift a hold without taking any funds
+    Release {
+        account_id: Ulid,
+        hold_id: Ulid,
+        #[arg(long)]
+        reason: String,
+    },
+    /// Set the withdrawal limits of an account, a limit left out is lifted
+    Limits {
+        account_id: Ulid,
+        /// Largest single withdrawal
+        #[arg(long)]
+        per_transaction: Option<Decimal>,
+        /// Largest total withdrawn over a rolling day
+        #[arg(long)]
+        daily: Option<Decimal>,
+        #[arg(long)]
+        currency: Currency,
+    },
+}
+
+/// An event as shown by `bankctl events`.
+#[derive(Debug, Serialize)]
+struct EventRow {
+    version: u64,
+    sequence_number: Ulid,
+    created_at: String,
+    event_type: String,
+    event: serde_json::Value,
+    metadata: EventMetadata,
+}
+
+/// A journal line as shown by `bankctl ledger entries`.
+#[derive(Debug, Serialize)]
+struct JournalLineRow {
+    sequence_number: Ulid,
+    posted_at: String,
+    event_type: String,
+    description: String,
+    #[serde(flatten)]
+    line: JournalLine,
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    if let Err(e) = run(cli) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), BankctlError> {
+    let config = Config::default();
+    let database = cli
+        .database
+        .clone()
+        .unwrap_or(config.event_store_path.clone());
+    let event_store = EventStoreSqlite::new(&database);
+    let projection_database_path = cli
+        .projection_database
+        .as_ref()
+        .unwrap_or(&config.projection_database_path);
+
+    match cli.command {
+        Command::Aggregates { aggregate_type } => {
+            let aggregates = event_store.get_aggregates(aggregate_type.as_deref())?;
+            print(
+                cli.output,
+                &aggregates,
+                aggregate_columns,
+                &AGGREGATE_HEADERS,
+            )
+        }
+        Command::Events { aggregate_id } => {
+            let aggregate = event_store
+                .get_aggregate(aggregate_id)?
+                .ok_or(BankctlError::AggregateNotFound(aggregate_id))?;
+
+            let rows = match aggregate.aggregate_type.as_str() {
+                ACCOUNT_AGGREGATE_TYPE => event_rows(
+                    event_store.get_events_for_aggregate::<Account, AccountEvent>(
+                        aggregate_id,
+                        ACCOUNT_AGGREGATE_TYPE,
+                    )?,
+                )?,
+                TRANSFER_AGGREGATE_TYPE => event_rows(
+                    event_store.get_events_for_aggregate::<Transfer, TransferEvent>(
+                        aggregate_id,
+                        TRANSFER_AGGREGATE_TYPE,
+                    )?,
+                )?,
+                other => return Err(BankctlError::UnknownAggregateType(other.to_string())),
+            };
+
+            print(cli.output, &rows, event_columns, &EVENT_HEADERS)
+        }
+        Command::RebuildProjection { projection } => {
+            let replayed = match projection {
+                // Rebuilding only reads the event store, the bus is never used
+                ProjectionName::Accounts => AccountHandler::new(
+                    AccountRepositorySqlite::new(projection_database_path),
+                    EventBusInMemory::new(),
+                    event_store,
+                )
+                .rebuild()?,
+                ProjectionName::Ledger => LedgerHandler::new(
+                    LedgerRepositorySqlite::new(projection_database_path),
+                    EventBusInMemory::new(),
+                    event_store,
+                )
+                .rebuild()?,
+            };
+            println!(
+                "Replayed {} events into the {:?} projection",
+                replayed, projection
+            );
+            Ok(())
+        }
+        Command::Replay { after } => {
+            let requeued = event_store.requeue(after)?;
+            let event_bus = EventBusKafka::new(
+                cli.kafka_bootstrap_servers
+                    .as_ref()
+                    .unwrap_or(&config.kafka_bootstrap_servers),
+            );
+            let published = OutboxRelay::new(event_store, event_bus).relay_pending()?;
+            println!("Requeued {} events, published {}", requeued, published);
+            Ok(())
+        }
+        Command::Account(command) => {
+            let fx_rates = FxRateProviderSqlite::new(&database);
+            let account_service = AccountService::new(
+                AccountRepositorySqlite::new(projection_database_path),
+                event_store.clone(),
+            )
+            .with_snapshot_policy(SnapshotPolicy::EveryNEvents(config.snapshot_interval))
+            .with_fx_policy(FxPolicy::new(Arc::new(fx_rates), config.fx_spread));
+
+            let mut metadata = EventMetadata::new().with_actor(cli.actor);
+            if let Some(correlation_id) = cli.correlation_id {
+                metadata = metadata.with_correlation_id(correlation_id);
+            }
+
+            // Events reach the projection once a running application relays the outbox
+            let account_id = match command {
+                AccountCommand::Open { balance, currency } => account_service
+                    .create_account(balance, currency, &metadata)?
+                    .account_id
+                    .ok_or_else(|| {
+                        AccountServiceError::OperationError("Account ID missing".to_string())
+                    })?,
+                AccountCommand::Deposit {
+                    account_id,
+                    amount,
+                    currency,
+                } => {
+                    account_service.deposit(account_id, amount, currency, &metadata)?;
+                    account_id
+                }
+                AccountCommand::Withdraw {
+                    account_id,
+                    amount,
+                    currency,
+                } => {
+                    account_service.withdraw(account_id, amount, currency, &metadata)?;
+                    account_id
+                }
+                AccountCommand::Freeze { account_id, reason } => {
+                    account_service.freeze_account(account_id, &reason, &metadata)?;
+                    account_id
+                }
+                AccountCommand::Unfreeze { account_id, reason } => {
+                    account_service.unfreeze_account(account_id, &reason, &metadata)?;
+                    account_id
+                }
+                AccountCommand::Close { account_id } => {
+                    account_service.close_account(account_id, &metadata)?;
+                    account_id
+                }
+                AccountCommand::Overdraft {
+                    account_id,
+                    limit,
+                    currency,
+                } => {
+                    account_service.set_overdraft_limit(account_id, limit, currency, &metadata)?;
+                    account_id
+                }
+                AccountCommand::InterestRate { account_id, rate } => {
+                    account_service.set_interest_rate(account_id, rate, &metadata)?;
+                    account_id
+                }
+                AccountCommand::FeeSchedule {
+                    account_id,
+                    product,
+                    monthly_maintenance,
+                    per_withdrawal,
+                    overdraft_usage,
+                    fx,
+                    currency,
+                } => {
+                    let schedule = FeeSchedule {
+                        product,
+                        monthly_maintenance,
+                        per_withdrawal,
+                        overdraft_usage,
+                        fx,
+                    };
+                    account_service.set_fee_schedule(account_id, schedule, currency, &metadata)?;
+                    account_id
+                }
+                AccountCommand::ChargeFee {
+                    account_id,
+                    kind,
+                    amount,
+                    currency,
+                } => {
+                    let fee_id = account_service
+                        .charge_fee(account_id, kind, amount, currency, &metadata)?;
+                    eprintln!("Charged fee {}", fee_id);
+                    account_id
+                }
+                AccountCommand::WaiveFee {
+                    account_id,
+                    fee_id,
+                    reason,
+                } => {
+                    account_service.waive_fee(account_id, fee_id, &reason, &metadata)?;
+                    account_id
+                }
+                AccountCommand::Reverse {
+                    account_id,
+                    sequence_number,
+                    reason,
+                } => {
+                    account_service.reverse_transaction(
+                        account_id,
+                        sequence_number,
+                        &reason,
+                        &metadata,
+                    )?;
+                    account_id
+                }
+                AccountCommand::Hold {
+                    account_id,
+                    amount,
+                    currency,
+                    expires_in,
+                } => {
+                    let hold_id = account_service.place_hold(
+                        account_id,
+                        amount,
+                        currency,
+                        Utc::now() + TimeDelta::hours(expires_in),
+                        &metadata,
+                    )?;
+                    eprintln!("Placed hold {}", hold_id);
+                    account_id
+                }
+                AccountCommand::Capture {
+                    account_id,
+                    hold_id,
+                    amount,
+                } => {
+                    account_service.capture_hold(account_id, hold_id, amount, &metadata)?;
+                    account_id
+                }
+                AccountCommand::Release {
+                    account_id,
+                    hold_id,
+                    reason,
+                } => {
+                    account_service.release_hold(account_id, hold_id, &reason, &metadata)?;
+                    account_id
+                }
+                AccountCommand::Limits {
+                    account_id,
+                    per_transaction,
+                    daily,
+                    currency,
+                } => {
+                    account_service.set_withdrawal_limits(
+                        account_id,
+                        per_transaction,
+                        daily,
+                        currency,
+                        &metadata,
+                    )?;
+                    account_id
+                }
+            };
+
+            let (account, version) =
+                Account::load::<_, AccountServiceError>(&event_store, account_id)?;
+            print(
+                cli.output,
+                &[account],
+                |account| account_columns(account, version, Utc::now()),
+                &ACCOUNT_HEADERS,
+            )
+        }
+        Command::AccrueInterest { through, day_count } => {
+            let through = match through {
+                Some(through) => through,
+                None => Utc::now().date_naive().pred_opt().ok_or_else(|| {
+                    AccountServiceError::OperationError("No day to accrue".to_string())
+                })?,
+            };
+            let account_service = AccountService::new(
+                AccountRepositorySqlite::new(projection_database_path),
+                event_store,
+            )
+            .with_snapshot_policy(SnapshotPolicy::EveryNEvents(config.snapshot_interval));
+            let job = InterestAccrualJob::new(
+                account_service,
+                day_count.unwrap_or(config.interest_day_count),
+            );
+            println!(
+                "Accrued {} days of interest through {}",
+                job.run_once(through)?,
+                through
+            );
+            Ok(())
+        }
+        Command::ChargeFees { through } => {
+            let through = match through {
+                Some(through) => through,
+                None => Utc::now().date_naive().pred_opt().ok_or_else(|| {
+                    AccountServiceError::OperationError("No day to charge for".to_string())
+                })?,
+            };
+            let account_service = AccountService::new(
+                AccountRepositorySqlite::new(projection_database_path),
+                event_store,
+            )
+            .with_snapshot_policy(SnapshotPolicy::EveryNEvents(config.snapshot_interval));
+            let job = MonthlyFeeJob::new(account_service);
+            println!(
+                "Charged {} monthly fees through {}",
+                job.run_once(through)?,
+                through
+            );
+            Ok(())
+        }
+        Command::Transactions {
+            account_id,
+            from,
+            to,
+            kinds,
+            after,
+            limit,
+        } => {
+            let query = transaction_query(from, to, kinds, after, limit);
+            let page = AccountRepositorySqlite::new(projection_database_path)
+                .get_transactions(account_id, &query)?;
+            print(
+                cli.output,
+                &page.transactions,
+                transaction_columns,
+                &TRANSACTION_HEADERS,
+            )?;
+            if let Some(next_after) = page.next_after {
+                eprintln!(
+                    "More transactions follow, continue with --after {}",
+                    next_after
+                );
+            }
+            Ok(())
+        }
+        Command::Ledger(command) => {
+            // The ledger is posted to by a running application or rebuilt with
+            // `rebuild-projection ledger`
+            let ledger = LedgerRepositorySqlite::new(projection_database_path);
+            match command {
+                LedgerCommand::TrialBalance => {
+                    let trial_balance = ledger.trial_balance()?;
+                    print(
+                        cli.output,
+                        &trial_balance.rows,
+                        trial_balance_columns,
+                        &TRIAL_BALANCE_HEADERS,
+                    )?;
+                    for (currency, (debits, credits)) in trial_balance.totals() {
+                        eprintln!("Total {}: debits {}, credits {}", currency, debits, credits);
+                    }
+                    trial_balance.assert_balanced()?;
+                }
+                LedgerCommand::Entries { account_id } => {
+                    let rows = journal_line_rows(ledger.get_entries(account_id)?);
+                    print(
+                        cli.output,
+                        &rows,
+                        journal_line_columns,
+                        &JOURNAL_LINE_HEADERS,
+                    )?;
+                }
+            }
+            Ok(())
+        }
+        Command::Fx(command) => {
+            let fx_rates = FxRateProviderSqlite::new(&database);
+            match command {
+                FxCommand::Import { path } => {
+                    println!("Imported {} exchange rates", fx_rates.load_csv(&path)?);
+                }
+                FxCommand::Rate { from, to } => {
+                    println!("1 {} = {} {}", from, fx_rates.rate(from, to)?, to);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn event_rows<T, E: Event<T> + Serialize>(
+    envelopes: Vec<EventEnvelope<T, E>>,
+) -> Result<Vec<EventRow>, BankctlError> {
+    envelopes
+        .into_iter()
+        .map(|envelope| {
+            Ok(EventRow {
+                version: envelope.version(),
+                sequence_number: envelope.sequence_number(),
+                created_at: format_timestamp(envelope.metadata.recorded_at),
+                event_type: envelope.event_type().to_string(),
+                event: serde_json::to_value(&envelope.event)?,
+                metadata: envelope.metadata,
+            })
+        })
+        .collect()
+}
+
+/// Sequence numbers are ULIDs generated when the event was appended, their timestamp is the
+/// moment the event was stored.
+fn created_at(sequence_number: Ulid) -> String {
+    format_timestamp(DateTime::<Utc>::from(sequence_number.datetime()))
+}
+
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+const AGGREGATE_HEADERS: [&str; 5] = ["TYPE", "ID", "VERSION", "CREATED_AT", "UPDATED_AT"];
+
+fn aggregate_columns(aggregate: &AggregateSummary) -> Vec<String> {
+    vec![
+        aggregate.aggregate_type.clone(),
+        aggregate.aggregate_id.to_string(),
+        aggregate.version.to_string(),
+        created_at(aggregate.first_sequence_number),
+        created_at(aggregate.last_sequence_number),
+    ]
+}
+
+const EVENT_HEADERS: [&str; 7] = [
+    "VERSION",
+    "SEQUENCE_NUMBER",
+    "CREATED_AT",
+    "EVENT_TYPE",
+    "ACTOR",
+    "CORRELATION_ID",
+    "EVENT",
+];
+
+fn event_columns(row: &EventRow) -> Vec<String> {
+    vec![
+        row.version.to_string(),
+        row.sequence_number.to_string(),
+        row.created_at.clone(),
+        row.event_type.clone(),
+        row.metadata.actor.clone().unwrap_or_default(),
+        row.metadata.correlation_id.clone().unwrap_or_default(),
+        row.event.to_string(),
+    ]
+}
+
+const ACCOUNT_HEADERS: [&str; 9] = [
+    "ID",
+    "BALANCE",
+    "AVAILABLE",
+    "CURRENCY",
+    "STATUS",
+    "OVERDRAFT",
+    "RATE",
+    "ACCRUED",
+    "VERSION",
+];
+
+/// The account at `version`, with its balance available at `at`.
+fn account_columns(account: &Account, version: u64, at: DateTime<Utc>) -> Vec<String> {
+    vec![
+        account
+            .account_id
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        account.balance.to_string(),
+        account.available_balance(at).to_string(),
+        account.currency.to_string(),
+        account.status.to_string(),
+        account.overdraft_limit.to_string(),
+        account.interest_rate.to_string(),
+        account.accrued_interest.to_string(),
+        version.to_string(),
+    ]
+}
+
+/// The filters of `bankctl transactions`, each left out is not applied.
+fn transaction_query(
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    kinds: Vec<TransactionKind>,
+    after: Option<Ulid>,
+    limit: Option<usize>,
+) -> TransactionQuery {
+    let mut query = TransactionQuery::new();
+    if let Some(from) = from {
+        query = query.with_from(from);
+    }
+    if let Some(to) = to {
+        query = query.with_to(to);
+    }
+    for kind in kinds {
+        query = query.with_kind(kind);
+    }
+    if let Some(after) = after {
+        query = query.with_after(after);
+    }
+    if let Some(limit) = limit {
+        query = query.with_limit(limit);
+    }
+    query
+}
+
+const TRANSACTION_HEADERS: [&str; 8] = [
+    "SEQUENCE_NUMBER",
+    "RECORDED_AT",
+    "TYPE",
+    "AMOUNT",
+    "BALANCE",
+    "CURRENCY",
+    "REVERSED_BY",
+    "DESCRIPTION",
+];
+
+fn transaction_columns(transaction: &Transaction) -> Vec<String> {
+    vec![
+        transaction.sequence_number.to_string(),
+        format_timestamp(transaction.recorded_at),
+        transaction.kind.to_string(),
+        transaction.amount.to_string(),
+        transaction.balance.to_string(),
+        transaction.currency.to_string(),
+        transaction
+            .reversed_by
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        transaction.description.clone(),
+    ]
+}
+
+const TRIAL_BALANCE_HEADERS: [&str; 5] = ["ACCOUNT", "NAME", "CURRENCY", "DEBITS", "CREDITS"];
+
+fn trial_balance_columns(row: &TrialBalanceRow) -> Vec<String> {
+    vec![
+        row.ledger_account.code(),
+        row.name.to_string(),
+        row.currency.to_string(),
+        row.debits.to_string(),
+        row.credits.to_string(),
+    ]
+}
+
+/// One row per journal line, each repeating the entry it belongs to.
+fn journal_line_rows(entries: Vec<JournalEntry>) -> Vec<JournalLineRow> {
+    entries
+        .into_iter()
+        .flat_map(|entry| {
+            let posted_at = format_timestamp(entry.posted_at);
+            entry.lines.into_iter().map(move |line| JournalLineRow {
+                sequence_number: entry.sequence_number,
+                posted_at: posted_at.clone(),
+                event_type: entry.event_type.clone(),
+                description: entry.description.clone(),
+                line,
+            })
+        })
+        .collect()
+}
+
+const JOURNAL_LINE_HEADERS: [&str; 8] = [
+    "SEQUENCE_NUMBER",
+    "POSTED_AT",
+    "EVENT_TYPE",
+    "ACCOUNT",
+    "SIDE",
+    "AMOUNT",
+    "CURRENCY",
+    "DESCRIPTION",
+];
+
+fn journal_line_columns(row: &JournalLineRow) -> Vec<String> {
+    vec![
+        row.sequence_number.to_string(),
+        row.posted_at.clone(),
+        row.event_type.clone(),
+        row.line.ledger_account.code(),
+        row.line.side.as_str().to_string(),
+        row.line.amount.to_string(),
+        row.line.currency.to_string(),
+        row.description.clone(),
+    ]
+}
+
+fn print<T: Serialize>(
+    output: Output,
+    items: &[T],
+    columns: impl Fn(&T) -> Vec<String>,
+    headers: &[&str],
+) -> Result<(), BankctlError> {
+    println!("{}", render(output, items, columns, headers)?);
+    Ok(())
+}
+
+/// Renders items as pretty JSON or as a table with a column per header, each column as wide
+/// as its widest cell.
+fn render<T: Serialize>(
+    output: Output,
+    items: &[T],
+    columns: impl Fn(&T) -> Vec<String>,
+    headers: &[&str],
+) -> Result<String, BankctlError> {
+    let rows: Vec<Vec<String>> = match output {
+        Output::Json => return Ok(serde_json::to_string_pretty(items)?),
+        Output::Table => items.iter().map(columns).collect(),
+    };
+
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain([header.len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let headers = headers.iter().map(|header| header.to_string()).collect();
+    let lines: Vec<String> = std::iter::once(headers)
+        .chain(rows)
+        .map(|row| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect();
+            cells.join("  ").trim_end().to_string()
+        })
+        .collect();
+
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bankingv2::account::events::DepositEvent;
+    use bankingv2::ledger::{LedgerAccount, Side};
+    use bankingv2::money::Money;
+
+    #[test]
+    fn table_columns_fit_their_widest_cell() {
+        let items = [("deposit", 100), ("fee", 5)];
+        let table = render(
+            Output::Table,
+            &items,
+            |(kind, amount)| vec![kind.to_string(), amount.to_string()],
+            &["TYPE", "AMOUNT"],
+        )
+        .unwrap();
+
+        assert_eq!(table, "TYPE     AMOUNT\ndeposit  100\nfee      5");
+    }
+
+    #[test]
+    fn json_output_ignores_the_columns() {
+        let json = render(Output::Json, &[("deposit", 100)], |_| vec![], &[]).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            serde_json::json!([["deposit", 100]])
+        );
+    }
+
+    #[test]
+    fn transaction_filters_are_read_from_the_command_line() {
+        let account_id = Ulid::new();
+        let cli = Cli::try_parse_from([
+            "bankctl",
+            "transactions",
+            &account_id.to_string(),
+            "--from",
+            "2026-03-01T00:00:00Z",
+            "--type",
+            "deposit,fee",
+            "--limit",
+            "10",
+        ])
+        .unwrap();
+        let Command::Transactions {
+            from,
+            to,
+            kinds,
+            after,
+            limit,
+            ..
+        } = cli.command
+        else {
+            panic!("expected the transactions command, got {:?}", cli.command);
+        };
+
+        let query = transaction_query(from, to, kinds, after, limit);
+
+        assert_eq!(query.from, "2026-03-01T00:00:00Z".parse().ok());
+        assert_eq!(query.to, None);
+        assert_eq!(
+            query.kinds,
+            vec![TransactionKind::Deposit, TransactionKind::Fee]
+        );
+        assert_eq!(query.after, None);
+        assert_eq!(query.limit, 10);
+    }
+
+    #[test]
+    fn events_show_who_caused_them() {
+        let account_id = Ulid::new();
+        let sequence_number = Ulid::new();
+        let envelope = EventEnvelope::<Account, AccountEvent>::new(
+            sequence_number,
+            2,
+            account_id,
+            ACCOUNT_AGGREGATE_TYPE.to_string(),
+            "deposit".to_string(),
+            AccountEvent::Deposited(DepositEvent {
+                account_id,
+                amount: Money::new(Decimal::from(25), Currency::EUR).unwrap(),
+                transfer_id: None,
+            }),
+            EventMetadata::new()
+                .with_actor("teller")
+                .with_correlation_id("request-1"),
+        );
+
+        let rows = event_rows(vec![envelope]).unwrap();
+        let columns = event_columns(&rows[0]);
+
+        assert_eq!(columns.len(), EVENT_HEADERS.len());
+        assert_eq!(columns[0], "2");
+        assert_eq!(columns[1], sequence_number.to_string());
+        assert_eq!(columns[3], "deposit");
+        assert_eq!(columns[4], "teller");
+        assert_eq!(columns[5], "request-1");
+        assert_eq!(rows[0].event["amount"], "25");
+    }
+
+    #[test]
+    fn journal_lines_repeat_their_entry() {
+        let account_id = Ulid::new();
+        let line = |ledger_account, side| JournalLine {
+            ledger_account,
+            side,
+            amount: Decimal::from(25),
+            currency: Currency::EUR,
+        };
+        let entry = JournalEntry {
+            sequence_number: Ulid::new(),
+            account_id,
+            event_type: "deposit".to_string(),
+            posted_at: Utc::now(),
+            description: "Deposit".to_string(),
+            lines: vec![
+                line(LedgerAccount::Cash, Side::Debit),
+                line(LedgerAccount::CustomerDeposits(account_id), Side::Credit),
+            ],
+        };
+
+        let rows = journal_line_rows(vec![entry.clone()]);
+        let columns: Vec<Vec<String>> = rows.iter().map(journal_line_columns).collect();
+
+        assert_eq!(columns.len(), 2);
+        for row in &columns {
+            assert_eq!(row[0], entry.sequence_number.to_string());
+            assert_eq!(row[2], "deposit");
+            assert_eq!(row[7], "Deposit");
+        }
+        assert_eq!(columns[0][3..5], ["1000", "debit"]);
+        assert_eq!(
+            columns[1][3..5],
+            [format!("2000:{}", account_id), "credit".to_string()]
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use bankingv2::account::{
+    Account,
+    repositories::{AccountRepositoryInMemory, AccountRepositorySqlite},
+};
+use bankingv2::api::AccountApi;
+use bankingv2::app::{self, App, Config};
+use bankingv2::event_bus_in_memory::EventBusInMemory;
+use bankingv2::event_bus_kafka::EventBusKafka;
+use bankingv2::event_store_in_memory::EventStoreInMemory;
+use bankingv2::event_store_sqlite::EventStoreSqlite;
+use bankingv2::ledger::repositories::{LedgerRepositoryInMemory, LedgerRepositorySqlite};
+use bankingv2::traits::{EventStore, Repository, SnapshotStore, TransactionRepository};
+use tiny_http::{Header, Response, Server};
+
+fn main() {
+    let config = Config::default();
+    let rebuild_projections = std::env::args().any(|arg| arg == "--rebuild-projections");
+
+    // `--in-memory` runs everything in a single process, without SQLite or Kafka
+    if std::env::args().any(|arg| arg == "--in-memory") {
+        serve(
+            &config,
+            app::start(
+                &config,
+                EventStoreInMemory::new(),
+                EventBusInMemory::new(),
+                AccountRepositoryInMemory::new(),
+                LedgerRepositoryInMemory::new(),
+                Arc::new(app::load_fx_rates_in_memory(&config)),
+                rebuild_projections,
+            ),
+        );
+    } else {
+        serve(
+            &config,
+            app::start(
+                &config,
+                EventStoreSqlite::new(&config.event_store_path),
+                EventBusKafka::new(&config.kafka_bootstrap_servers),
+                AccountRepositorySqlite::new(&config.projection_database_path),
+                LedgerRepositorySqlite::new(&config.projection_database_path),
+                Arc::new(app::load_fx_rates_sqlite(&config)),
+                rebuild_projections,
+            ),
+        );
+    }
+}
+
+fn serve<R, S>(config: &Config, app: App<R, S>)
+where
+    R: Repository<Account> + TransactionRepository,
+    S: EventStore + SnapshotStore,
+{
+    let api = AccountApi::new(app.account_service);
+    let server = Server::http(&config.http_address).expect("Failed to start HTTP server");
+    println!("Listening on http://{}", config.http_address);
+
+    // Requests are handled one at a time, commands on an account are serialised anyway
+    for mut request in server.incoming_requests() {
+        let headers: Vec<(String, String)> = request
+            .headers()
+            .iter()
+            .map(|header| (header.field.to_string(), header.value.to_string()))
+            .collect();
+        let headers: Vec<(&str, &str)> = headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+
+        let mut body = String::new();
+        let response = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => api.handle(request.method().as_str(), request.url(), &headers, &body),
+            Err(e) => api.handle_bad_request(&e.to_string()),
+        };
+
+        let content_type = Header::from_bytes("Content-Type", response.content_type)
+            .expect("Content type is a valid header");
+        let result = request.respond(
+            Response::from_string(response.body)
+                .with_status_code(response.status)
+                .with_header(content_type),
+        );
+        if let Err(e) = result {
+            eprintln!("Failed to send response: {}", e);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::traits::event_bus::{EventBusError, EventHandler};
+use crate::traits::{Event, EventBus, event::EventEnvelope, outbox::OutboxMessage};
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+type Subscriber = Arc<dyn Fn(&OutboxMessage) + Send + Sync + 'static>;
+
+/// Event bus that delivers published events to its subscribers in process.
+///
+/// Delivery is synchronous: `publish` returns once every subscriber has handled the event, in
+/// the order the subscribers were registered. Like `EventBusKafka`, messages travel serialised
+/// and handler or deserialisation errors are reported without failing the publish.
+#[derive(Clone, Default)]
+pub struct EventBusInMemory {
+    subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl EventBusInMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl EventBus for EventBusInMemory {
+    fn publish(&self, message: &OutboxMessage) -> Result<(), EventBusError> {
+        // Release the lock before handling so handlers may subscribe or publish themselves
+        let subscribers = self
+            .subscribers
+            .lock()
+            .map_err(|e| EventBusError::ProduceError(e.to_string()))?
+            .clone();
+
+        for subscriber in subscribers {
+            subscriber(message);
+        }
+
+        Ok(())
+    }
+
+    fn subscribe<T, E>(&self, _subscriber: &str, aggregate_type: &str, handler: EventHandler<T, E>)
+    where
+        T: 'static,
+        E: Event<T> + for<'de> Deserialize<'de> + 'static,
+    {
+        let aggregate_type = aggregate_type.to_string();
+
+        let subscriber: Subscriber = Arc::new(move |message: &OutboxMessage| {
+            if message.aggregate_type != aggregate_type {
+                return;
+            }
+
+            match EventEnvelope::<T, E>::from_json(message.payload.as_bytes()) {
+                Ok(envelope) => {
+                    if let Err(e) = handler(envelope) {
+                        eprintln!("Error handling event: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("Failed to deserialize: {}", e),
+            }
+        });
+
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(subscriber);
+    }
+}
@@ -1,100 +1,183 @@
-use crate::traits::event_bus::EventBusError;
-use crate::traits::{Event, EventBus, event::EventEnvelope};
+use crate::traits::event_bus::{EventBusError, EventHandler};
+use crate::traits::{Event, EventBus, event::EventEnvelope, outbox::OutboxMessage};
 use rdkafka::Message;
 use rdkafka::config::ClientConfig;
 use rdkafka::consumer::{BaseConsumer, Consumer};
-use rdkafka::producer::{BaseProducer, BaseRecord};
-use serde::{Deserialize, Serialize};
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{BaseProducer, BaseRecord, Producer};
+use serde::Deserialize;
 use std::sync::Arc;
 use std::time::Duration;
-use ulid::Ulid;
+
+use chrono::SecondsFormat;
+
+/// Prefix of the consumer groups, every subscriber consumes the topic in its own group
+const CONSUMER_GROUP_PREFIX: &str = "banking_consumer_group";
+
+/// How many times a message is handed to a failing handler before it is skipped.
+const MAX_HANDLE_ATTEMPTS: u32 = 5;
+
+/// Delay before handing a message to a failing handler again, doubled after every attempt.
+const HANDLE_RETRY_DELAY: Duration = Duration::from_millis(200);
 
 #[derive(Clone)]
 pub struct EventBusKafka {
+    bootstrap_servers: String,
     producer: Arc<BaseProducer>,
-    consumer: Arc<BaseConsumer>,
+}
+
+/// Prefix of the Kafka headers carrying the free-form headers of an event's metadata.
+const METADATA_HEADER_PREFIX: &str = "meta.";
+
+/// The part of a serialised envelope needed to route it, read before decoding the event.
+#[derive(Deserialize)]
+struct EnvelopeRoute {
+    aggregate_type: String,
 }
 
 impl EventBusKafka {
     pub fn new(bootstrap_servers: &str) -> Self {
-        // Use a stable consumer group ID instead of a random one
-        let consumer_group = "banking_consumer_group";
-
         let producer: BaseProducer = ClientConfig::new()
             .set("bootstrap.servers", bootstrap_servers)
             .set("message.timeout.ms", "5000")
             .create()
             .expect("Failed to create producer");
 
-        let consumer: BaseConsumer = ClientConfig::new()
-            .set("bootstrap.servers", bootstrap_servers) // Should match producer config
-            .set("group.id", consumer_group)
-            .set("enable.auto.commit", "true")
-            .set("auto.offset.reset", "earliest") // Process existing messages only once per consumer group
-            .create()
-            .expect("Failed to create consumer");
-
-        consumer
-            .subscribe(&["events"])
-            .expect("Failed to subscribe to topic");
-
         Self {
+            bootstrap_servers: bootstrap_servers.to_string(),
             producer: Arc::new(producer),
-            consumer: Arc::new(consumer),
         }
     }
 }
 
-impl EventBus for EventBusKafka {
-    fn produce_event<T, E: Event<T> + Serialize>(&self, event: E) -> Result<(), EventBusError> {
-        let envelope = EventEnvelope::new(
-            Ulid::new(),
-            event.aggregate_id(),
-            event.aggregate_type().to_string(),
-            event.event_type().to_string(),
-            event,
-        );
-        let envelope_json =
-            serde_json::to_string(&envelope).map_err(EventBusError::SerialisationError)?;
+/// Kafka headers describing a message, so consumers can route and trace events without
+/// decoding the payload.
+fn message_headers(message: &OutboxMessage) -> OwnedHeaders {
+    let metadata = &message.metadata;
+    let mut headers = vec![
+        ("event_type", Some(message.event_type.clone())),
+        ("aggregate_type", Some(message.aggregate_type.clone())),
+        ("aggregate_id", Some(message.aggregate_id.to_string())),
+        ("sequence_number", Some(message.sequence_number.to_string())),
+        (
+            "recorded_at",
+            Some(
+                metadata
+                    .recorded_at
+                    .to_rfc3339_opts(SecondsFormat::Micros, true),
+            ),
+        ),
+        ("correlation_id", metadata.correlation_id.clone()),
+        (
+            "causation_id",
+            metadata.causation_id.map(|id| id.to_string()),
+        ),
+        ("actor", metadata.actor.clone()),
+        ("client_request_id", metadata.client_request_id.clone()),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| value.map(|value| (key.to_string(), value)))
+    .collect::<Vec<_>>();
+
+    headers.extend(
+        metadata
+            .headers
+            .iter()
+            .map(|(key, value)| (format!("{METADATA_HEADER_PREFIX}{key}"), value.clone())),
+    );
+
+    headers
+        .iter()
+        .fold(OwnedHeaders::new(), |kafka_headers, (key, value)| {
+            kafka_headers.insert(Header {
+                key,
+                value: Some(value),
+            })
+        })
+}
 
+impl EventBus for EventBusKafka {
+    fn publish(&self, message: &OutboxMessage) -> Result<(), EventBusError> {
         self.producer
             .send(
                 BaseRecord::to("events")
-                    .payload(&envelope_json)
-                    .key(&envelope.aggregate_id.to_string()),
+                    .payload(&message.payload)
+                    .key(&message.aggregate_id.to_string())
+                    .headers(message_headers(message)),
             )
             .map_err(|(e, _)| EventBusError::ProduceError(e.to_string()))?;
 
         Ok(())
     }
 
-    fn subscribe<T, E>(
-        &self,
-        aggregate_type: &str,
-        handler: Box<dyn Fn(E) -> Result<(), EventBusError> + Send + Sync + 'static>,
-    ) where
+    fn flush(&self) -> Result<(), EventBusError> {
+        // Wait for delivery, an unflushed message would be lost if the process stops
+        self.producer
+            .flush(Duration::from_secs(5))
+            .map_err(|e| EventBusError::ProduceError(e.to_string()))
+    }
+
+    fn subscribe<T, E>(&self, subscriber: &str, aggregate_type: &str, handler: EventHandler<T, E>)
+    where
+        T: 'static,
         E: Event<T> + for<'de> Deserialize<'de> + 'static,
     {
         let aggregate_type = aggregate_type.to_string();
-        let consumer = self.consumer.clone(); // Clone the Arc<BaseConsumer>
+
+        // Use a stable consumer group ID per subscriber so each receives every event once
+        let consumer: BaseConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &self.bootstrap_servers) // Should match producer config
+            .set("group.id", format!("{CONSUMER_GROUP_PREFIX}.{subscriber}"))
+            .set("enable.auto.commit", "true")
+            .set("auto.offset.reset", "earliest") // Process existing messages only once per consumer group
+            .create()
+            .expect("Failed to create consumer");
+
+        consumer
+            .subscribe(&["events"])
+            .expect("Failed to subscribe to topic");
 
         std::thread::spawn(move || {
             loop {
                 match consumer.poll(Duration::from_millis(50)) {
                     Some(Ok(msg)) => {
-                        if let Some(payload) = msg.payload() {
-                            {
-                                let payload_vec = payload.to_vec();
-                                match serde_json::from_slice::<EventEnvelope<T, E>>(&payload_vec) {
-                                    Ok(envelope) => {
-                                        if envelope.event.aggregate_type() == aggregate_type {
-                                            if let Err(e) = handler(envelope.event) {
-                                                eprintln!("Error handling event: {}", e);
-                                            }
-                                        }
-                                    }
-                                    Err(e) => eprintln!("Failed to deserialize: {}", e),
+                        let Some(payload) = msg.payload() else {
+                            continue;
+                        };
+
+                        // Every subscriber reads the whole topic, skip other aggregates undecoded
+                        match serde_json::from_slice::<EnvelopeRoute>(payload) {
+                            Ok(route) if route.aggregate_type != aggregate_type => continue,
+                            Ok(_) => {}
+                            Err(e) => {
+                                eprintln!("Failed to deserialize: {}", e);
+                                continue;
+                            }
+                        }
+
+                        // Handlers return the errors worth retrying, redeliver the message in
+                        // place so later events of the partition never overtake it
+                        let mut delay = HANDLE_RETRY_DELAY;
+                        for attempt in 1..=MAX_HANDLE_ATTEMPTS {
+                            let result = match EventEnvelope::<T, E>::from_json(payload) {
+                                Ok(envelope) => handler(envelope),
+                                Err(e) => {
+                                    eprintln!("Failed to deserialize: {}", e);
+                                    break;
+                                }
+                            };
+
+                            match result {
+                                Ok(()) => break,
+                                Err(e) if attempt < MAX_HANDLE_ATTEMPTS => {
+                                    eprintln!("Error handling event, retrying: {}", e);
+                                    std::thread::sleep(delay);
+                                    delay *= 2;
                                 }
+                                Err(e) => eprintln!(
+                                    "Error handling event, giving up after {} attempts: {}",
+                                    attempt, e
+                                ),
                             }
                         }
                     }
@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use ulid::{Generator, Ulid};
+
+use crate::traits::{
+    Event, EventStore, Outbox, SnapshotStore,
+    event::{EventEnvelope, EventMetadata},
+    event_store::{AggregateSummary, EventStoreError},
+    outbox::{OutboxError, OutboxMessage},
+    snapshot_store::{Snapshot, SnapshotStoreError},
+};
+
+/// Event store kept in process memory, for tests and single-process development.
+///
+/// Events are stored serialised, exactly like `EventStoreSqlite` does, so serialisation
+/// problems surface here as well. Clones share the same underlying store.
+#[derive(Clone, Default)]
+pub struct EventStoreInMemory {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    events: Vec<StoredEvent>,
+    outbox: Vec<(OutboxMessage, bool)>,
+    snapshots: HashMap<(String, Ulid), (u64, String)>,
+    generator: Generator,
+    /// Time every event is recorded at instead of the time of its append.
+    recorded_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+struct StoredEvent {
+    sequence_number: Ulid,
+    aggregate_id: Ulid,
+    aggregate_type: String,
+    version: u64,
+    event_json: String,
+}
+
+impl EventStoreInMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records every event at `recorded_at`, for tests that work on fixed dates.
+    pub fn with_recorded_at(self, recorded_at: DateTime<Utc>) -> Self {
+        self.set_recorded_at(recorded_at);
+        self
+    }
+
+    /// Records the events appended from now on at `recorded_at`, through every clone.
+    pub fn set_recorded_at(&self, recorded_at: DateTime<Utc>) {
+        self.lock().recorded_at = Some(recorded_at);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        // A panic while holding the lock cannot leave a half-written command behind, every
+        // append is validated before anything is pushed
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn decode<T, E: Event<T> + DeserializeOwned>(
+    stored: &StoredEvent,
+) -> Result<EventEnvelope<T, E>, EventStoreError> {
+    let mut envelope = EventEnvelope::<T, E>::from_json(stored.event_json.as_bytes())
+        .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
+    envelope.version = stored.version;
+    Ok(envelope)
+}
+
+impl EventStore for EventStoreInMemory {
+    fn append_events<T, E: Event<T> + Serialize>(
+        &self,
+        aggregate_id: Ulid,
+        aggregate_type: &str,
+        expected_version: u64,
+        events: Vec<E>,
+        metadata: &EventMetadata,
+    ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError> {
+        let mut inner = self.lock();
+
+        let actual_version = inner
+            .events
+            .iter()
+            .filter(|e| e.aggregate_id == aggregate_id && e.aggregate_type == aggregate_type)
+            .map(|e| e.version)
+            .max()
+            .unwrap_or(0);
+
+        if actual_version != expected_version {
+            return Err(EventStoreError::ConcurrencyConflict {
+                expected: expected_version,
+                actual: actual_version,
+            });
+        }
+
+        // Serialise the whole batch before storing anything so the append stays atomic
+        let mut envelopes = Vec::with_capacity(events.len());
+        let mut serialised = Vec::with_capacity(events.len());
+        let metadata = match inner.recorded_at {
+            Some(recorded_at) => EventMetadata {
+                recorded_at,
+                ..metadata.clone()
+            },
+            None => metadata.recorded_now(),
+        };
+
+        for (version, event) in (expected_version + 1..).zip(events) {
+            let sequence_number = inner
+                .generator
+                .generate()
+                .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
+
+            let envelope = EventEnvelope::new(
+                sequence_number,
+                version,
+                aggregate_id,
+                aggregate_type.to_string(),
+                event.event_type().to_string(),
+                event,
+                metadata.clone(),
+            );
+            let event_json = serde_json::to_string(&envelope)
+                .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
+
+            serialised.push(event_json);
+            envelopes.push(envelope);
+        }
+
+        for (envelope, event_json) in envelopes.iter().zip(serialised) {
+            let id = inner.outbox.len() as u64 + 1;
+            inner.outbox.push((
+                OutboxMessage {
+                    id,
+                    sequence_number: envelope.sequence_number(),
+                    aggregate_id,
+                    aggregate_type: aggregate_type.to_string(),
+                    event_type: envelope.event_type().to_string(),
+                    payload: event_json.clone(),
+                    metadata: metadata.clone(),
+                    attempts: 0,
+                },
+                false,
+            ));
+            inner.events.push(StoredEvent {
+                sequence_number: envelope.sequence_number(),
+                aggregate_id,
+                aggregate_type: aggregate_type.to_string(),
+                version: envelope.version(),
+                event_json,
+            });
+        }
+
+        Ok(envelopes)
+    }
+
+    fn get_events_for_aggregate<T, E: Event<T> + Serialize + for<'de> Deserialize<'de>>(
+        &self,
+        aggregate_id: Ulid,
+        aggregate_type: &str,
+    ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError> {
+        self.get_events_for_aggregate_since(aggregate_id, aggregate_type, 0)
+    }
+
+    fn get_events_for_aggregate_since<T, E: Event<T> + Serialize + for<'de> Deserialize<'de>>(
+        &self,
+        aggregate_id: Ulid,
+        aggregate_type: &str,
+        after_version: u64,
+    ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError> {
+        // Events are pushed in version order per aggregate, no sorting needed
+        self.lock()
+            .events
+            .iter()
+            .filter(|e| {
+                e.aggregate_id == aggregate_id
+                    && e.aggregate_type == aggregate_type
+                    && e.version > after_version
+            })
+            .map(decode)
+            .collect()
+    }
+
+    fn get_events_for_aggregate_type<T, E: Event<T> + Serialize + for<'de> Deserialize<'de>>(
+        &self,
+        aggregate_type: &str,
+        after_sequence_number: Option<Ulid>,
+        limit: usize,
+    ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError> {
+        self.lock()
+            .events
+            .iter()
+            .filter(|e| {
+                e.aggregate_type == aggregate_type
+                    && after_sequence_number.is_none_or(|after| e.sequence_number > after)
+            })
+            .take(limit)
+            .map(decode)
+            .collect()
+    }
+
+    fn get_all_events<T, E: Event<T> + Serialize + for<'de> Deserialize<'de>>(
+        &self,
+    ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError> {
+        self.lock().events.iter().map(decode).collect()
+    }
+
+    fn get_aggregates(
+        &self,
+        aggregate_type: Option<&str>,
+    ) -> Result<Vec<AggregateSummary>, EventStoreError> {
+        let mut aggregates: Vec<AggregateSummary> = Vec::new();
+
+        // Events are in sequence order, so aggregates are discovered in creation order
+        for event in self
+            .lock()
+            .events
+            .iter()
+            .filter(|e| aggregate_type.is_none_or(|t| e.aggregate_type == t))
+        {
+            match aggregates.iter_mut().find(|a| {
+                a.aggregate_id == event.aggregate_id && a.aggregate_type == event.aggregate_type
+            }) {
+                Some(aggregate) => {
+                    aggregate.version = event.version;
+                    aggregate.last_sequence_number = event.sequence_number;
+                }
+                None => aggregates.push(AggregateSummary {
+                    aggregate_type: event.aggregate_type.clone(),
+                    aggregate_id: event.aggregate_id,
+                    version: event.version,
+                    first_sequence_number: event.sequence_number,
+                    last_sequence_number: event.sequence_number,
+                }),
+            }
+        }
+
+        Ok(aggregates)
+    }
+
+    fn get_aggregate(
+        &self,
+        aggregate_id: Ulid,
+    ) -> Result<Option<AggregateSummary>, EventStoreError> {
+        let inner = self.lock();
+        let mut events = inner
+            .events
+            .iter()
+            .filter(|e| e.aggregate_id == aggregate_id);
+
+        let Some(first) = events.next() else {
+            return Ok(None);
+        };
+        let last = events.next_back().unwrap_or(first);
+
+        Ok(Some(AggregateSummary {
+            aggregate_type: first.aggregate_type.clone(),
+            aggregate_id,
+            version: last.version,
+            first_sequence_number: first.sequence_number,
+            last_sequence_number: last.sequence_number,
+        }))
+    }
+}
+
+impl Outbox for EventStoreInMemory {
+    fn get_pending_messages(&self, limit: usize) -> Result<Vec<OutboxMessage>, OutboxError> {
+        Ok(self
+            .lock()
+            .outbox
+            .iter()
+            .filter(|(_, dispatched)| !dispatched)
+            .take(limit)
+            .map(|(message, _)| message.clone())
+            .collect())
+    }
+
+    fn mark_dispatched(&self, id: u64) -> Result<(), OutboxError> {
+        let mut inner = self.lock();
+        let (message, dispatched) = find_message(&mut inner, id)?;
+        message.attempts += 1;
+        *dispatched = true;
+        Ok(())
+    }
+
+    fn mark_failed(&self, id: u64, _error: &str) -> Result<(), OutboxError> {
+        let mut inner = self.lock();
+        let (message, _) = find_message(&mut inner, id)?;
+        message.attempts += 1;
+        Ok(())
+    }
+
+    fn requeue(&self, after_sequence_number: Option<Ulid>) -> Result<usize, OutboxError> {
+        let mut requeued = 0;
+
+        for (message, dispatched) in self.lock().outbox.iter_mut() {
+            if *dispatched
+                && after_sequence_number.is_none_or(|after| message.sequence_number > after)
+            {
+                *dispatched = false;
+                requeued += 1;
+            }
+        }
+
+        Ok(requeued)
+    }
+}
+
+fn find_message(
+    inner: &mut Inner,
+    id: u64,
+) -> Result<(&mut OutboxMessage, &mut bool), OutboxError> {
+    inner
+        .outbox
+        .iter_mut()
+        .find(|(message, _)| message.id == id)
+        .map(|(message, dispatched)| (message, dispatched))
+        .ok_or_else(|| OutboxError::OutboxError(format!("Outbox message {} not found", id)))
+}
+
+impl SnapshotStore for EventStoreInMemory {
+    fn save_snapshot<T: Serialize>(
+        &self,
+        snapshot: &Snapshot<T>,
+    ) -> Result<(), SnapshotStoreError> {
+        let state_json = serde_json::to_string(&snapshot.state)
+            .map_err(|e| SnapshotStoreError::SnapshotStoreError(e.to_string()))?;
+
+        let mut inner = self.lock();
+        let key = (snapshot.aggregate_type.clone(), snapshot.aggregate_id);
+
+        // Never replace a snapshot with an older one taken by a slower writer
+        if inner
+            .snapshots
+            .get(&key)
+            .is_none_or(|(version, _)| snapshot.version > *version)
+        {
+            inner.snapshots.insert(key, (snapshot.version, state_json));
+        }
+
+        Ok(())
+    }
+
+    fn get_snapshot<T: DeserializeOwned>(
+        &self,
+        aggregate_id: Ulid,
+        aggregate_type: &str,
+    ) -> Result<Option<Snapshot<T>>, SnapshotStoreError> {
+        let inner = self.lock();
+        let Some((version, state_json)) = inner
+            .snapshots
+            .get(&(aggregate_type.to_string(), aggregate_id))
+        else {
+            return Ok(None);
+        };
+
+        let state = serde_json::from_str(state_json)
+            .map_err(|e| SnapshotStoreError::SnapshotStoreError(e.to_string()))?;
+
+        Ok(Some(Snapshot {
+            aggregate_id,
+            aggregate_type: aggregate_type.to_string(),
+            version: *version,
+            state,
+        }))
+    }
+}
@@ -1,10 +1,35 @@
+use chrono::{DateTime, SecondsFormat, Utc};
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
-use rusqlite::named_params;
-use serde::{Deserialize, Serialize};
+use rusqlite::{ErrorCode, OptionalExtension, TransactionBehavior, named_params};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use ulid::Ulid;
 
-use crate::traits::{Event, EventStore, event::EventEnvelope, event_store::EventStoreError};
+use crate::traits::{
+    Event, EventStore, Outbox, SnapshotStore,
+    event::{EventEnvelope, EventMetadata, tag_legacy_event},
+    event_store::{AggregateSummary, EventStoreError},
+    outbox::{OutboxError, OutboxMessage},
+    snapshot_store::{Snapshot, SnapshotStoreError},
+};
+
+/// Columns read for every event, in the order `envelope_from_row` expects them.
+const EVENT_COLUMNS: &str = "sequence_number, aggregate_id, aggregate_type, event, version,
+    recorded_at, correlation_id, causation_id, actor, client_request_id, headers";
+
+/// Metadata columns added to the events table after its creation.
+const METADATA_COLUMNS: [&str; 6] = [
+    "recorded_at",
+    "correlation_id",
+    "causation_id",
+    "actor",
+    "client_request_id",
+    "headers",
+];
+
+/// `user_version` of databases whose legacy events have been tagged, older databases are
+/// migrated once on startup.
+const LEGACY_EVENTS_TAGGED: i64 = 1;
 
 #[derive(Debug, Clone)]
 pub struct EventStoreSqlite {
@@ -17,14 +42,21 @@ impl EventStoreSqlite {
         let pool = Pool::new(manager).expect("Failed to create pool");
 
         // Apply migrations using pool.get()
-        let conn = pool.get().expect("Failed to get connection");
+        let mut conn = pool.get().expect("Failed to get connection");
         conn.execute_batch(
             "CREATE TABLE IF NOT EXISTS events (
                 sequence_number TEXT PRIMARY KEY NOT NULL,
                 aggregate_id TEXT NOT NULL,
                 aggregate_type TEXT NOT NULL,
+                version INTEGER NOT NULL,
                 event TEXT NOT NULL,
-                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                recorded_at TEXT,
+                correlation_id TEXT,
+                causation_id TEXT,
+                actor TEXT,
+                client_request_id TEXT,
+                headers TEXT
             );
 
             CREATE INDEX IF NOT EXISTS idx_events_aggregate
@@ -32,64 +64,368 @@ impl EventStoreSqlite {
         )
         .expect("Failed to create events table");
 
+        // Databases created before versioning was introduced lack the version column,
+        // backfill it from the existing ordering of each aggregate's stream
+        let has_version_column: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info('events') WHERE name = 'version'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("Failed to inspect events table");
+
+        if !has_version_column {
+            conn.execute_batch(
+                "ALTER TABLE events ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+
+                UPDATE events SET version = (
+                    SELECT COUNT(*) FROM events AS previous
+                    WHERE previous.aggregate_id = events.aggregate_id
+                    AND previous.aggregate_type = events.aggregate_type
+                    AND previous.sequence_number <= events.sequence_number
+                );",
+            )
+            .expect("Failed to add version column to events table");
+        }
+
+        // Databases created before metadata was recorded lack its columns, the only metadata
+        // known for their events is when they were created
+        for column in METADATA_COLUMNS {
+            let has_column: bool = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM pragma_table_info('events') WHERE name = :name",
+                    named_params! { ":name": column },
+                    |row| row.get(0),
+                )
+                .expect("Failed to inspect events table");
+
+            if !has_column {
+                conn.execute_batch(&format!("ALTER TABLE events ADD COLUMN {column} TEXT;"))
+                    .expect("Failed to add metadata column to events table");
+            }
+        }
+
+        conn.execute_batch(
+            "UPDATE events
+            SET recorded_at = strftime('%Y-%m-%dT%H:%M:%S', created_at) || '.000000Z'
+            WHERE recorded_at IS NULL AND created_at IS NOT NULL;",
+        )
+        .expect("Failed to backfill recorded_at of events");
+
+        conn.execute_batch(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_events_aggregate_version
+            ON events(aggregate_type, aggregate_id, version);",
+        )
+        .expect("Failed to create events version index");
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS outbox (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                sequence_number TEXT NOT NULL UNIQUE,
+                aggregate_id TEXT NOT NULL,
+                aggregate_type TEXT NOT NULL,
+                event_type TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                dispatched_at TIMESTAMP
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_outbox_pending
+            ON outbox(dispatched_at, id);",
+        )
+        .expect("Failed to create outbox table");
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS snapshots (
+                aggregate_id TEXT NOT NULL,
+                aggregate_type TEXT NOT NULL,
+                version INTEGER NOT NULL,
+                state TEXT NOT NULL,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (aggregate_type, aggregate_id)
+            );",
+        )
+        .expect("Failed to create snapshots table");
+
+        let schema_version: i64 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .expect("Failed to read events schema version");
+        if schema_version < LEGACY_EVENTS_TAGGED {
+            Self::tag_legacy_events(&mut conn).expect("Failed to tag legacy events");
+        }
+
         Self { pool }
     }
+
+    /// Rewrites events stored before events carried their own discriminator, tagging each with
+    /// the `event_type` recorded on its envelope. Bumps the database to
+    /// [`LEGACY_EVENTS_TAGGED`] in the same transaction, so this only runs once.
+    fn tag_legacy_events(conn: &mut rusqlite::Connection) -> Result<(), rusqlite::Error> {
+        let transaction = conn.transaction()?;
+
+        let legacy_events = {
+            let mut statement = transaction.prepare("SELECT sequence_number, event FROM events")?;
+            let rows = statement.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+
+            let mut legacy_events = Vec::new();
+            for row in rows {
+                let (sequence_number, event_json) = row?;
+                let Ok(mut envelope) = serde_json::from_str(&event_json) else {
+                    continue;
+                };
+                if tag_legacy_event(&mut envelope) {
+                    legacy_events.push((sequence_number, envelope.to_string()));
+                }
+            }
+            legacy_events
+        };
+
+        for (sequence_number, event_json) in legacy_events {
+            transaction.execute(
+                "UPDATE events SET event = :event WHERE sequence_number = :sequence_number",
+                named_params! {
+                    ":sequence_number": sequence_number,
+                    ":event": event_json,
+                },
+            )?;
+        }
+
+        transaction.pragma_update(None, "user_version", LEGACY_EVENTS_TAGGED)?;
+        transaction.commit()
+    }
+}
+
+/// Version of the last event stored for the aggregate, `0` if it has none.
+fn stored_version(
+    conn: &rusqlite::Connection,
+    aggregate_id: Ulid,
+    aggregate_type: &str,
+) -> Result<u64, EventStoreError> {
+    conn.query_row(
+        "SELECT MAX(version) FROM events
+         WHERE aggregate_id = :aggregate_id AND aggregate_type = :aggregate_type",
+        named_params! {
+            ":aggregate_id": aggregate_id.to_string(),
+            ":aggregate_type": aggregate_type,
+        },
+        |row| row.get::<_, Option<u64>>(0),
+    )
+    .map(|version| version.unwrap_or(0))
+    .map_err(|e| EventStoreError::EventStoreError(e.to_string()))
+}
+
+/// Sequence number of the last event stored by any writer, `None` for an empty store.
+fn last_sequence_number(conn: &rusqlite::Connection) -> Result<Option<Ulid>, EventStoreError> {
+    conn.query_row("SELECT MAX(sequence_number) FROM events", [], |row| {
+        row.get::<_, Option<String>>(0)
+    })
+    .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?
+    .map(|s| Ulid::from_string(&s))
+    .transpose()
+    .map_err(|e| EventStoreError::EventStoreError(e.to_string()))
+}
+
+/// ULIDs created within the same millisecond are randomly ordered, and other processes' clocks
+/// may run ahead of ours. Keep sequence numbers strictly increasing so they reflect the order
+/// events were stored in.
+fn next_sequence_number(last: Option<Ulid>) -> Result<Ulid, EventStoreError> {
+    let candidate = Ulid::new();
+    match last {
+        Some(last) if candidate <= last => last.increment().ok_or_else(|| {
+            EventStoreError::EventStoreError("Sequence number overflow".to_string())
+        }),
+        _ => Ok(candidate),
+    }
+}
+
+/// Decodes an event selected with [`EVENT_COLUMNS`].
+fn envelope_from_row<T, E: Event<T> + DeserializeOwned>(
+    row: &rusqlite::Row,
+) -> Result<EventEnvelope<T, E>, rusqlite::Error> {
+    let event_json: String = row.get(3)?;
+
+    // Deserialize just the event from the JSON string
+    let mut envelope = EventEnvelope::<T, E>::from_json(event_json.as_bytes()).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+
+    // The columns are authoritative, rows written before versioning or metadata lack them in
+    // the serialized envelope
+    envelope.version = row.get(4)?;
+    envelope.metadata = metadata_from_row(row, 5)?;
+
+    Ok(envelope)
+}
+
+/// Decodes the metadata columns, in the order of [`METADATA_COLUMNS`], starting at `first`.
+fn metadata_from_row(row: &rusqlite::Row, first: usize) -> Result<EventMetadata, rusqlite::Error> {
+    let conversion_error = |index: usize, e: Box<dyn std::error::Error + Send + Sync>| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, e)
+    };
+
+    let recorded_at = row
+        .get::<_, Option<String>>(first)?
+        .map(|s| DateTime::parse_from_rfc3339(&s).map(|at| at.with_timezone(&Utc)))
+        .transpose()
+        .map_err(|e| conversion_error(first, Box::new(e)))?
+        .unwrap_or_default();
+    let causation_id = row
+        .get::<_, Option<String>>(first + 2)?
+        .map(|s| Ulid::from_string(&s))
+        .transpose()
+        .map_err(|e| conversion_error(first + 2, Box::new(e)))?;
+    let headers = row
+        .get::<_, Option<String>>(first + 5)?
+        .map(|s| serde_json::from_str(&s))
+        .transpose()
+        .map_err(|e| conversion_error(first + 5, Box::new(e)))?
+        .unwrap_or_default();
+
+    Ok(EventMetadata {
+        recorded_at,
+        correlation_id: row.get(first + 1)?,
+        causation_id,
+        actor: row.get(first + 3)?,
+        client_request_id: row.get(first + 4)?,
+        headers,
+    })
 }
 
 impl EventStore for EventStoreSqlite {
-    fn append_event<T, E: Event<T> + Serialize>(
+    fn append_events<T, E: Event<T> + Serialize>(
         &self,
         aggregate_id: Ulid,
         aggregate_type: &str,
-        event: E,
-    ) -> Result<(), EventStoreError> {
-        let conn = self.pool.get().expect("Failed to get connection");
-        let sequence_number = Ulid::new();
-
-        let envelope = EventEnvelope::new(
-            sequence_number,
-            aggregate_id,
-            aggregate_type.to_string(),
-            event.event_type().to_string(),
-            event,
-        );
+        expected_version: u64,
+        events: Vec<E>,
+        metadata: &EventMetadata,
+    ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError> {
+        let mut conn = self.pool.get().expect("Failed to get connection");
 
-        // Store the sequence number, aggregate_id, and aggregate_type as separate columns
-        // but serialize just the event itself (not the whole envelope)
-        let event_json = serde_json::to_string(&envelope)
+        // Take the write lock up front so the version check and the inserts are atomic
+        let transaction = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
             .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
 
-        let mut statement = conn
-            .prepare(
-                "INSERT INTO events (sequence_number, aggregate_id, aggregate_type, event) VALUES (:sequence_number, :aggregate_id, :aggregate_type, :event)",
-            )
+        let actual_version = stored_version(&transaction, aggregate_id, aggregate_type)?;
+
+        if actual_version != expected_version {
+            return Err(EventStoreError::ConcurrencyConflict {
+                expected: expected_version,
+                actual: actual_version,
+            });
+        }
+
+        // Read under the write lock, so events stored by other processes are accounted for
+        let mut last_sequence_number = last_sequence_number(&transaction)?;
+        let mut envelopes = Vec::with_capacity(events.len());
+        let metadata = metadata.recorded_now();
+        let headers_json = serde_json::to_string(&metadata.headers)
             .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
 
-        statement
-            .execute(named_params! {
-                ":sequence_number": sequence_number.to_string(),
-                ":aggregate_id": aggregate_id.to_string(),
-                ":aggregate_type": aggregate_type,
-                ":event": event_json,
-            })
+        for (version, event) in (expected_version + 1..).zip(events) {
+            // Generated under the write lock, so sequence numbers follow commit order
+            let sequence_number = next_sequence_number(last_sequence_number)?;
+            last_sequence_number = Some(sequence_number);
+
+            let envelope = EventEnvelope::new(
+                sequence_number,
+                version,
+                aggregate_id,
+                aggregate_type.to_string(),
+                event.event_type().to_string(),
+                event,
+                metadata.clone(),
+            );
+
+            // Store the sequence number, aggregate_id, and aggregate_type as separate columns
+            // next to the serialized envelope
+            let event_json = serde_json::to_string(&envelope)
+                .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
+
+            let inserted = transaction
+                .execute(
+                    "INSERT INTO events (sequence_number, aggregate_id, aggregate_type, version, event, recorded_at, correlation_id, causation_id, actor, client_request_id, headers)
+                     VALUES (:sequence_number, :aggregate_id, :aggregate_type, :version, :event, :recorded_at, :correlation_id, :causation_id, :actor, :client_request_id, :headers)",
+                    named_params! {
+                        ":sequence_number": sequence_number.to_string(),
+                        ":aggregate_id": aggregate_id.to_string(),
+                        ":aggregate_type": aggregate_type,
+                        ":version": version,
+                        ":event": event_json,
+                        ":recorded_at": metadata.recorded_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+                        ":correlation_id": metadata.correlation_id,
+                        ":causation_id": metadata.causation_id.map(|id| id.to_string()),
+                        ":actor": metadata.actor,
+                        ":client_request_id": metadata.client_request_id,
+                        ":headers": headers_json,
+                    },
+                );
+
+            match inserted {
+                Ok(_) => {}
+                // Another writer claimed this version between our check and the insert, report
+                // the version it left the stream at
+                Err(e) if e.sqlite_error_code() == Some(ErrorCode::ConstraintViolation) => {
+                    return Err(EventStoreError::ConcurrencyConflict {
+                        expected: expected_version,
+                        actual: stored_version(&transaction, aggregate_id, aggregate_type)?,
+                    });
+                }
+                Err(e) => return Err(EventStoreError::EventStoreError(e.to_string())),
+            }
+
+            // Queue the event for the outbox relay as part of the same transaction
+            transaction
+                .execute(
+                    "INSERT INTO outbox (sequence_number, aggregate_id, aggregate_type, event_type, payload) VALUES (:sequence_number, :aggregate_id, :aggregate_type, :event_type, :payload)",
+                    named_params! {
+                        ":sequence_number": sequence_number.to_string(),
+                        ":aggregate_id": aggregate_id.to_string(),
+                        ":aggregate_type": aggregate_type,
+                        ":event_type": envelope.event_type(),
+                        ":payload": event_json,
+                    },
+                )
+                .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
+
+            envelopes.push(envelope);
+        }
+
+        transaction
+            .commit()
             .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
 
-        Ok(())
+        Ok(envelopes)
     }
 
     fn get_events_for_aggregate<T, E: Event<T> + Serialize + for<'de> Deserialize<'de>>(
         &self,
         aggregate_id: Ulid,
         aggregate_type: &str,
+    ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError> {
+        self.get_events_for_aggregate_since(aggregate_id, aggregate_type, 0)
+    }
+
+    fn get_events_for_aggregate_since<T, E: Event<T> + Serialize + for<'de> Deserialize<'de>>(
+        &self,
+        aggregate_id: Ulid,
+        aggregate_type: &str,
+        after_version: u64,
     ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError> {
         let conn = self.pool.get().expect("Failed to get connection");
         let mut statement = conn
-            .prepare(
-                "SELECT sequence_number, aggregate_id, aggregate_type, event
+            .prepare(&format!(
+                "SELECT {EVENT_COLUMNS}
              FROM events
              WHERE aggregate_id = :aggregate_id AND aggregate_type = :aggregate_type
-             ORDER BY sequence_number",
-            )
+             AND version > :after_version
+             ORDER BY version"
+            ))
             .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
 
         let rows = statement
@@ -97,23 +433,45 @@ impl EventStore for EventStoreSqlite {
                 named_params! {
                     ":aggregate_id": aggregate_id.to_string(),
                     ":aggregate_type": aggregate_type,
+                    ":after_version": after_version,
                 },
-                |row| {
-                    let event_json: String = row.get(3)?;
+                envelope_from_row,
+            )
+            .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
 
-                    // Deserialize just the event from the JSON string
-                    let envelope: EventEnvelope<T, E> =
-                        serde_json::from_str(&event_json).map_err(|e| {
-                            rusqlite::Error::FromSqlConversionFailure(
-                                3,
-                                rusqlite::types::Type::Text,
-                                Box::new(e),
-                            )
-                        })?;
+        let mut events = Vec::new();
+        for row in rows {
+            events.push(row.map_err(|e| EventStoreError::EventStoreError(e.to_string()))?);
+        }
+
+        Ok(events)
+    }
 
-                    // Create a new envelope with the event and metadata
-                    Ok(envelope)
+    fn get_events_for_aggregate_type<T, E: Event<T> + Serialize + for<'de> Deserialize<'de>>(
+        &self,
+        aggregate_type: &str,
+        after_sequence_number: Option<Ulid>,
+        limit: usize,
+    ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError> {
+        let conn = self.pool.get().expect("Failed to get connection");
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT {EVENT_COLUMNS}
+             FROM events
+             WHERE aggregate_type = :aggregate_type AND sequence_number > :after_sequence_number
+             ORDER BY sequence_number
+             LIMIT :limit"
+            ))
+            .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
+
+        let rows = statement
+            .query_map(
+                named_params! {
+                    ":aggregate_type": aggregate_type,
+                    ":after_sequence_number": after_sequence_number.unwrap_or(Ulid::nil()).to_string(),
+                    ":limit": limit,
                 },
+                envelope_from_row,
             )
             .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
 
@@ -130,28 +488,13 @@ impl EventStore for EventStoreSqlite {
     ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError> {
         let conn = self.pool.get().expect("Failed to get connection");
         let mut statement = conn
-            .prepare(
-                "SELECT sequence_number, aggregate_id, aggregate_type, event FROM events ORDER BY sequence_number",
-            )
+            .prepare(&format!(
+                "SELECT {EVENT_COLUMNS} FROM events ORDER BY sequence_number"
+            ))
             .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
 
         let rows = statement
-            .query_map([], |row| {
-                let event_json: String = row.get(3)?;
-
-                // Deserialize just the event from the JSON string
-                let envelope: EventEnvelope<T, E> =
-                    serde_json::from_str(&event_json).map_err(|e| {
-                        rusqlite::Error::FromSqlConversionFailure(
-                            3,
-                            rusqlite::types::Type::Text,
-                            Box::new(e),
-                        )
-                    })?;
-
-                // Create a new envelope with the event and metadata
-                Ok(envelope)
-            })
+            .query_map([], envelope_from_row)
             .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
 
         let mut events = Vec::new();
@@ -162,4 +505,473 @@ impl EventStore for EventStoreSqlite {
 
         Ok(events)
     }
+
+    fn get_aggregates(
+        &self,
+        aggregate_type: Option<&str>,
+    ) -> Result<Vec<AggregateSummary>, EventStoreError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
+        let mut statement = conn
+            .prepare(
+                "SELECT aggregate_type, aggregate_id, MAX(version), MIN(sequence_number), MAX(sequence_number)
+             FROM events
+             WHERE :aggregate_type IS NULL OR aggregate_type = :aggregate_type
+             GROUP BY aggregate_type, aggregate_id
+             ORDER BY MIN(sequence_number)",
+            )
+            .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
+
+        let rows = statement
+            .query_map(
+                named_params! { ":aggregate_type": aggregate_type },
+                aggregate_from_row,
+            )
+            .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
+
+        let mut aggregates = Vec::new();
+        for row in rows {
+            aggregates.push(row.map_err(|e| EventStoreError::EventStoreError(e.to_string()))?);
+        }
+
+        Ok(aggregates)
+    }
+
+    fn get_aggregate(
+        &self,
+        aggregate_id: Ulid,
+    ) -> Result<Option<AggregateSummary>, EventStoreError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| EventStoreError::EventStoreError(e.to_string()))?;
+
+        // IDs are ULIDs, unique across aggregate types
+        conn.query_row(
+            "SELECT aggregate_type, aggregate_id, MAX(version), MIN(sequence_number), MAX(sequence_number)
+             FROM events
+             WHERE aggregate_id = :aggregate_id
+             GROUP BY aggregate_type, aggregate_id",
+            named_params! { ":aggregate_id": aggregate_id.to_string() },
+            aggregate_from_row,
+        )
+        .optional()
+        .map_err(|e| EventStoreError::EventStoreError(e.to_string()))
+    }
+}
+
+/// Decodes an aggregate summarised by type, ID, last version and first and last sequence
+/// numbers, in that order.
+fn aggregate_from_row(row: &rusqlite::Row) -> Result<AggregateSummary, rusqlite::Error> {
+    let parse_ulid = |index: usize| {
+        row.get::<_, String>(index).and_then(|s| {
+            Ulid::from_string(&s).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    index,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })
+        })
+    };
+
+    Ok(AggregateSummary {
+        aggregate_type: row.get(0)?,
+        aggregate_id: parse_ulid(1)?,
+        version: row.get(2)?,
+        first_sequence_number: parse_ulid(3)?,
+        last_sequence_number: parse_ulid(4)?,
+    })
+}
+
+impl Outbox for EventStoreSqlite {
+    fn get_pending_messages(&self, limit: usize) -> Result<Vec<OutboxMessage>, OutboxError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| OutboxError::OutboxError(e.to_string()))?;
+        let mut statement = conn
+            .prepare(
+                "SELECT outbox.id, outbox.sequence_number, outbox.aggregate_id, outbox.aggregate_type,
+                outbox.event_type, outbox.payload, outbox.attempts,
+                events.recorded_at, events.correlation_id, events.causation_id, events.actor,
+                events.client_request_id, events.headers
+             FROM outbox
+             LEFT JOIN events ON events.sequence_number = outbox.sequence_number
+             WHERE outbox.dispatched_at IS NULL
+             ORDER BY outbox.id
+             LIMIT :limit",
+            )
+            .map_err(|e| OutboxError::OutboxError(e.to_string()))?;
+
+        let rows = statement
+            .query_map(named_params! { ":limit": limit }, |row| {
+                let parse_ulid = |index: usize| {
+                    row.get::<_, String>(index).and_then(|s| {
+                        Ulid::from_string(&s).map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                index,
+                                rusqlite::types::Type::Text,
+                                Box::new(e),
+                            )
+                        })
+                    })
+                };
+
+                Ok(OutboxMessage {
+                    id: row.get(0)?,
+                    sequence_number: parse_ulid(1)?,
+                    aggregate_id: parse_ulid(2)?,
+                    aggregate_type: row.get(3)?,
+                    event_type: row.get(4)?,
+                    payload: row.get(5)?,
+                    attempts: row.get(6)?,
+                    metadata: metadata_from_row(row, 7)?,
+                })
+            })
+            .map_err(|e| OutboxError::OutboxError(e.to_string()))?;
+
+        let mut messages = Vec::new();
+        for row in rows {
+            messages.push(row.map_err(|e| OutboxError::OutboxError(e.to_string()))?);
+        }
+
+        Ok(messages)
+    }
+
+    fn mark_dispatched(&self, id: u64) -> Result<(), OutboxError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| OutboxError::OutboxError(e.to_string()))?;
+
+        conn.execute(
+            "UPDATE outbox SET dispatched_at = CURRENT_TIMESTAMP, attempts = attempts + 1 WHERE id = :id",
+            named_params! { ":id": id },
+        )
+        .map_err(|e| OutboxError::OutboxError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn mark_failed(&self, id: u64, error: &str) -> Result<(), OutboxError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| OutboxError::OutboxError(e.to_string()))?;
+
+        conn.execute(
+            "UPDATE outbox SET attempts = attempts + 1, last_error = :error WHERE id = :id",
+            named_params! { ":id": id, ":error": error },
+        )
+        .map_err(|e| OutboxError::OutboxError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn requeue(&self, after_sequence_number: Option<Ulid>) -> Result<usize, OutboxError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| OutboxError::OutboxError(e.to_string()))?;
+
+        conn.execute(
+            "UPDATE outbox SET dispatched_at = NULL
+             WHERE dispatched_at IS NOT NULL AND sequence_number > :after_sequence_number",
+            named_params! {
+                ":after_sequence_number": after_sequence_number.unwrap_or(Ulid::nil()).to_string(),
+            },
+        )
+        .map_err(|e| OutboxError::OutboxError(e.to_string()))
+    }
+}
+
+impl SnapshotStore for EventStoreSqlite {
+    fn save_snapshot<T: Serialize>(
+        &self,
+        snapshot: &Snapshot<T>,
+    ) -> Result<(), SnapshotStoreError> {
+        let state_json = serde_json::to_string(&snapshot.state)
+            .map_err(|e| SnapshotStoreError::SnapshotStoreError(e.to_string()))?;
+
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| SnapshotStoreError::SnapshotStoreError(e.to_string()))?;
+
+        // Never replace a snapshot with an older one taken by a slower writer
+        conn.execute(
+            "INSERT INTO snapshots (aggregate_id, aggregate_type, version, state)
+             VALUES (:aggregate_id, :aggregate_type, :version, :state)
+             ON CONFLICT (aggregate_type, aggregate_id) DO UPDATE
+             SET version = excluded.version, state = excluded.state, created_at = CURRENT_TIMESTAMP
+             WHERE excluded.version > snapshots.version",
+            named_params! {
+                ":aggregate_id": snapshot.aggregate_id.to_string(),
+                ":aggregate_type": snapshot.aggregate_type,
+                ":version": snapshot.version,
+                ":state": state_json,
+            },
+        )
+        .map_err(|e| SnapshotStoreError::SnapshotStoreError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn get_snapshot<T: DeserializeOwned>(
+        &self,
+        aggregate_id: Ulid,
+        aggregate_type: &str,
+    ) -> Result<Option<Snapshot<T>>, SnapshotStoreError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| SnapshotStoreError::SnapshotStoreError(e.to_string()))?;
+
+        let row = conn
+            .query_row(
+                "SELECT version, state FROM snapshots
+                 WHERE aggregate_id = :aggregate_id AND aggregate_type = :aggregate_type",
+                named_params! {
+                    ":aggregate_id": aggregate_id.to_string(),
+                    ":aggregate_type": aggregate_type,
+                },
+                |row| Ok((row.get::<_, u64>(0)?, row.get::<_, String>(1)?)),
+            )
+            .optional()
+            .map_err(|e| SnapshotStoreError::SnapshotStoreError(e.to_string()))?;
+
+        let Some((version, state_json)) = row else {
+            return Ok(None);
+        };
+
+        let state = serde_json::from_str(&state_json)
+            .map_err(|e| SnapshotStoreError::SnapshotStoreError(e.to_string()))?;
+
+        Ok(Some(Snapshot {
+            aggregate_id,
+            aggregate_type: aggregate_type.to_string(),
+            version,
+            state,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Account;
+    use crate::account::events::{ACCOUNT_AGGREGATE_TYPE, AccountEvent, DepositEvent};
+    use crate::money::{Currency, Money};
+    use rust_decimal::Decimal;
+
+    /// Store backed by a fresh database file, so every test starts empty.
+    fn temp_store() -> (EventStoreSqlite, String) {
+        let path = std::env::temp_dir()
+            .join(format!("bankingv2-{}.db", Ulid::new()))
+            .to_string_lossy()
+            .into_owned();
+        (EventStoreSqlite::new(&path), path)
+    }
+
+    fn deposit(account_id: Ulid, amount: i64) -> AccountEvent {
+        AccountEvent::Deposited(DepositEvent {
+            account_id,
+            amount: Money::new(Decimal::from(amount), Currency::EUR).unwrap(),
+            transfer_id: None,
+        })
+    }
+
+    #[test]
+    fn conflicts_report_the_stored_version() {
+        let (event_store, path) = temp_store();
+        let account_id = Ulid::new();
+
+        event_store
+            .append_events::<Account, _>(
+                account_id,
+                ACCOUNT_AGGREGATE_TYPE,
+                0,
+                vec![deposit(account_id, 1), deposit(account_id, 2)],
+                &EventMetadata::new(),
+            )
+            .unwrap();
+
+        let stale = event_store.append_event::<Account, _>(
+            account_id,
+            ACCOUNT_AGGREGATE_TYPE,
+            1,
+            deposit(account_id, 3),
+            &EventMetadata::new(),
+        );
+
+        assert!(matches!(
+            stale,
+            Err(EventStoreError::ConcurrencyConflict {
+                expected: 1,
+                actual: 2
+            })
+        ));
+        assert_eq!(
+            event_store
+                .get_events_for_aggregate::<Account, AccountEvent>(
+                    account_id,
+                    ACCOUNT_AGGREGATE_TYPE
+                )
+                .unwrap()
+                .len(),
+            2
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn sequence_numbers_follow_events_stored_by_other_processes() {
+        let (event_store, path) = temp_store();
+        let account_id = Ulid::new();
+
+        // Another process whose clock runs a minute ahead stored an event
+        let ahead = Ulid::from_parts(
+            u64::try_from(Utc::now().timestamp_millis()).unwrap() + 60_000,
+            0,
+        );
+        event_store
+            .pool
+            .get()
+            .unwrap()
+            .execute(
+                "INSERT INTO events (sequence_number, aggregate_id, aggregate_type, version, event)
+                 VALUES (:sequence_number, :aggregate_id, :aggregate_type, 1, '{}')",
+                named_params! {
+                    ":sequence_number": ahead.to_string(),
+                    ":aggregate_id": Ulid::new().to_string(),
+                    ":aggregate_type": ACCOUNT_AGGREGATE_TYPE,
+                },
+            )
+            .unwrap();
+
+        let stored = EventStoreSqlite::new(&path)
+            .append_events::<Account, _>(
+                account_id,
+                ACCOUNT_AGGREGATE_TYPE,
+                0,
+                vec![deposit(account_id, 1), deposit(account_id, 2)],
+                &EventMetadata::new(),
+            )
+            .unwrap();
+
+        assert!(stored[0].sequence_number > ahead);
+        assert!(stored[1].sequence_number > stored[0].sequence_number);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    /// Event whose serialisation fails when `broken` is set, JSON keys must be strings.
+    #[derive(Serialize)]
+    struct FlakyEvent {
+        aggregate_id: Ulid,
+        broken: Option<std::collections::HashMap<(u8, u8), u8>>,
+    }
+
+    impl Event<()> for FlakyEvent {
+        fn aggregate_id(&self) -> Ulid {
+            self.aggregate_id
+        }
+
+        fn aggregate_type(&self) -> &str {
+            "flaky"
+        }
+
+        fn event_type(&self) -> &str {
+            "flaky"
+        }
+
+        fn apply(&self, _state: &mut ()) -> Result<(), crate::traits::event::ApplyError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failed_append_stores_none_of_its_events() {
+        let (event_store, path) = temp_store();
+        let aggregate_id = Ulid::new();
+        let event = |broken: bool| FlakyEvent {
+            aggregate_id,
+            broken: broken.then(|| [((0, 0), 0)].into()),
+        };
+
+        // The first event is inserted before the second fails to serialise
+        let result = event_store.append_events::<(), _>(
+            aggregate_id,
+            "flaky",
+            0,
+            vec![event(false), event(true)],
+            &EventMetadata::new(),
+        );
+
+        assert!(matches!(result, Err(EventStoreError::EventStoreError(_))));
+        assert!(event_store.get_aggregates(None).unwrap().is_empty());
+        assert!(event_store.get_pending_messages(10).unwrap().is_empty());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn legacy_events_are_tagged_once_on_startup() {
+        let (event_store, path) = temp_store();
+        let account_id = Ulid::new();
+        let sequence_number = Ulid::new();
+
+        // Written before events carried their type, and before the tagging migration ran
+        let legacy_json = serde_json::json!({
+            "sequence_number": sequence_number,
+            "aggregate_id": account_id,
+            "aggregate_type": ACCOUNT_AGGREGATE_TYPE,
+            "event_type": "deposit",
+            "event": { "account_id": account_id, "amount": "25" },
+        })
+        .to_string();
+        {
+            let conn = event_store.pool.get().unwrap();
+            conn.execute(
+                "INSERT INTO events (sequence_number, aggregate_id, aggregate_type, version, event)
+                 VALUES (:sequence_number, :aggregate_id, :aggregate_type, 1, :event)",
+                named_params! {
+                    ":sequence_number": sequence_number.to_string(),
+                    ":aggregate_id": account_id.to_string(),
+                    ":aggregate_type": ACCOUNT_AGGREGATE_TYPE,
+                    ":event": legacy_json,
+                },
+            )
+            .unwrap();
+            conn.pragma_update(None, "user_version", 0).unwrap();
+        }
+
+        let event_store = EventStoreSqlite::new(&path);
+        let conn = event_store.pool.get().unwrap();
+        let (stored_json, schema_version): (String, i64) = conn
+            .query_row(
+                "SELECT event, (SELECT user_version FROM pragma_user_version) FROM events",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+
+        assert_eq!(schema_version, LEGACY_EVENTS_TAGGED);
+        let stored: serde_json::Value = serde_json::from_str(&stored_json).unwrap();
+        assert_eq!(stored["event"]["event_type"], "deposit");
+
+        let envelope =
+            EventEnvelope::<Account, AccountEvent>::from_json(stored_json.as_bytes()).unwrap();
+        let AccountEvent::Deposited(deposit) = envelope.event else {
+            panic!("expected a deposit, got {:?}", envelope.event);
+        };
+        assert_eq!(deposit.amount.value(), Decimal::from(25));
+        assert_eq!(deposit.amount.currency(), Currency::EUR);
+
+        std::fs::remove_file(path).unwrap();
+    }
 }
@@ -0,0 +1,141 @@
+use std::fmt;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::account::{Account, AccountService, account_service::AccountServiceError};
+use crate::traits::{EventStore, Repository, SnapshotStore, event::EventMetadata};
+
+/// Actor recorded on the events of monthly fee runs.
+const FEE_ACTOR: &str = "monthly-fees";
+
+/// What a fee was charged for, recorded on `fee_charged` events so statements can tell fees
+/// apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeKind {
+    /// Charged once per month end while a schedule is in force.
+    MonthlyMaintenance,
+    /// Charged with every withdrawal, transfers excluded.
+    Withdrawal,
+    /// Charged with every withdrawal that leaves the account overdrawn.
+    Overdraft,
+    /// Charged with every deposit converted from another currency.
+    Fx,
+    /// Charged by hand, never by a schedule.
+    Other,
+}
+
+impl FeeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeKind::MonthlyMaintenance => "monthly_maintenance",
+            FeeKind::Withdrawal => "withdrawal",
+            FeeKind::Overdraft => "overdraft",
+            FeeKind::Fx => "fx",
+            FeeKind::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for FeeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for FeeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "monthly_maintenance" => Ok(FeeKind::MonthlyMaintenance),
+            "withdrawal" => Ok(FeeKind::Withdrawal),
+            "overdraft" => Ok(FeeKind::Overdraft),
+            "fx" => Ok(FeeKind::Fx),
+            "other" => Ok(FeeKind::Other),
+            other => Err(format!("Unknown fee kind: {}", other)),
+        }
+    }
+}
+
+/// Fees of an account product, in the account currency. A zero fee is not charged.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeeSchedule {
+    /// Name of the product the schedule belongs to.
+    pub product: String,
+    pub monthly_maintenance: Decimal,
+    pub per_withdrawal: Decimal,
+    pub overdraft_usage: Decimal,
+    pub fx: Decimal,
+}
+
+impl FeeSchedule {
+    /// The fee charged for `kind`, zero for fees only charged by hand.
+    pub fn fee(&self, kind: FeeKind) -> Decimal {
+        match kind {
+            FeeKind::MonthlyMaintenance => self.monthly_maintenance,
+            FeeKind::Withdrawal => self.per_withdrawal,
+            FeeKind::Overdraft => self.overdraft_usage,
+            FeeKind::Fx => self.fx,
+            FeeKind::Other => Decimal::ZERO,
+        }
+    }
+}
+
+/// Charges the monthly maintenance fee of every account, once per month end.
+pub struct MonthlyFeeJob<R: Repository<Account>, S: EventStore + SnapshotStore> {
+    account_service: AccountService<R, S>,
+    poll_interval: Duration,
+}
+
+impl<R: Repository<Account>, S: EventStore + SnapshotStore> MonthlyFeeJob<R, S> {
+    pub fn new(account_service: AccountService<R, S>) -> Self {
+        Self {
+            account_service,
+            poll_interval: Duration::from_secs(3600),
+        }
+    }
+
+    /// Charges every account for the month ends up to `through` it was not charged for yet,
+    /// returning the number of fees charged. An account that fails is logged and picked up
+    /// again by the next run.
+    pub fn run_once(&self, through: NaiveDate) -> Result<usize, AccountServiceError> {
+        let metadata = EventMetadata::new()
+            .with_actor(FEE_ACTOR)
+            .with_correlation_id(format!("fees-{}", through));
+        let mut charged = 0;
+
+        for account_id in self.account_service.get_account_ids()? {
+            match self
+                .account_service
+                .charge_monthly_fees(account_id, through, &metadata)
+            {
+                Ok(fees) => charged += fees,
+                Err(e) => eprintln!("Failed to charge fees to {}: {}", account_id, e),
+            }
+        }
+
+        Ok(charged)
+    }
+
+    /// Charges through the last day that has ended, checking hourly forever.
+    pub fn run(&self) {
+        loop {
+            if let Some(yesterday) = Utc::now().date_naive().pred_opt() {
+                match self.run_once(yesterday) {
+                    Ok(0) => {}
+                    Ok(charged) => println!("Charged {} monthly fees", charged),
+                    Err(e) => eprintln!("Error charging monthly fees: {}", e),
+                }
+            }
+
+            thread::sleep(self.poll_interval);
+        }
+    }
+}
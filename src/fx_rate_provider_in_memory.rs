@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::money::Currency;
+use crate::traits::{
+    FxRateProvider,
+    fx_rate_provider::{FxRate, FxRateError, check_rate, read_rates_csv, resolve_rate},
+};
+
+/// Exchange rates kept in process memory, for tests and single-process development.
+#[derive(Debug, Clone, Default)]
+pub struct FxRateProviderInMemory {
+    rates: HashMap<(Currency, Currency), Decimal>,
+}
+
+impl FxRateProviderInMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lists the rate converting one unit of `base` into `quote`.
+    pub fn with_rate(
+        mut self,
+        base: Currency,
+        quote: Currency,
+        rate: Decimal,
+    ) -> Result<Self, FxRateError> {
+        check_rate(base, quote, rate)?;
+        self.rates.insert((base, quote), rate);
+        Ok(self)
+    }
+
+    /// Loads the rates of a CSV file, see [`read_rates_csv`].
+    pub fn from_csv(path: &str) -> Result<Self, FxRateError> {
+        read_rates_csv(path)?
+            .into_iter()
+            .try_fold(Self::new(), |rates, FxRate { base, quote, rate }| {
+                rates.with_rate(base, quote, rate)
+            })
+    }
+}
+
+impl FxRateProvider for FxRateProviderInMemory {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, FxRateError> {
+        resolve_rate(from, to, |base, quote| {
+            Ok(self.rates.get(&(base, quote)).copied())
+        })
+    }
+}
@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{OptionalExtension, named_params};
+use rust_decimal::Decimal;
+
+use crate::money::Currency;
+use crate::traits::{
+    FxRateProvider,
+    fx_rate_provider::{FxRateError, check_rate, read_rates_csv, resolve_rate},
+};
+
+/// Exchange rates stored in SQLite, imported from CSV files with [`FxRateProviderSqlite::load_csv`].
+#[derive(Debug, Clone)]
+pub struct FxRateProviderSqlite {
+    pool: Pool<SqliteConnectionManager>,
+}
+
+impl FxRateProviderSqlite {
+    pub fn new(db_path: &str) -> Self {
+        let manager = SqliteConnectionManager::file(db_path);
+        let pool = Pool::new(manager).expect("Failed to create pool");
+
+        let conn = pool.get().expect("Failed to get connection");
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS fx_rates (
+                base TEXT NOT NULL,
+                quote TEXT NOT NULL,
+                rate TEXT NOT NULL,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (base, quote)
+            );",
+        )
+        .expect("Failed to create fx rates table");
+
+        Self { pool }
+    }
+
+    /// Stores the rate converting one unit of `base` into `quote`, replacing the current one.
+    pub fn set_rate(
+        &self,
+        base: Currency,
+        quote: Currency,
+        rate: Decimal,
+    ) -> Result<(), FxRateError> {
+        check_rate(base, quote, rate)?;
+
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| FxRateError::FxRateProviderError(e.to_string()))?;
+
+        conn.execute(
+            "INSERT INTO fx_rates (base, quote, rate) VALUES (:base, :quote, :rate)
+             ON CONFLICT (base, quote) DO UPDATE
+             SET rate = excluded.rate, updated_at = CURRENT_TIMESTAMP",
+            named_params! {
+                ":base": base.code(),
+                ":quote": quote.code(),
+                ":rate": rate.to_string(),
+            },
+        )
+        .map_err(|e| FxRateError::FxRateProviderError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Imports the rates of a CSV file, see [`read_rates_csv`], returning how many were stored.
+    /// Conversions already recorded keep the rate they were made at.
+    pub fn load_csv(&self, path: &str) -> Result<usize, FxRateError> {
+        let rates = read_rates_csv(path)?;
+        for rate in &rates {
+            self.set_rate(rate.base, rate.quote, rate.rate)?;
+        }
+
+        println!("Loaded {} exchange rates from {}", rates.len(), path);
+
+        Ok(rates.len())
+    }
+}
+
+impl FxRateProvider for FxRateProviderSqlite {
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, FxRateError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| FxRateError::FxRateProviderError(e.to_string()))?;
+
+        resolve_rate(from, to, |base, quote| {
+            let rate = conn
+                .query_row(
+                    "SELECT rate FROM fx_rates WHERE base = :base AND quote = :quote",
+                    named_params! { ":base": base.code(), ":quote": quote.code() },
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()
+                .map_err(|e| FxRateError::FxRateProviderError(e.to_string()))?;
+
+            rate.map(|rate| {
+                Decimal::from_str(&rate).map_err(|e| FxRateError::InvalidRate(e.to_string()))
+            })
+            .transpose()
+        })
+    }
+}
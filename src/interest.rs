@@ -0,0 +1,157 @@
+use std::fmt;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{Datelike, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::account::{Account, AccountService, account_service::AccountServiceError};
+use crate::traits::{EventStore, Repository, SnapshotStore, event::EventMetadata};
+
+/// Actor recorded on the events of interest accrual runs.
+const INTEREST_ACTOR: &str = "interest-accrual";
+
+/// Decimal places daily interest is kept to before it is posted and rounded to the currency.
+const ACCRUAL_SCALE: u32 = 10;
+
+/// Day-count convention: how many days make up the year a day's interest is a fraction of.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DayCount {
+    /// Every year has 365 days.
+    #[default]
+    #[serde(rename = "ACT/365")]
+    Act365,
+    /// Every year has 360 days.
+    #[serde(rename = "ACT/360")]
+    Act360,
+    /// Years have their actual length, 366 days in leap years.
+    #[serde(rename = "ACT/ACT")]
+    ActAct,
+}
+
+impl DayCount {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DayCount::Act365 => "ACT/365",
+            DayCount::Act360 => "ACT/360",
+            DayCount::ActAct => "ACT/ACT",
+        }
+    }
+
+    pub fn days_in_year(&self, day: NaiveDate) -> u32 {
+        match self {
+            DayCount::Act365 => 365,
+            DayCount::Act360 => 360,
+            DayCount::ActAct if day.leap_year() => 366,
+            DayCount::ActAct => 365,
+        }
+    }
+
+    /// Interest earned on `balance` over `day` at `annual_rate`. Nothing is earned on a balance
+    /// that is not positive.
+    pub fn daily_interest(
+        &self,
+        balance: Decimal,
+        annual_rate: Decimal,
+        day: NaiveDate,
+    ) -> Decimal {
+        if balance <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+
+        (balance * annual_rate / Decimal::from(self.days_in_year(day))).round_dp(ACCRUAL_SCALE)
+    }
+}
+
+impl fmt::Display for DayCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DayCount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "ACT/365" => Ok(DayCount::Act365),
+            "ACT/360" => Ok(DayCount::Act360),
+            "ACT/ACT" => Ok(DayCount::ActAct),
+            other => Err(format!("Unknown day-count convention: {}", other)),
+        }
+    }
+}
+
+/// Whether `day` is the last day of its month, the day accrued interest is posted on.
+pub fn is_month_end(day: NaiveDate) -> bool {
+    day.succ_opt()
+        .is_none_or(|next| next.month() != day.month())
+}
+
+/// The last month end on or before `day`.
+pub fn last_month_end(day: NaiveDate) -> NaiveDate {
+    if is_month_end(day) {
+        return day;
+    }
+    day.with_day(1)
+        .and_then(|first| first.pred_opt())
+        .unwrap_or(NaiveDate::MIN)
+}
+
+/// Accrues and posts interest for every account, daily.
+pub struct InterestAccrualJob<R: Repository<Account>, S: EventStore + SnapshotStore> {
+    account_service: AccountService<R, S>,
+    day_count: DayCount,
+    poll_interval: Duration,
+}
+
+impl<R: Repository<Account>, S: EventStore + SnapshotStore> InterestAccrualJob<R, S> {
+    pub fn new(account_service: AccountService<R, S>, day_count: DayCount) -> Self {
+        Self {
+            account_service,
+            day_count,
+            poll_interval: Duration::from_secs(3600),
+        }
+    }
+
+    /// Accrues every account through `through`, returning the number of days accrued over all
+    /// of them. An account that fails is logged and picked up again by the next run.
+    pub fn run_once(&self, through: NaiveDate) -> Result<usize, AccountServiceError> {
+        let metadata = EventMetadata::new()
+            .with_actor(INTEREST_ACTOR)
+            .with_correlation_id(format!("interest-{}", through));
+        let mut accrued = 0;
+
+        for account_id in self.account_service.get_account_ids()? {
+            match self.account_service.accrue_interest(
+                account_id,
+                through,
+                self.day_count,
+                &metadata,
+            ) {
+                Ok(days) => accrued += days,
+                Err(e) => eprintln!("Failed to accrue interest for {}: {}", account_id, e),
+            }
+        }
+
+        Ok(accrued)
+    }
+
+    /// Accrues through the last day that has ended, checking hourly forever. Runs after the
+    /// first of a day find nothing left to accrue.
+    pub fn run(&self) {
+        loop {
+            if let Some(yesterday) = Utc::now().date_naive().pred_opt() {
+                match self.run_once(yesterday) {
+                    Ok(0) => {}
+                    Ok(accrued) => println!("Accrued {} days of interest", accrued),
+                    Err(e) => eprintln!("Error accruing interest: {}", e),
+                }
+            }
+
+            thread::sleep(self.poll_interval);
+        }
+    }
+}
@@ -0,0 +1,292 @@
+pub mod ledger_handler;
+pub mod repositories;
+
+pub use ledger_handler::LedgerHandler;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::money::Currency;
+
+#[derive(Debug, Error)]
+pub enum LedgerError {
+    #[error("Unbalanced: {0}")]
+    Unbalanced(String),
+    #[error("Unknown ledger account: {0}")]
+    UnknownAccount(String),
+}
+
+/// Kind of a ledger account, deciding on which side its balance normally grows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountType {
+    Asset,
+    Liability,
+    Income,
+    Expense,
+}
+
+/// Chart of accounts. Every customer account has a deposits account of its own, the rest are
+/// internal accounts of the bank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LedgerAccount {
+    /// Money held by the bank.
+    Cash,
+    /// Currency bought and sold converting deposits, one position per currency.
+    FxPosition,
+    /// What the bank owes a customer account.
+    CustomerDeposits(Ulid),
+    /// Interest accrued but not yet posted to customer accounts.
+    InterestPayable,
+    /// Reversed conversions waiting to be refunded in their source currency.
+    Suspense,
+    FeeIncome,
+    /// What the spread earned on conversions.
+    FxIncome,
+    InterestExpense,
+}
+
+impl LedgerAccount {
+    /// Code the account is stored and listed under, customer deposits are suffixed with the
+    /// customer account's ID.
+    pub fn code(&self) -> String {
+        match self {
+            LedgerAccount::Cash => "1000".to_string(),
+            LedgerAccount::FxPosition => "1100".to_string(),
+            LedgerAccount::CustomerDeposits(account_id) => format!("2000:{}", account_id),
+            LedgerAccount::InterestPayable => "2100".to_string(),
+            LedgerAccount::Suspense => "2900".to_string(),
+            LedgerAccount::FeeIncome => "4000".to_string(),
+            LedgerAccount::FxIncome => "4100".to_string(),
+            LedgerAccount::InterestExpense => "5000".to_string(),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LedgerAccount::Cash => "Cash",
+            LedgerAccount::FxPosition => "FX position",
+            LedgerAccount::CustomerDeposits(_) => "Customer deposits",
+            LedgerAccount::InterestPayable => "Interest payable",
+            LedgerAccount::Suspense => "Suspense",
+            LedgerAccount::FeeIncome => "Fee income",
+            LedgerAccount::FxIncome => "FX income",
+            LedgerAccount::InterestExpense => "Interest expense",
+        }
+    }
+
+    pub fn account_type(&self) -> AccountType {
+        match self {
+            LedgerAccount::Cash | LedgerAccount::FxPosition => AccountType::Asset,
+            LedgerAccount::CustomerDeposits(_)
+            | LedgerAccount::InterestPayable
+            | LedgerAccount::Suspense => AccountType::Liability,
+            LedgerAccount::FeeIncome | LedgerAccount::FxIncome => AccountType::Income,
+            LedgerAccount::InterestExpense => AccountType::Expense,
+        }
+    }
+}
+
+impl fmt::Display for LedgerAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.code())
+    }
+}
+
+impl FromStr for LedgerAccount {
+    type Err = LedgerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1000" => Ok(LedgerAccount::Cash),
+            "1100" => Ok(LedgerAccount::FxPosition),
+            "2100" => Ok(LedgerAccount::InterestPayable),
+            "2900" => Ok(LedgerAccount::Suspense),
+            "4000" => Ok(LedgerAccount::FeeIncome),
+            "4100" => Ok(LedgerAccount::FxIncome),
+            "5000" => Ok(LedgerAccount::InterestExpense),
+            other => other
+                .strip_prefix("2000:")
+                .and_then(|account_id| Ulid::from_string(account_id).ok())
+                .map(LedgerAccount::CustomerDeposits)
+                .ok_or_else(|| LedgerError::UnknownAccount(other.to_string())),
+        }
+    }
+}
+
+impl Serialize for LedgerAccount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for LedgerAccount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Debit,
+    Credit,
+}
+
+impl Side {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Side::Debit => "debit",
+            Side::Credit => "credit",
+        }
+    }
+}
+
+impl FromStr for Side {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "debit" => Ok(Side::Debit),
+            "credit" => Ok(Side::Credit),
+            other => Err(format!("Unknown side: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalLine {
+    pub ledger_account: LedgerAccount,
+    pub side: Side,
+    pub amount: Decimal,
+    pub currency: Currency,
+}
+
+/// Lines posted for one account event, identified by the event's sequence number.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub sequence_number: Ulid,
+    pub account_id: Ulid,
+    pub event_type: String,
+    pub posted_at: DateTime<Utc>,
+    pub description: String,
+    pub lines: Vec<JournalLine>,
+}
+
+impl JournalEntry {
+    /// Checks that the lines balance in every currency they are in.
+    pub fn new(
+        sequence_number: Ulid,
+        account_id: Ulid,
+        event_type: &str,
+        posted_at: DateTime<Utc>,
+        description: String,
+        lines: Vec<JournalLine>,
+    ) -> Result<Self, LedgerError> {
+        let entry = Self {
+            sequence_number,
+            account_id,
+            event_type: event_type.to_string(),
+            posted_at,
+            description,
+            lines,
+        };
+
+        let balance = TrialBalance::from_lines(&entry.lines);
+        if !balance.is_balanced() {
+            return Err(LedgerError::Unbalanced(format!(
+                "Entry for event {} ({}) does not balance",
+                sequence_number, event_type
+            )));
+        }
+
+        Ok(entry)
+    }
+}
+
+/// Debits and credits posted to one ledger account in one currency.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrialBalanceRow {
+    pub ledger_account: LedgerAccount,
+    pub name: &'static str,
+    pub account_type: AccountType,
+    pub currency: Currency,
+    pub debits: Decimal,
+    pub credits: Decimal,
+}
+
+/// Totals of every ledger account, in the order of the chart of accounts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TrialBalance {
+    pub rows: Vec<TrialBalanceRow>,
+}
+
+impl TrialBalance {
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a JournalLine>) -> Self {
+        let mut totals: BTreeMap<(LedgerAccount, Currency), (Decimal, Decimal)> = BTreeMap::new();
+        for line in lines {
+            let (debits, credits) = totals
+                .entry((line.ledger_account, line.currency))
+                .or_default();
+            match line.side {
+                Side::Debit => *debits += line.amount,
+                Side::Credit => *credits += line.amount,
+            }
+        }
+
+        Self {
+            rows: totals
+                .into_iter()
+                .map(
+                    |((ledger_account, currency), (debits, credits))| TrialBalanceRow {
+                        ledger_account,
+                        name: ledger_account.name(),
+                        account_type: ledger_account.account_type(),
+                        currency,
+                        debits,
+                        credits,
+                    },
+                )
+                .collect(),
+        }
+    }
+
+    /// Total debits and credits per currency.
+    pub fn totals(&self) -> BTreeMap<Currency, (Decimal, Decimal)> {
+        let mut totals: BTreeMap<Currency, (Decimal, Decimal)> = BTreeMap::new();
+        for row in &self.rows {
+            let (debits, credits) = totals.entry(row.currency).or_default();
+            *debits += row.debits;
+            *credits += row.credits;
+        }
+        totals
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        self.totals()
+            .values()
+            .all(|(debits, credits)| debits == credits)
+    }
+
+    /// Fails unless debits equal credits in every currency.
+    pub fn assert_balanced(&self) -> Result<(), LedgerError> {
+        for (currency, (debits, credits)) in self.totals() {
+            if debits != credits {
+                return Err(LedgerError::Unbalanced(format!(
+                    "{} debits of {} do not equal credits of {}",
+                    currency, debits, credits
+                )));
+            }
+        }
+        Ok(())
+    }
+}
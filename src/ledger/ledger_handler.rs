@@ -0,0 +1,541 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use rust_decimal::Decimal;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::account::Account;
+use crate::account::events::{ACCOUNT_AGGREGATE_TYPE, AccountEvent};
+use crate::money::{Currency, Money};
+use crate::traits::event_bus::EventBusError;
+use crate::traits::event_store::EventStoreError;
+use crate::traits::{
+    EventBus, EventStore, Ledger, Projection, SnapshotStore,
+    event::{ApplyError, EventEnvelope},
+    repository::RepositoryError,
+    snapshot_store::SnapshotStoreError,
+};
+
+use super::{JournalEntry, JournalLine, LedgerAccount, LedgerError, Side};
+
+/// Number of events read from the event store at a time while catching up.
+const CATCH_UP_BATCH_SIZE: usize = 500;
+
+#[derive(Debug, Error)]
+pub enum LedgerHandlerError {
+    #[error("Ledger error: {0}")]
+    LedgerError(#[from] LedgerError),
+
+    #[error("Apply error: {0}")]
+    ApplyError(#[from] ApplyError),
+
+    #[error("Repository error: {0}")]
+    RepositoryError(#[from] RepositoryError),
+
+    #[error("Event bus error: {0}")]
+    EventBusError(#[from] EventBusError),
+
+    #[error("Event store error: {0}")]
+    EventStoreError(#[from] EventStoreError),
+
+    #[error("Snapshot store error: {0}")]
+    SnapshotStoreError(#[from] SnapshotStoreError),
+}
+
+/// Posts a balanced journal entry for every account event that moves money.
+pub struct LedgerHandler<
+    L: Ledger + Projection + Send + Sync + Clone + 'static,
+    B: EventBus + Send + Sync + Clone + 'static,
+    S: EventStore + SnapshotStore + Send + Sync + Clone + 'static,
+> {
+    ledger: L,
+    event_bus: B,
+    event_store: S,
+    /// Currency of every account seen, learnt from its opening so that accruals, which carry
+    /// none, need not load the account.
+    currencies: Arc<Mutex<HashMap<Ulid, Currency>>>,
+}
+
+impl<
+    L: Ledger + Projection + Send + Sync + Clone + 'static,
+    B: EventBus + Send + Sync + Clone + 'static,
+    S: EventStore + SnapshotStore + Send + Sync + Clone + 'static,
+> LedgerHandler<L, B, S>
+{
+    pub fn new(ledger: L, event_bus: B, event_store: S) -> Self {
+        Self {
+            ledger,
+            event_bus,
+            event_store,
+            currencies: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn listen(&self) {
+        let ledger = self.ledger.clone();
+        let event_bus = self.event_bus.clone();
+        let event_store = self.event_store.clone();
+        let currencies = self.currencies.clone();
+
+        self.event_bus.subscribe(
+            "ledger",
+            ACCOUNT_AGGREGATE_TYPE,
+            Box::new(move |envelope: EventEnvelope<Account, AccountEvent>| {
+                let handler = LedgerHandler {
+                    ledger: ledger.clone(),
+                    event_bus: event_bus.clone(),
+                    event_store: event_store.clone(),
+                    currencies: currencies.clone(),
+                };
+                handler
+                    .handle(envelope)
+                    .map_err(|e| EventBusError::HandleError(e.to_string()))
+            }),
+        );
+    }
+
+    /// Posts an event delivered by the bus. The stored events between the checkpoint and it
+    /// are posted first, so an entry the bus gave up on is retried rather than left out of
+    /// the books.
+    pub fn handle(
+        &self,
+        envelope: EventEnvelope<Account, AccountEvent>,
+    ) -> Result<(), LedgerHandlerError> {
+        self.catch_up_before(Some(envelope.sequence_number()))?;
+        self.post(envelope)
+    }
+
+    /// Posts the entry for an event and advances the ledger's checkpoint. Events at or before
+    /// the checkpoint were already posted and are skipped, which makes redelivery harmless.
+    fn post(
+        &self,
+        envelope: EventEnvelope<Account, AccountEvent>,
+    ) -> Result<(), LedgerHandlerError> {
+        let sequence_number = envelope.sequence_number();
+        if self
+            .ledger
+            .get_checkpoint()?
+            .is_some_and(|checkpoint| sequence_number <= checkpoint)
+        {
+            return Ok(());
+        }
+
+        if let Some(entry) = self.journal_entry(&envelope)? {
+            self.ledger.post(entry)?;
+        }
+
+        self.ledger.save_checkpoint(sequence_number)?;
+        Ok(())
+    }
+
+    /// Posts every stored event the ledger has not seen yet, returning how many were handled.
+    pub fn catch_up(&self) -> Result<usize, LedgerHandlerError> {
+        self.catch_up_before(None)
+    }
+
+    /// Posts the stored events after the checkpoint, those before `before` only when set.
+    fn catch_up_before(&self, before: Option<Ulid>) -> Result<usize, LedgerHandlerError> {
+        let mut handled = 0;
+
+        loop {
+            let checkpoint = self.ledger.get_checkpoint()?;
+            let envelopes = self
+                .event_store
+                .get_events_for_aggregate_type::<Account, AccountEvent>(
+                    ACCOUNT_AGGREGATE_TYPE,
+                    checkpoint,
+                    CATCH_UP_BATCH_SIZE,
+                )?;
+
+            if envelopes.is_empty() {
+                return Ok(handled);
+            }
+
+            for envelope in envelopes {
+                if before.is_some_and(|before| envelope.sequence_number() >= before) {
+                    return Ok(handled);
+                }
+                self.post(envelope)?;
+                handled += 1;
+            }
+        }
+    }
+
+    /// Drops the ledger and reposts the complete event history into it.
+    pub fn rebuild(&self) -> Result<usize, LedgerHandlerError> {
+        self.ledger.reset()?;
+        self.catch_up()
+    }
+
+    /// The entry an event is booked with, `None` for events that move no money.
+    pub fn journal_entry(
+        &self,
+        envelope: &EventEnvelope<Account, AccountEvent>,
+    ) -> Result<Option<JournalEntry>, LedgerHandlerError> {
+        let account_id = envelope.aggregate_id;
+        let customer = LedgerAccount::CustomerDeposits(account_id);
+
+        let (description, lines) = match &envelope.event {
+            AccountEvent::Opened(event) => {
+                self.currencies()
+                    .insert(account_id, event.balance.currency());
+                (
+                    "Opening balance".to_string(),
+                    transfer(LedgerAccount::Cash, customer, event.balance),
+                )
+            }
+            AccountEvent::Deposited(event) => (
+                "Deposit".to_string(),
+                transfer(LedgerAccount::Cash, customer, event.amount),
+            ),
+            AccountEvent::Withdrawn(event) => (
+                "Withdrawal".to_string(),
+                transfer(customer, LedgerAccount::Cash, event.amount),
+            ),
+            AccountEvent::HoldCaptured(event) => (
+                format!("Capture of hold {}", event.hold_id),
+                transfer(customer, LedgerAccount::Cash, event.amount),
+            ),
+            // Cash comes in the source currency and is sold for the account currency, the bank
+            // keeps the fee out of what it bought
+            AccountEvent::ConvertedDeposit(event) => {
+                let currency = event.amount.currency();
+                let mut lines =
+                    transfer(LedgerAccount::Cash, LedgerAccount::FxPosition, event.source);
+                lines.extend(line(
+                    LedgerAccount::FxPosition,
+                    Side::Debit,
+                    event.amount.value() + event.fee.value(),
+                    currency,
+                ));
+                lines.extend(line(customer, Side::Credit, event.amount.value(), currency));
+                lines.extend(line(
+                    LedgerAccount::FxIncome,
+                    Side::Credit,
+                    event.fee.value(),
+                    currency,
+                ));
+                (format!("Deposit converted from {}", event.source), lines)
+            }
+            AccountEvent::InterestAccrued(event) => {
+                let currency = self.currency(account_id)?;
+                let mut lines = line(
+                    LedgerAccount::InterestExpense,
+                    Side::Debit,
+                    event.amount,
+                    currency,
+                );
+                lines.extend(line(
+                    LedgerAccount::InterestPayable,
+                    Side::Credit,
+                    event.amount,
+                    currency,
+                ));
+                (format!("Interest accrued on {}", event.day), lines)
+            }
+            AccountEvent::InterestPosted(event) => (
+                format!("Interest posted on {}", event.posted_on),
+                transfer(LedgerAccount::InterestPayable, customer, event.amount),
+            ),
+            AccountEvent::FeeCharged(event) => (
+                format!("{} fee {}", event.kind, event.fee_id),
+                transfer(customer, LedgerAccount::FeeIncome, event.amount),
+            ),
+            AccountEvent::FeeWaived(event) => (
+                format!("Waiver of fee {}: {}", event.fee_id, event.reason),
+                transfer(LedgerAccount::FeeIncome, customer, event.amount),
+            ),
+            // A reversed conversion owes the customer the source currency, which is left in
+            // suspense until it is refunded
+            AccountEvent::TransactionReversed(event) => {
+                let lines = match event.reversed_event_type.as_str() {
+                    "deposit" => transfer(customer, LedgerAccount::Cash, event.amount),
+                    "converted_deposit" => {
+                        transfer(customer, LedgerAccount::Suspense, event.amount)
+                    }
+                    _ => transfer(LedgerAccount::Cash, customer, event.amount),
+                };
+                (
+                    format!(
+                        "Reversal of {}: {}",
+                        event.reversed_sequence_number, event.reason
+                    ),
+                    lines,
+                )
+            }
+            AccountEvent::Closed(_)
+            | AccountEvent::Frozen(_)
+            | AccountEvent::Unfrozen(_)
+            | AccountEvent::OverdraftLimitChanged(_)
+            | AccountEvent::WithdrawalLimitsChanged(_)
+            | AccountEvent::HoldPlaced(_)
+            | AccountEvent::HoldReleased(_)
+            | AccountEvent::InterestRateChanged(_)
+            | AccountEvent::FeeScheduleChanged(_) => return Ok(None),
+        };
+
+        if lines.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(JournalEntry::new(
+            envelope.sequence_number(),
+            account_id,
+            envelope.event_type(),
+            envelope.metadata.recorded_at,
+            description,
+            lines,
+        )?))
+    }
+
+    /// The account's currency, loading the account only if its opening was not seen.
+    fn currency(&self, account_id: Ulid) -> Result<Currency, LedgerHandlerError> {
+        if let Some(currency) = self.currencies().get(&account_id) {
+            return Ok(*currency);
+        }
+
+        let (account, _) = Account::load::<_, LedgerHandlerError>(&self.event_store, account_id)?;
+        self.currencies().insert(account_id, account.currency);
+        Ok(account.currency)
+    }
+
+    fn currencies(&self) -> MutexGuard<'_, HashMap<Ulid, Currency>> {
+        self.currencies.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A line for `amount`, none when it is zero.
+fn line(
+    ledger_account: LedgerAccount,
+    side: Side,
+    amount: Decimal,
+    currency: Currency,
+) -> Vec<JournalLine> {
+    if amount.is_zero() {
+        return Vec::new();
+    }
+
+    vec![JournalLine {
+        ledger_account,
+        side,
+        amount,
+        currency,
+    }]
+}
+
+/// Debits `debit` and credits `credit` with `amount`.
+fn transfer(debit: LedgerAccount, credit: LedgerAccount, amount: Money) -> Vec<JournalLine> {
+    let mut lines = line(debit, Side::Debit, amount.value(), amount.currency());
+    lines.extend(line(
+        credit,
+        Side::Credit,
+        amount.value(),
+        amount.currency(),
+    ));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::AccountService;
+    use crate::account::repositories::AccountRepositoryInMemory;
+    use crate::event_bus_in_memory::EventBusInMemory;
+    use crate::event_store_in_memory::EventStoreInMemory;
+    use crate::fee::FeeKind;
+    use crate::interest::DayCount;
+    use crate::ledger::TrialBalance;
+    use crate::ledger::repositories::LedgerRepositoryInMemory;
+    use crate::traits::event::EventMetadata;
+
+    /// Ledger failing to post the next `failing_posts` entries.
+    #[derive(Clone, Default)]
+    struct FlakyLedger {
+        inner: LedgerRepositoryInMemory,
+        failing_posts: Arc<Mutex<u32>>,
+    }
+
+    impl Ledger for FlakyLedger {
+        fn post(&self, entry: JournalEntry) -> Result<(), RepositoryError> {
+            let mut failing = self.failing_posts.lock().unwrap();
+            if *failing > 0 {
+                *failing -= 1;
+                return Err(RepositoryError::RepositoryError(
+                    "database is locked".to_string(),
+                ));
+            }
+            self.inner.post(entry)
+        }
+
+        fn get_entries(&self, account_id: Ulid) -> Result<Vec<JournalEntry>, RepositoryError> {
+            self.inner.get_entries(account_id)
+        }
+
+        fn trial_balance(&self) -> Result<TrialBalance, RepositoryError> {
+            self.inner.trial_balance()
+        }
+    }
+
+    impl Projection for FlakyLedger {
+        fn reset(&self) -> Result<(), RepositoryError> {
+            self.inner.reset()
+        }
+
+        fn get_checkpoint(&self) -> Result<Option<Ulid>, RepositoryError> {
+            self.inner.get_checkpoint()
+        }
+
+        fn save_checkpoint(&self, sequence_number: Ulid) -> Result<(), RepositoryError> {
+            self.inner.save_checkpoint(sequence_number)
+        }
+    }
+
+    #[test]
+    fn every_money_movement_is_posted_balanced() {
+        let event_store = EventStoreInMemory::new();
+        let ledger = LedgerRepositoryInMemory::new();
+        let handler =
+            LedgerHandler::new(ledger.clone(), EventBusInMemory::new(), event_store.clone());
+        let service = AccountService::new(AccountRepositoryInMemory::new(), event_store.clone());
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(100), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .deposit(account_id, Decimal::from(50), Currency::EUR, &metadata)
+            .unwrap();
+        service
+            .withdraw(account_id, Decimal::from(30), Currency::EUR, &metadata)
+            .unwrap();
+        let fee_id = service
+            .charge_fee(
+                account_id,
+                FeeKind::Other,
+                Decimal::from(5),
+                Currency::EUR,
+                &metadata,
+            )
+            .unwrap();
+        service
+            .waive_fee(account_id, fee_id, "goodwill", &metadata)
+            .unwrap();
+        service
+            .charge_fee(
+                account_id,
+                FeeKind::Other,
+                Decimal::from(2),
+                Currency::EUR,
+                &metadata,
+            )
+            .unwrap();
+        service
+            .set_interest_rate(account_id, Decimal::new(5, 2), &metadata)
+            .unwrap();
+
+        // Changing the interest rate moves no money
+        assert_eq!(handler.catch_up().unwrap(), 7);
+        assert_eq!(ledger.get_entries(account_id).unwrap().len(), 6);
+
+        let trial_balance = ledger.trial_balance().unwrap();
+        trial_balance.assert_balanced().unwrap();
+        let customer = trial_balance
+            .rows
+            .iter()
+            .find(|row| row.ledger_account == LedgerAccount::CustomerDeposits(account_id))
+            .unwrap();
+        assert_eq!(customer.credits - customer.debits, Decimal::from(118));
+        let (account, _) =
+            Account::load::<_, LedgerHandlerError>(&event_store, account_id).unwrap();
+        assert_eq!(account.balance, Decimal::from(118));
+
+        assert_eq!(handler.rebuild().unwrap(), 7);
+        assert_eq!(ledger.trial_balance().unwrap(), trial_balance);
+    }
+
+    #[test]
+    fn accruals_are_booked_in_the_currency_the_account_opened_in() {
+        let day = chrono::NaiveDate::from_ymd_opt(2026, 3, 20).unwrap();
+        let event_store = EventStoreInMemory::new()
+            .with_recorded_at(day.and_hms_opt(10, 0, 0).unwrap().and_utc());
+        let service = AccountService::new(AccountRepositoryInMemory::new(), event_store.clone());
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(1000), Currency::USD, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .set_interest_rate(account_id, Decimal::new(365, 4), &metadata)
+            .unwrap();
+        service
+            .accrue_interest(account_id, day, DayCount::Act365, &metadata)
+            .unwrap();
+
+        // A store without the account: the currency can only come from the opening
+        let ledger = LedgerRepositoryInMemory::new();
+        let handler = LedgerHandler::new(
+            ledger.clone(),
+            EventBusInMemory::new(),
+            EventStoreInMemory::new(),
+        );
+        for envelope in service.get_account_events(account_id).unwrap() {
+            handler.handle(envelope).unwrap();
+        }
+
+        let entries = ledger.get_entries(account_id).unwrap();
+        let accrual = entries
+            .iter()
+            .find(|entry| entry.event_type == "interest_accrued")
+            .unwrap();
+        assert!(
+            accrual
+                .lines
+                .iter()
+                .all(|line| line.currency == Currency::USD)
+        );
+    }
+
+    #[test]
+    fn entries_the_bus_gave_up_on_are_posted_before_later_ones() {
+        let event_store = EventStoreInMemory::new();
+        let ledger = FlakyLedger::default();
+        let handler =
+            LedgerHandler::new(ledger.clone(), EventBusInMemory::new(), event_store.clone());
+        let service = AccountService::new(AccountRepositoryInMemory::new(), event_store);
+        let metadata = EventMetadata::new();
+
+        let account_id = service
+            .create_account(Decimal::from(10), Currency::EUR, &metadata)
+            .unwrap()
+            .account_id
+            .unwrap();
+        service
+            .deposit(account_id, Decimal::from(5), Currency::EUR, &metadata)
+            .unwrap();
+        service
+            .withdraw(account_id, Decimal::from(3), Currency::EUR, &metadata)
+            .unwrap();
+        let history = service.get_account_events(account_id).unwrap();
+        let [opened, deposited, withdrawn] = <[_; 3]>::try_from(history).unwrap();
+
+        handler.handle(opened).unwrap();
+        *ledger.failing_posts.lock().unwrap() = 1;
+        assert!(handler.handle(deposited).is_err());
+
+        // The bus moved on, the withdrawal brings the deposit along
+        handler.handle(withdrawn).unwrap();
+        handler.catch_up().unwrap();
+        let event_types: Vec<String> = ledger
+            .get_entries(account_id)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.event_type)
+            .collect();
+        assert_eq!(event_types, ["account_opened", "deposit", "withdraw"]);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod ledger_repository_in_memory;
+pub mod ledger_repository_sqlite;
+
+pub use ledger_repository_in_memory::LedgerRepositoryInMemory;
+pub use ledger_repository_sqlite::LedgerRepositorySqlite;
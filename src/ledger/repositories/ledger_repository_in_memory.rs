@@ -0,0 +1,75 @@
+use crate::{
+    ledger::{JournalEntry, TrialBalance},
+    traits::{Ledger, Projection, repository::RepositoryError},
+};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use ulid::Ulid;
+
+/// Ledger kept in process memory, for tests and single-process development. Clones share the
+/// same underlying ledger.
+#[derive(Debug, Clone, Default)]
+pub struct LedgerRepositoryInMemory {
+    entries: Arc<Mutex<BTreeMap<Ulid, JournalEntry>>>,
+    checkpoint: Arc<Mutex<Option<Ulid>>>,
+}
+
+impl LedgerRepositoryInMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn save_checkpoint_value(&self, checkpoint: Option<Ulid>) -> Result<(), RepositoryError> {
+        *self
+            .checkpoint
+            .lock()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))? = checkpoint;
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, BTreeMap<Ulid, JournalEntry>>, RepositoryError> {
+        self.entries
+            .lock()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))
+    }
+}
+
+impl Ledger for LedgerRepositoryInMemory {
+    fn post(&self, entry: JournalEntry) -> Result<(), RepositoryError> {
+        self.lock()?.entry(entry.sequence_number).or_insert(entry);
+        Ok(())
+    }
+
+    fn get_entries(&self, account_id: Ulid) -> Result<Vec<JournalEntry>, RepositoryError> {
+        Ok(self
+            .lock()?
+            .values()
+            .filter(|entry| entry.account_id == account_id)
+            .cloned()
+            .collect())
+    }
+
+    fn trial_balance(&self) -> Result<TrialBalance, RepositoryError> {
+        Ok(TrialBalance::from_lines(
+            self.lock()?.values().flat_map(|entry| &entry.lines),
+        ))
+    }
+}
+
+impl Projection for LedgerRepositoryInMemory {
+    fn reset(&self) -> Result<(), RepositoryError> {
+        self.lock()?.clear();
+        self.save_checkpoint_value(None)
+    }
+
+    fn get_checkpoint(&self) -> Result<Option<Ulid>, RepositoryError> {
+        self.checkpoint
+            .lock()
+            .map(|checkpoint| *checkpoint)
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))
+    }
+
+    fn save_checkpoint(&self, sequence_number: Ulid) -> Result<(), RepositoryError> {
+        self.save_checkpoint_value(Some(sequence_number))
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{
+    ledger::{JournalEntry, JournalLine, TrialBalance},
+    money::CurrencyError,
+    traits::{Ledger, Projection, repository::RepositoryError},
+};
+use chrono::{DateTime, Utc};
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{OptionalExtension, named_params};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use ulid::Ulid;
+
+/// Name the ledger's checkpoint is stored under.
+const PROJECTION_NAME: &str = "ledger";
+
+/// One journal entry per account event, keyed by the event's sequence number.
+const CREATE_LEDGER_ENTRIES_TABLE: &str = "CREATE TABLE IF NOT EXISTS ledger_entries (
+    sequence_number TEXT PRIMARY KEY NOT NULL,
+    account_id TEXT NOT NULL,
+    event_type TEXT NOT NULL,
+    description TEXT NOT NULL,
+    posted_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS ledger_entries_account_id ON ledger_entries (account_id);";
+
+/// Debit and credit lines of the journal entries, in the order they were posted in.
+const CREATE_LEDGER_LINES_TABLE: &str = "CREATE TABLE IF NOT EXISTS ledger_lines (
+    sequence_number TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    ledger_account TEXT NOT NULL,
+    side TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    PRIMARY KEY (sequence_number, line)
+);";
+
+#[derive(Debug, Clone)]
+pub struct LedgerRepositorySqlite {
+    pool: Pool<SqliteConnectionManager>,
+}
+
+impl LedgerRepositorySqlite {
+    pub fn new(db_path: &str) -> Self {
+        let manager = SqliteConnectionManager::file(db_path);
+        let pool = Pool::new(manager).expect("Failed to create pool");
+
+        // Apply migrations
+        let conn = pool.get().expect("Failed to get connection");
+        conn.execute_batch(CREATE_LEDGER_ENTRIES_TABLE)
+            .expect("Failed to create ledger entries table");
+        conn.execute_batch(CREATE_LEDGER_LINES_TABLE)
+            .expect("Failed to create ledger lines table");
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS projection_checkpoints (
+                projection TEXT PRIMARY KEY NOT NULL,
+                sequence_number TEXT NOT NULL,
+                updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            );",
+        )
+        .expect("Failed to create projection checkpoints table");
+
+        Self { pool }
+    }
+
+    /// Lines of the entries matching `condition`, keyed by the sequence number of their entry.
+    fn get_lines(
+        conn: &rusqlite::Connection,
+        condition: &str,
+        params: &[(&str, &dyn rusqlite::ToSql)],
+    ) -> Result<Vec<(Ulid, JournalLine)>, RepositoryError> {
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT ledger_lines.sequence_number, ledger_account, side, amount, currency
+                FROM ledger_lines
+                JOIN ledger_entries USING (sequence_number)
+                WHERE {condition}
+                ORDER BY ledger_lines.sequence_number, line"
+            ))
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        let rows = statement
+            .query_map(params, |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                ))
+            })
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        rows.map(|row| {
+            let (sequence_number, ledger_account, side, amount, currency) =
+                row.map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+            let invalid = |e: &dyn std::fmt::Display| {
+                RepositoryError::RepositoryError(format!(
+                    "Invalid ledger line of {}: {}",
+                    sequence_number, e
+                ))
+            };
+
+            Ok((
+                Ulid::from_string(&sequence_number).map_err(|e| invalid(&e))?,
+                JournalLine {
+                    ledger_account: ledger_account.parse().map_err(|e| invalid(&e))?,
+                    side: side.parse().map_err(|e: String| invalid(&e))?,
+                    amount: Decimal::from_str(&amount).map_err(|e| invalid(&e))?,
+                    currency: currency.parse().map_err(|e: CurrencyError| invalid(&e))?,
+                },
+            ))
+        })
+        .collect()
+    }
+}
+
+impl Ledger for LedgerRepositorySqlite {
+    fn post(&self, entry: JournalEntry) -> Result<(), RepositoryError> {
+        let mut conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        let transaction = conn
+            .transaction()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        let inserted = transaction
+            .execute(
+                "INSERT OR IGNORE INTO ledger_entries
+                    (sequence_number, account_id, event_type, description, posted_at)
+                VALUES (:sequence_number, :account_id, :event_type, :description, :posted_at)",
+                named_params! {
+                    ":sequence_number": entry.sequence_number.to_string(),
+                    ":account_id": entry.account_id.to_string(),
+                    ":event_type": entry.event_type,
+                    ":description": entry.description,
+                    ":posted_at": entry.posted_at.to_rfc3339(),
+                },
+            )
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        // An entry already posted keeps the lines it was posted with
+        if inserted == 0 {
+            return Ok(());
+        }
+
+        for (line, journal_line) in entry.lines.iter().enumerate() {
+            transaction
+                .execute(
+                    "INSERT INTO ledger_lines
+                        (sequence_number, line, ledger_account, side, amount, currency)
+                    VALUES (:sequence_number, :line, :ledger_account, :side, :amount, :currency)",
+                    named_params! {
+                        ":sequence_number": entry.sequence_number.to_string(),
+                        ":line": line as i64,
+                        ":ledger_account": journal_line.ledger_account.code(),
+                        ":side": journal_line.side.as_str(),
+                        ":amount": journal_line.amount.to_string(),
+                        ":currency": journal_line.currency.code(),
+                    },
+                )
+                .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        }
+
+        transaction
+            .commit()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        println!(
+            "Journal entry posted for event {}: {}",
+            entry.sequence_number, entry.description
+        );
+
+        Ok(())
+    }
+
+    fn get_entries(&self, account_id: Ulid) -> Result<Vec<JournalEntry>, RepositoryError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        let mut statement = conn
+            .prepare(
+                "SELECT sequence_number, event_type, description, posted_at
+                FROM ledger_entries
+                WHERE account_id = :account_id
+                ORDER BY sequence_number",
+            )
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        let rows = statement
+            .query_map(
+                named_params! { ":account_id": account_id.to_string() },
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                    ))
+                },
+            )
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        let mut entries = rows
+            .map(|row| {
+                let (sequence_number, event_type, description, posted_at) =
+                    row.map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+                let invalid = |e: &dyn std::fmt::Display| {
+                    RepositoryError::RepositoryError(format!(
+                        "Invalid journal entry {}: {}",
+                        sequence_number, e
+                    ))
+                };
+
+                Ok(JournalEntry {
+                    sequence_number: Ulid::from_string(&sequence_number)
+                        .map_err(|e| invalid(&e))?,
+                    account_id,
+                    event_type,
+                    posted_at: DateTime::parse_from_rfc3339(&posted_at)
+                        .map_err(|e| invalid(&e))?
+                        .with_timezone(&Utc),
+                    description,
+                    lines: Vec::new(),
+                })
+            })
+            .collect::<Result<Vec<_>, RepositoryError>>()?;
+
+        let account_id = account_id.to_string();
+        for (sequence_number, line) in Self::get_lines(
+            &conn,
+            "ledger_entries.account_id = :account_id",
+            named_params! { ":account_id": account_id },
+        )? {
+            if let Some(entry) = entries
+                .iter_mut()
+                .find(|entry| entry.sequence_number == sequence_number)
+            {
+                entry.lines.push(line);
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn trial_balance(&self) -> Result<TrialBalance, RepositoryError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        // Amounts are stored as text to keep their precision, so they are summed here rather
+        // than by SQLite
+        let lines = Self::get_lines(&conn, "1 = 1", &[])?;
+        Ok(TrialBalance::from_lines(lines.iter().map(|(_, line)| line)))
+    }
+}
+
+impl Projection for LedgerRepositorySqlite {
+    fn reset(&self) -> Result<(), RepositoryError> {
+        let mut conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        let transaction = conn
+            .transaction()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        transaction
+            .execute_batch(&format!(
+                "DROP TABLE IF EXISTS ledger_entries; {CREATE_LEDGER_ENTRIES_TABLE}
+                DROP TABLE IF EXISTS ledger_lines; {CREATE_LEDGER_LINES_TABLE}"
+            ))
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+        transaction
+            .execute(
+                "DELETE FROM projection_checkpoints WHERE projection = :projection",
+                named_params! { ":projection": PROJECTION_NAME },
+            )
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        transaction
+            .commit()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        println!("Ledger reset");
+
+        Ok(())
+    }
+
+    fn get_checkpoint(&self) -> Result<Option<Ulid>, RepositoryError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        let checkpoint = conn
+            .query_row(
+                "SELECT sequence_number FROM projection_checkpoints WHERE projection = :projection",
+                named_params! { ":projection": PROJECTION_NAME },
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        checkpoint
+            .map(|s| Ulid::from_string(&s))
+            .transpose()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))
+    }
+
+    fn save_checkpoint(&self, sequence_number: Ulid) -> Result<(), RepositoryError> {
+        let conn = self
+            .pool
+            .get()
+            .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        conn.execute(
+            "INSERT INTO projection_checkpoints (projection, sequence_number)
+             VALUES (:projection, :sequence_number)
+             ON CONFLICT (projection) DO UPDATE
+             SET sequence_number = excluded.sequence_number, updated_at = CURRENT_TIMESTAMP",
+            named_params! {
+                ":projection": PROJECTION_NAME,
+                ":sequence_number": sequence_number.to_string(),
+            },
+        )
+        .map_err(|e| RepositoryError::RepositoryError(e.to_string()))?;
+
+        Ok(())
+    }
+}
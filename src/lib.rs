@@ -0,0 +1,19 @@
+pub mod account;
+pub mod api;
+pub mod app;
+pub mod event_bus_in_memory;
+pub mod event_bus_kafka;
+pub mod event_store_in_memory;
+pub mod event_store_sqlite;
+pub mod fee;
+pub mod fx_rate_provider_in_memory;
+pub mod fx_rate_provider_sqlite;
+pub mod interest;
+pub mod ledger;
+pub mod money;
+pub mod outbox_relay;
+pub mod traits;
+pub mod transfer;
+
+use account::Account;
+use traits::{Aggregate, Event};
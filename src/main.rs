@@ -1,71 +1,59 @@
-pub mod account;
-pub mod event_bus_kafka;
-pub mod event_store_sqlite;
-pub mod traits;
+use std::sync::Arc;
 
-use account::{Account, AccountHandler, AccountService, repositories::AccountRepositorySqlite};
-use event_bus_kafka::EventBusKafka;
-use event_store_sqlite::EventStoreSqlite;
+use bankingv2::account::{
+    Account,
+    repositories::{AccountRepositoryInMemory, AccountRepositorySqlite},
+};
+use bankingv2::app::{self, App, Config};
+use bankingv2::event_bus_in_memory::EventBusInMemory;
+use bankingv2::event_bus_kafka::EventBusKafka;
+use bankingv2::event_store_in_memory::EventStoreInMemory;
+use bankingv2::event_store_sqlite::EventStoreSqlite;
+use bankingv2::ledger::repositories::{LedgerRepositoryInMemory, LedgerRepositorySqlite};
+use bankingv2::money::Currency;
+use bankingv2::traits::{EventStore, Repository, SnapshotStore, event::EventMetadata};
 use rust_decimal::Decimal;
-use std::thread;
-use traits::{Aggregate, Event};
-
-struct Config {
-    event_store_path: String,
-    projection_database_path: String,
-    kafka_bootstrap_servers: String,
-}
-
-impl Config {
-    pub fn new(
-        event_store_path: String,
-        projection_database_path: String,
-        kafka_bootstrap_servers: String,
-    ) -> Self {
-        Self {
-            event_store_path,
-            projection_database_path,
-            kafka_bootstrap_servers,
-        }
-    }
-}
 
 fn main() {
-    // TODO: BALANCE NOT BEING CALCULATED CORRECTLY: IS 100 SHOULD BE 200
-    // TODO: on startup have repositories seed projections with data from event store []
-    // TODO: write interfaces to trigger writing and reading of aggregates []
     // TODO: make simple front-end (tech stack to be determined) []
-    let config = Config::new(
-        "data.db".to_string(),
-        "data.db".to_string(),
-        "localhost:9092".to_string(),
-    );
-
-    // construct necessary components
-    let event_store = EventStoreSqlite::new(&config.event_store_path);
-    let event_bus = EventBusKafka::new(&config.kafka_bootstrap_servers);
+    let config = Config::default();
+    let rebuild_projections = std::env::args().any(|arg| arg == "--rebuild-projections");
 
-    // account components
-    let account_repository = AccountRepositorySqlite::new(&config.projection_database_path);
-    let account_service = AccountService::new(
-        account_repository.clone(),
-        event_store.clone(),
-        event_bus.clone(),
-    );
-    let account_handler = AccountHandler::new(
-        account_repository.clone(),
-        event_bus.clone(),
-        event_store.clone(),
-    );
+    // `--in-memory` runs everything in a single process, without SQLite or Kafka
+    if std::env::args().any(|arg| arg == "--in-memory") {
+        run(app::start(
+            &config,
+            EventStoreInMemory::new(),
+            EventBusInMemory::new(),
+            AccountRepositoryInMemory::new(),
+            LedgerRepositoryInMemory::new(),
+            Arc::new(app::load_fx_rates_in_memory(&config)),
+            rebuild_projections,
+        ));
+    } else {
+        run(app::start(
+            &config,
+            EventStoreSqlite::new(&config.event_store_path),
+            EventBusKafka::new(&config.kafka_bootstrap_servers),
+            AccountRepositorySqlite::new(&config.projection_database_path),
+            LedgerRepositorySqlite::new(&config.projection_database_path),
+            Arc::new(app::load_fx_rates_sqlite(&config)),
+            rebuild_projections,
+        ));
+    }
+}
 
-    // start application
-    thread::spawn(move || {
-        account_handler.listen();
-    });
+fn run<R, S>(app: App<R, S>)
+where
+    R: Repository<Account>,
+    S: EventStore + SnapshotStore,
+{
+    let metadata = EventMetadata::new().with_actor("demo");
 
     // create account
-    let account = account_service
-        .create_account(Decimal::from(100))
+    let account = app
+        .account_service
+        .create_account(Decimal::from(100), Currency::EUR, &metadata)
         .expect("Failed to create account");
     let account_id = account
         .account_id
@@ -73,10 +61,28 @@ fn main() {
         .unwrap();
 
     // deposit 100 into the account
-    account_service
-        .deposit(account_id, Decimal::from(100))
+    app.account_service
+        .deposit(account_id, Decimal::from(100), Currency::EUR, &metadata)
         .expect("Failed to deposit");
 
+    // transfer 50 to a second account, the process manager moves the money
+    let savings_id = app
+        .account_service
+        .create_account(Decimal::from(0), Currency::EUR, &metadata)
+        .expect("Failed to create account")
+        .account_id
+        .ok_or("Failed to get account id".to_string())
+        .unwrap();
+    app.transfer_service
+        .initiate(
+            account_id,
+            savings_id,
+            Decimal::from(50),
+            Currency::EUR,
+            &metadata,
+        )
+        .expect("Failed to initiate transfer");
+
     // Keep the main thread alive to prevent the application from exiting
     println!("Application started. Listening for events...");
     loop {
@@ -0,0 +1,330 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use serde_json::Value;
+use thiserror::Error;
+
+/// Largest amount a single command may move or open an account with.
+pub const MAX_AMOUNT: Decimal = Decimal::from_parts(1_000_000_000, 0, 0, false, 0);
+
+/// ISO 4217 currencies the bank holds, with the number of digits of their minor unit.
+const CURRENCIES: &[(&str, u32)] = &[
+    ("AUD", 2),
+    ("BHD", 3),
+    ("BRL", 2),
+    ("CAD", 2),
+    ("CHF", 2),
+    ("CNY", 2),
+    ("CZK", 2),
+    ("DKK", 2),
+    ("EUR", 2),
+    ("GBP", 2),
+    ("HKD", 2),
+    ("HUF", 2),
+    ("INR", 2),
+    ("ISK", 0),
+    ("JOD", 3),
+    ("JPY", 0),
+    ("KRW", 0),
+    ("KWD", 3),
+    ("MXN", 2),
+    ("NOK", 2),
+    ("NZD", 2),
+    ("OMR", 3),
+    ("PLN", 2),
+    ("SEK", 2),
+    ("SGD", 2),
+    ("TND", 3),
+    ("TRY", 2),
+    ("USD", 2),
+    ("ZAR", 2),
+];
+
+#[derive(Debug, Error)]
+pub enum AmountError {
+    #[error("Amount must be positive, got {0}")]
+    NotPositive(Decimal),
+    #[error("Amount must not be negative, got {0}")]
+    Negative(Decimal),
+    #[error("Amount {value} has more than the {max_scale} decimal places of {currency}")]
+    TooPrecise {
+        value: Decimal,
+        currency: Currency,
+        max_scale: u32,
+    },
+    #[error("Amount {value} exceeds the limit of {max}")]
+    TooLarge { value: Decimal, max: Decimal },
+}
+
+#[derive(Debug, Error)]
+pub enum CurrencyError {
+    #[error("Unknown ISO 4217 currency: {0}")]
+    UnknownCurrency(String),
+}
+
+/// An ISO 4217 currency, serialised as its alphabetic code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Currency {
+    code: &'static str,
+    minor_units: u32,
+}
+
+impl Currency {
+    pub const EUR: Currency = Currency {
+        code: "EUR",
+        minor_units: 2,
+    };
+    pub const USD: Currency = Currency {
+        code: "USD",
+        minor_units: 2,
+    };
+    pub const JPY: Currency = Currency {
+        code: "JPY",
+        minor_units: 0,
+    };
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Number of decimal places of the currency's minor unit, 2 for cents, 0 for yen.
+    pub fn minor_units(&self) -> u32 {
+        self.minor_units
+    }
+
+    /// Rounds a computed amount to the currency's minor unit. Midpoints round to the even
+    /// neighbour, which keeps repeated rounding from drifting in either direction.
+    pub fn round(&self, value: Decimal) -> Decimal {
+        value.round_dp_with_strategy(self.minor_units, RoundingStrategy::MidpointNearestEven)
+    }
+}
+
+/// Currency of accounts and events stored before they recorded one.
+impl Default for Currency {
+    fn default() -> Self {
+        Currency::EUR
+    }
+}
+
+impl FromStr for Currency {
+    type Err = CurrencyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.trim().to_ascii_uppercase();
+        CURRENCIES
+            .iter()
+            .find(|(known, _)| *known == code)
+            .map(|&(code, minor_units)| Currency { code, minor_units })
+            .ok_or(CurrencyError::UnknownCurrency(s.to_string()))
+    }
+}
+
+impl Serialize for Currency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code)
+    }
+}
+
+impl<'de> Deserialize<'de> for Currency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code)
+    }
+}
+
+/// A validated, non-negative amount, without the currency it is counted in.
+///
+/// Serialised as a bare decimal, so events stored before amounts were validated still decode.
+/// Amounts read back from the event store are not validated again, events record what already
+/// happened.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Amount(Decimal);
+
+impl Amount {
+    pub const ZERO: Amount = Amount(Decimal::ZERO);
+
+    /// Validates an amount moved by a command, which must be positive.
+    pub fn new(value: Decimal) -> Result<Self, AmountError> {
+        if value <= Decimal::ZERO {
+            return Err(AmountError::NotPositive(value));
+        }
+        Self::non_negative(value)
+    }
+
+    /// Validates an amount that may be zero, such as the balance an account is opened with.
+    pub fn non_negative(value: Decimal) -> Result<Self, AmountError> {
+        if value < Decimal::ZERO {
+            return Err(AmountError::Negative(value));
+        }
+
+        if value > MAX_AMOUNT {
+            return Err(AmountError::TooLarge {
+                value,
+                max: MAX_AMOUNT,
+            });
+        }
+
+        Ok(Self(value))
+    }
+
+    pub fn value(&self) -> Decimal {
+        self.0
+    }
+}
+
+impl From<Amount> for Decimal {
+    fn from(amount: Amount) -> Self {
+        amount.0
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A validated amount in a currency, carried by commands and events. Events flatten it into
+/// their `amount` and `currency` fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    amount: Amount,
+    currency: Currency,
+}
+
+impl Money {
+    /// Validates an amount moved by a command, which must be positive and no more precise than
+    /// the currency's minor unit.
+    pub fn new(value: Decimal, currency: Currency) -> Result<Self, AmountError> {
+        Self::in_currency(Amount::new(value)?, currency)
+    }
+
+    /// Validates an amount that may be zero, such as the balance an account is opened with.
+    pub fn non_negative(value: Decimal, currency: Currency) -> Result<Self, AmountError> {
+        Self::in_currency(Amount::non_negative(value)?, currency)
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self {
+            amount: Amount::ZERO,
+            currency,
+        }
+    }
+
+    fn in_currency(amount: Amount, currency: Currency) -> Result<Self, AmountError> {
+        // Trailing zeros do not make an amount more precise, "5.000" is five
+        if amount.value().normalize().scale() > currency.minor_units() {
+            return Err(AmountError::TooPrecise {
+                value: amount.value(),
+                currency,
+                max_scale: currency.minor_units(),
+            });
+        }
+
+        Ok(Self { amount, currency })
+    }
+
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+
+    pub fn value(&self) -> Decimal {
+        self.amount.value()
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+/// Upcaster for events stored before amounts carried a currency, those were all in the
+/// default currency.
+pub(crate) fn add_default_currency(mut event: Value) -> Value {
+    if let Some(event) = event.as_object_mut() {
+        event
+            .entry("currency")
+            .or_insert_with(|| Currency::default().code().into());
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn amounts_must_be_positive() {
+        assert!(matches!(
+            Money::new(Decimal::ZERO, Currency::EUR),
+            Err(AmountError::NotPositive(_))
+        ));
+        assert!(matches!(
+            Money::new(decimal("-5"), Currency::EUR),
+            Err(AmountError::NotPositive(_))
+        ));
+        assert_eq!(
+            Money::non_negative(Decimal::ZERO, Currency::EUR).unwrap(),
+            Money::zero(Currency::EUR)
+        );
+        assert!(matches!(
+            Money::non_negative(decimal("-0.01"), Currency::EUR),
+            Err(AmountError::Negative(_))
+        ));
+    }
+
+    #[test]
+    fn amounts_are_limited_in_scale_and_size() {
+        assert!(matches!(
+            Money::new(decimal("0.001"), Currency::EUR),
+            Err(AmountError::TooPrecise { .. })
+        ));
+        assert!(matches!(
+            Money::new(decimal("0.5"), Currency::JPY),
+            Err(AmountError::TooPrecise { .. })
+        ));
+        assert_eq!(
+            Money::new(decimal("5.000"), Currency::EUR).unwrap().value(),
+            decimal("5.000")
+        );
+        assert!(Money::new(decimal("0.001"), "BHD".parse().unwrap()).is_ok());
+        assert!(Amount::new(MAX_AMOUNT).is_ok());
+        assert!(matches!(
+            Amount::new(MAX_AMOUNT + decimal("0.01")),
+            Err(AmountError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn currencies_round_to_their_minor_unit() {
+        assert_eq!(Currency::EUR.round(decimal("1.005")), decimal("1.00"));
+        assert_eq!(Currency::EUR.round(decimal("1.015")), decimal("1.02"));
+        assert_eq!(Currency::JPY.round(decimal("2.5")), decimal("2"));
+        assert_eq!(
+            "usd".parse::<Currency>().unwrap().code(),
+            Currency::USD.code()
+        );
+        assert!("XYZ".parse::<Currency>().is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use std::thread;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::traits::{EventBus, Outbox, event_bus::EventBusError, outbox::OutboxError};
+
+#[derive(Debug, Error)]
+pub enum OutboxRelayError {
+    #[error("Outbox error: {0}")]
+    OutboxError(#[from] OutboxError),
+    #[error("Event bus error: {0}")]
+    EventBusError(#[from] EventBusError),
+}
+
+/// Publishes events from the outbox to the event bus.
+///
+/// Messages are published strictly in the order they were stored, a batch at a time, and are
+/// only marked as dispatched once the bus has flushed the batch. When publishing fails the
+/// failure is recorded on the message and the batch stops, so later events never overtake an
+/// earlier one; the message is retried on the next run.
+pub struct OutboxRelay<O: Outbox, B: EventBus> {
+    outbox: O,
+    event_bus: B,
+    batch_size: usize,
+    poll_interval: Duration,
+    max_backoff: Duration,
+}
+
+impl<O: Outbox, B: EventBus> OutboxRelay<O, B> {
+    pub fn new(outbox: O, event_bus: B) -> Self {
+        Self {
+            outbox,
+            event_bus,
+            batch_size: 100,
+            poll_interval: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    /// Publishes pending messages until the outbox is empty, returning how many were dispatched.
+    pub fn relay_pending(&self) -> Result<usize, OutboxRelayError> {
+        let mut dispatched = 0;
+
+        loop {
+            let messages = self.outbox.get_pending_messages(self.batch_size)?;
+            if messages.is_empty() {
+                return Ok(dispatched);
+            }
+
+            let mut published = Vec::with_capacity(messages.len());
+            let mut failure = None;
+            for message in &messages {
+                match self.event_bus.publish(message) {
+                    Ok(()) => published.push(message.id),
+                    Err(e) => {
+                        failure = Some((message.id, e));
+                        break;
+                    }
+                }
+            }
+
+            // Whether any message of an unflushed batch was delivered is unknown, keep all of
+            // them pending and record the failure on the first
+            if let Err(e) = self.event_bus.flush() {
+                self.outbox.mark_failed(messages[0].id, &e.to_string())?;
+                return Err(e.into());
+            }
+
+            for id in published {
+                self.outbox.mark_dispatched(id)?;
+                dispatched += 1;
+            }
+
+            if let Some((id, e)) = failure {
+                self.outbox.mark_failed(id, &e.to_string())?;
+                return Err(e.into());
+            }
+        }
+    }
+
+    /// How long to wait before the next run after `consecutive_failures` failed runs, doubling
+    /// from the poll interval up to the maximum backoff.
+    fn delay(&self, consecutive_failures: u32) -> Duration {
+        self.poll_interval
+            .saturating_mul(2u32.saturating_pow(consecutive_failures))
+            .min(self.max_backoff)
+    }
+
+    /// Relays pending messages forever, backing off exponentially while the bus is failing.
+    pub fn run(&self) {
+        let mut consecutive_failures = 0;
+
+        loop {
+            match self.relay_pending() {
+                Ok(_) => consecutive_failures = 0,
+                Err(e) => {
+                    eprintln!("Error relaying outbox: {}", e);
+                    consecutive_failures += 1;
+                }
+            }
+
+            thread::sleep(self.delay(consecutive_failures));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Account;
+    use crate::account::events::{ACCOUNT_AGGREGATE_TYPE, AccountEvent, DepositEvent};
+    use crate::event_bus_in_memory::EventBusInMemory;
+    use crate::event_store_in_memory::EventStoreInMemory;
+    use crate::money::{Currency, Money};
+    use crate::traits::event::EventMetadata;
+    use crate::traits::event_store::EventStoreError;
+    use crate::traits::outbox::OutboxMessage;
+    use crate::traits::{EventStore, event_bus::EventHandler};
+    use rust_decimal::Decimal;
+    use serde::Deserialize;
+    use std::sync::{Arc, Mutex};
+    use ulid::Ulid;
+
+    /// Bus that rejects the next `failures` publishes, then records what it is given.
+    #[derive(Clone, Default)]
+    struct FlakyBus {
+        failures: Arc<Mutex<u32>>,
+        published: Arc<Mutex<Vec<Ulid>>>,
+    }
+
+    impl EventBus for FlakyBus {
+        fn publish(&self, message: &OutboxMessage) -> Result<(), EventBusError> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(EventBusError::ProduceError(
+                    "broker unavailable".to_string(),
+                ));
+            }
+
+            self.published.lock().unwrap().push(message.sequence_number);
+            Ok(())
+        }
+
+        fn subscribe<T, E>(&self, _: &str, _: &str, _: EventHandler<T, E>)
+        where
+            T: 'static,
+            E: crate::traits::Event<T> + for<'de> Deserialize<'de> + 'static,
+        {
+        }
+    }
+
+    fn deposit(
+        event_store: &EventStoreInMemory,
+        account_id: Ulid,
+        expected_version: u64,
+    ) -> Result<(), EventStoreError> {
+        let event = AccountEvent::Deposited(DepositEvent {
+            account_id,
+            amount: Money::new(Decimal::ONE, Currency::EUR).unwrap(),
+            transfer_id: None,
+        });
+        event_store.append_event::<Account, _>(
+            account_id,
+            ACCOUNT_AGGREGATE_TYPE,
+            expected_version,
+            event,
+            &EventMetadata::new(),
+        )
+    }
+
+    #[test]
+    fn appended_events_are_queued_in_the_same_transaction() {
+        let event_store = EventStoreInMemory::new();
+        let account_id = Ulid::new();
+        deposit(&event_store, account_id, 0).unwrap();
+
+        assert!(matches!(
+            deposit(&event_store, account_id, 0),
+            Err(EventStoreError::ConcurrencyConflict { .. })
+        ));
+
+        // Exactly the stored event is queued, the rejected one left no message behind
+        let stored = event_store
+            .get_events_for_aggregate::<Account, AccountEvent>(account_id, ACCOUNT_AGGREGATE_TYPE)
+            .unwrap();
+        let pending = event_store.get_pending_messages(10).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].sequence_number, stored[0].sequence_number);
+        assert_eq!(pending[0].event_type, "deposit");
+
+        let relay = OutboxRelay::new(event_store.clone(), EventBusInMemory::new());
+        assert_eq!(relay.relay_pending().unwrap(), 1);
+        assert!(event_store.get_pending_messages(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn failed_publishes_are_retried_in_order() {
+        let event_store = EventStoreInMemory::new();
+        let account_id = Ulid::new();
+        for version in 0..3 {
+            deposit(&event_store, account_id, version).unwrap();
+        }
+        let sequence_numbers: Vec<Ulid> = event_store
+            .get_pending_messages(10)
+            .unwrap()
+            .iter()
+            .map(|message| message.sequence_number)
+            .collect();
+
+        let bus = FlakyBus::default();
+        *bus.failures.lock().unwrap() = 1;
+        let relay = OutboxRelay::new(event_store.clone(), bus.clone());
+
+        assert!(relay.relay_pending().is_err());
+        let pending = event_store.get_pending_messages(10).unwrap();
+        assert_eq!(pending.len(), 3);
+        assert_eq!(pending[0].attempts, 1);
+
+        assert_eq!(relay.relay_pending().unwrap(), 3);
+        assert_eq!(*bus.published.lock().unwrap(), sequence_numbers);
+        assert!(event_store.get_pending_messages(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let relay = OutboxRelay::new(EventStoreInMemory::new(), EventBusInMemory::new());
+
+        assert_eq!(relay.delay(0), Duration::from_millis(100));
+        assert_eq!(relay.delay(1), Duration::from_millis(200));
+        assert_eq!(relay.delay(3), Duration::from_millis(800));
+        assert_eq!(relay.delay(9), Duration::from_secs(30));
+        assert_eq!(relay.delay(u32::MAX), Duration::from_secs(30));
+    }
+}
@@ -3,9 +3,18 @@ pub mod command;
 pub mod event;
 pub mod event_bus;
 pub mod event_store;
+pub mod fx_rate_provider;
+pub mod ledger;
+pub mod outbox;
+pub mod projection;
 pub mod repository;
+pub mod snapshot_store;
+pub mod transaction_repository;
+pub mod upcaster;
 
 pub use {
     aggregate::Aggregate, command::Command, event::Event, event_bus::EventBus,
-    event_store::EventStore, repository::Repository,
+    event_store::EventStore, fx_rate_provider::FxRateProvider, ledger::Ledger, outbox::Outbox,
+    projection::Projection, repository::Repository, snapshot_store::SnapshotStore,
+    transaction_repository::TransactionRepository,
 };
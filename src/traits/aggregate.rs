@@ -1,7 +1,16 @@
-use super::{Command, Event, event::ApplyError};
+use super::{
+    Command, Event,
+    event::{ApplyError, EventEnvelope},
+};
 
 pub trait Aggregate<T: Default> {
     fn from_history<E: Event<T>>(history: Vec<E>) -> Result<T, ApplyError>;
+    /// Continues from a snapshotted state, `history` holds only the envelopes newer than the
+    /// snapshot. Events are applied as recorded, with the metadata of their envelopes.
+    fn from_snapshot_and_history<E: Event<T>>(
+        snapshot: T,
+        history: &[EventEnvelope<T, E>],
+    ) -> Result<T, ApplyError>;
     fn handle_command<C, E, Err>(state: T, command: C) -> Result<Vec<E>, Err>
     where
         C: Command<T, E, Err>,
@@ -19,6 +28,19 @@ impl<T: Default + 'static> Aggregate<T> for T {
         Ok(state)
     }
 
+    fn from_snapshot_and_history<E: Event<T>>(
+        snapshot: T,
+        history: &[EventEnvelope<T, E>],
+    ) -> Result<T, ApplyError> {
+        let mut state = snapshot;
+        for envelope in history {
+            envelope
+                .event
+                .apply_recorded(&mut state, &envelope.metadata)?;
+        }
+        Ok(state)
+    }
+
     fn handle_command<C, E, Err>(state: T, command: C) -> Result<Vec<E>, Err>
     where
         C: Command<T, E, Err>,
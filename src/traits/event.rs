@@ -1,9 +1,14 @@
+use std::collections::BTreeMap;
 use std::marker::PhantomData;
 
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
 use thiserror::Error;
 use ulid::Ulid;
 
+use super::upcaster::{INITIAL_SCHEMA_VERSION, UpcasterRegistry};
+
 // Definition of ApplyError using thiserror
 #[derive(Debug, Error)]
 pub enum ApplyError {
@@ -14,20 +19,123 @@ pub enum ApplyError {
 }
 // End of ApplyError definition
 
+/// Field that tagged event enums use to carry their discriminator, its value must match
+/// [`Event::event_type`].
+pub const EVENT_TYPE_TAG: &str = "event_type";
+
 pub trait Event<T> {
     fn aggregate_id(&self) -> Ulid;
     fn aggregate_type(&self) -> &str;
     fn event_type(&self) -> &str;
     fn apply(&self, state: &mut T) -> Result<(), ApplyError>;
+
+    /// Applies the event as stored, with the metadata of its envelope. Events whose effect
+    /// depends on when they were recorded override it, the others are just applied.
+    fn apply_recorded(&self, state: &mut T, _metadata: &EventMetadata) -> Result<(), ApplyError> {
+        self.apply(state)
+    }
+
+    /// Version of the event's serialised shape, recorded on its envelope. Bump it whenever the
+    /// shape changes and register an upcaster from the previous version in [`Event::upcasters`].
+    fn schema_version(&self) -> u32 {
+        INITIAL_SCHEMA_VERSION
+    }
+
+    /// Upcasters bringing events stored with an older [`Event::schema_version`] up to date.
+    fn upcasters() -> UpcasterRegistry
+    where
+        Self: Sized,
+    {
+        UpcasterRegistry::new()
+    }
+}
+
+fn initial_schema_version() -> u32 {
+    INITIAL_SCHEMA_VERSION
+}
+
+/// Where an event came from, stored next to it so every change can be traced to its origin.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventMetadata {
+    /// When the event store recorded the event, set on append.
+    pub recorded_at: DateTime<Utc>,
+    /// Identifies the interaction as a whole, shared by every event it led to.
+    pub correlation_id: Option<String>,
+    /// Sequence number of the event that caused this one, `None` for events caused by a user.
+    pub causation_id: Option<Ulid>,
+    /// User or system that initiated the interaction.
+    pub actor: Option<String>,
+    /// ID the client attached to its request.
+    pub client_request_id: Option<String>,
+    pub headers: BTreeMap<String, String>,
+}
+
+impl EventMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Metadata for events appended in reaction to `envelope`, continuing its interaction.
+    pub fn caused_by<T, E: Event<T>>(envelope: &EventEnvelope<T, E>) -> Self {
+        let metadata = &envelope.metadata;
+
+        Self {
+            correlation_id: metadata
+                .correlation_id
+                .clone()
+                .or_else(|| Some(envelope.sequence_number.to_string())),
+            causation_id: Some(envelope.sequence_number),
+            actor: metadata.actor.clone(),
+            client_request_id: metadata.client_request_id.clone(),
+            headers: metadata.headers.clone(),
+            ..Self::default()
+        }
+    }
+
+    /// Copy of the metadata recorded now, at the microsecond precision event stores keep.
+    pub fn recorded_now(&self) -> Self {
+        Self {
+            recorded_at: Utc::now().trunc_subsecs(6),
+            ..self.clone()
+        }
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: impl Into<String>) -> Self {
+        self.correlation_id = Some(correlation_id.into());
+        self
+    }
+
+    pub fn with_actor(mut self, actor: impl Into<String>) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    pub fn with_client_request_id(mut self, client_request_id: impl Into<String>) -> Self {
+        self.client_request_id = Some(client_request_id.into());
+        self
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventEnvelope<T, E: Event<T>> {
     pub sequence_number: Ulid,
+    #[serde(default)]
+    pub version: u64,
     pub aggregate_id: Ulid,
     pub aggregate_type: String,
     pub event: E,
     pub event_type: String,
+    /// Schema version of `event`, see [`Event::schema_version`].
+    #[serde(default = "initial_schema_version")]
+    pub schema_version: u32,
+    #[serde(default)]
+    pub metadata: EventMetadata,
     #[serde(skip)]
     pub _phantom: PhantomData<T>,
 }
@@ -35,17 +143,22 @@ pub struct EventEnvelope<T, E: Event<T>> {
 impl<T, E: Event<T>> EventEnvelope<T, E> {
     pub fn new(
         sequence_number: Ulid,
+        version: u64,
         aggregate_id: Ulid,
         aggregate_type: String,
         event_type: String,
         event: E,
+        metadata: EventMetadata,
     ) -> Self {
         Self {
             sequence_number,
+            version,
             aggregate_id,
             aggregate_type,
             event_type,
+            schema_version: event.schema_version(),
             event,
+            metadata,
             _phantom: PhantomData,
         }
     }
@@ -69,6 +182,65 @@ impl<T, E: Event<T>> EventEnvelope<T, E> {
     pub fn event_type(&self) -> &str {
         &self.event_type
     }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn metadata(&self) -> &EventMetadata {
+        &self.metadata
+    }
+}
+
+impl<T, E: Event<T> + DeserializeOwned> EventEnvelope<T, E> {
+    /// Deserialises an envelope as written by the event store or the event bus, including
+    /// envelopes written before events carried their own discriminator. Events stored with an
+    /// older schema version are upcast to the current one first.
+    pub fn from_json(json: &[u8]) -> Result<Self, serde_json::Error> {
+        let mut envelope: Value = serde_json::from_slice(json)?;
+        tag_legacy_event(&mut envelope);
+        upcast_event(&mut envelope, &E::upcasters());
+        serde_json::from_value(envelope)
+    }
+}
+
+/// Runs the event of a serialised envelope through the upcasters of its type, recording the
+/// schema version it ends at on the envelope.
+fn upcast_event(envelope: &mut Value, upcasters: &UpcasterRegistry) {
+    let Some(Value::String(event_type)) = envelope.get(EVENT_TYPE_TAG).cloned() else {
+        return;
+    };
+    let Some(object) = envelope.as_object_mut() else {
+        return;
+    };
+
+    let schema_version = object
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .and_then(|version| u32::try_from(version).ok())
+        .unwrap_or(INITIAL_SCHEMA_VERSION);
+    let event = object.remove("event").unwrap_or(Value::Null);
+
+    let (event, schema_version) = upcasters.upcast(&event_type, schema_version, event);
+    object.insert("event".to_string(), event);
+    object.insert("schema_version".to_string(), schema_version.into());
+}
+
+/// Untagged events only record their type on the envelope, which is ambiguous for events of the
+/// same shape. Copies the envelope's `event_type` onto the event so it can be deserialised as a
+/// tagged event, returning whether the envelope was changed.
+pub fn tag_legacy_event(envelope: &mut Value) -> bool {
+    let Some(event_type) = envelope.get(EVENT_TYPE_TAG).cloned() else {
+        return false;
+    };
+
+    match envelope.get_mut("event") {
+        Some(Value::Object(event)) if !event.contains_key(EVENT_TYPE_TAG) => {
+            event.insert(EVENT_TYPE_TAG.to_string(), event_type);
+            true
+        }
+        _ => false,
+    }
 }
 
 // impl<T: Default> Event<T> for T {
@@ -1,5 +1,5 @@
-use super::Event;
-use serde::{Deserialize, Serialize};
+use super::{Event, event::EventEnvelope, outbox::OutboxMessage};
+use serde::Deserialize;
 use thiserror::Error;
 #[derive(Debug, Error)]
 pub enum EventBusError {
@@ -13,12 +13,26 @@ pub enum EventBusError {
     HandleError(String),
 }
 
+/// Callback invoked for every event delivered to a subscription.
+pub type EventHandler<T, E> =
+    Box<dyn Fn(EventEnvelope<T, E>) -> Result<(), EventBusError> + Send + Sync + 'static>;
+
 pub trait EventBus {
-    fn produce_event<T, E: Event<T> + Serialize>(&self, event: E) -> Result<(), EventBusError>;
-    fn subscribe<T, E>(
-        &self,
-        event_type: &str,
-        handler: Box<dyn Fn(E) -> Result<(), EventBusError> + Send + Sync + 'static>,
-    ) where
+    /// Publishes a stored event. Buses may buffer it, it is only delivered once [`flush`]
+    /// returns.
+    ///
+    /// [`flush`]: EventBus::flush
+    fn publish(&self, message: &OutboxMessage) -> Result<(), EventBusError>;
+    /// Waits until every event published so far has been accepted, so the outbox relay can
+    /// safely mark them as dispatched. Buses that deliver on publish have nothing to wait for.
+    fn flush(&self) -> Result<(), EventBusError> {
+        Ok(())
+    }
+    /// Delivers every event of `aggregate_type` to `handler`. Each `subscriber` receives every
+    /// event independently and the name must stay stable across restarts, buses that track
+    /// delivery progress store it under this name.
+    fn subscribe<T, E>(&self, subscriber: &str, aggregate_type: &str, handler: EventHandler<T, E>)
+    where
+        T: 'static,
         E: Event<T> + for<'de> Deserialize<'de> + 'static;
 }
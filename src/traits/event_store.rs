@@ -2,28 +2,97 @@ use crate::Event;
 use serde::{Deserialize, Serialize};
 use ulid::Ulid;
 
-use super::event::EventEnvelope;
+use super::event::{EventEnvelope, EventMetadata};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum EventStoreError {
     #[error("Event store error: {0}")]
     EventStoreError(String),
+    #[error("Concurrency conflict: expected version {expected}, actual version {actual}")]
+    ConcurrencyConflict { expected: u64, actual: u64 },
+}
+
+/// Summary of an aggregate's event stream.
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregateSummary {
+    pub aggregate_type: String,
+    pub aggregate_id: Ulid,
+    /// Version of the last event, equal to the number of events in the stream.
+    pub version: u64,
+    pub first_sequence_number: Ulid,
+    pub last_sequence_number: Ulid,
 }
 
 pub trait EventStore {
+    /// Appends an event to the stream of the given aggregate.
+    ///
+    /// `expected_version` is the version of the last event the caller based its decision on
+    /// (`0` for a new aggregate). If another event was appended in the meantime the append is
+    /// rejected with [`EventStoreError::ConcurrencyConflict`].
     fn append_event<T, E: Event<T> + Serialize>(
         &self,
         aggregate_id: Ulid,
         aggregate_type: &str,
+        expected_version: u64,
         event: E,
-    ) -> Result<(), EventStoreError>;
+        metadata: &EventMetadata,
+    ) -> Result<(), EventStoreError> {
+        self.append_events(
+            aggregate_id,
+            aggregate_type,
+            expected_version,
+            vec![event],
+            metadata,
+        )
+        .map(|_| ())
+    }
+    /// Appends all events produced by a single command atomically, either every event is
+    /// stored or none is. Returns the stored envelopes with their assigned sequence numbers and
+    /// versions, in order.
+    ///
+    /// Every event is stored with `metadata`, its `recorded_at` is set to the time of the append.
+    fn append_events<T, E: Event<T> + Serialize>(
+        &self,
+        aggregate_id: Ulid,
+        aggregate_type: &str,
+        expected_version: u64,
+        events: Vec<E>,
+        metadata: &EventMetadata,
+    ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError>;
     fn get_events_for_aggregate<T, E: Event<T> + Serialize + for<'de> Deserialize<'de>>(
         &self,
         aggregate_id: Ulid,
         aggregate_type: &str,
     ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError>;
+    /// Returns the events of the aggregate with a version greater than `after_version`.
+    fn get_events_for_aggregate_since<T, E: Event<T> + Serialize + for<'de> Deserialize<'de>>(
+        &self,
+        aggregate_id: Ulid,
+        aggregate_type: &str,
+        after_version: u64,
+    ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError>;
+    /// Returns up to `limit` events of the given aggregate type in the order they were stored,
+    /// starting after `after_sequence_number`. Paging through this streams the whole history.
+    fn get_events_for_aggregate_type<T, E: Event<T> + Serialize + for<'de> Deserialize<'de>>(
+        &self,
+        aggregate_type: &str,
+        after_sequence_number: Option<Ulid>,
+        limit: usize,
+    ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError>;
     fn get_all_events<T, E: Event<T> + Serialize + for<'de> Deserialize<'de>>(
         &self,
     ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError>;
+    /// Lists every aggregate with at least one event, optionally only those of one type, in the
+    /// order they were created.
+    fn get_aggregates(
+        &self,
+        aggregate_type: Option<&str>,
+    ) -> Result<Vec<AggregateSummary>, EventStoreError>;
+    /// Summarises the stream of the aggregate with this ID whatever its type, `None` if it has
+    /// no events.
+    fn get_aggregate(
+        &self,
+        aggregate_id: Ulid,
+    ) -> Result<Option<AggregateSummary>, EventStoreError>;
 }
@@ -0,0 +1,121 @@
+use std::fs;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::money::Currency;
+
+#[derive(Debug, Error)]
+pub enum FxRateError {
+    #[error("No exchange rate from {from} to {to}")]
+    RateNotFound { from: Currency, to: Currency },
+    #[error("Invalid exchange rate: {0}")]
+    InvalidRate(String),
+    #[error("FX rate provider error: {0}")]
+    FxRateProviderError(String),
+}
+
+/// Source of the exchange rates deposits in a foreign currency are converted with.
+pub trait FxRateProvider {
+    /// Mid-market rate converting one unit of `from` into `to`.
+    fn rate(&self, from: Currency, to: Currency) -> Result<Decimal, FxRateError>;
+}
+
+/// Resolves the rate from `from` to `to` with `find`, which looks up rates as they were
+/// listed. Rates are only listed in one direction, the other one is their inverse.
+pub(crate) fn resolve_rate(
+    from: Currency,
+    to: Currency,
+    find: impl Fn(Currency, Currency) -> Result<Option<Decimal>, FxRateError>,
+) -> Result<Decimal, FxRateError> {
+    if from == to {
+        return Ok(Decimal::ONE);
+    }
+
+    if let Some(rate) = find(from, to)? {
+        return Ok(rate);
+    }
+
+    match find(to, from)? {
+        Some(rate) => Decimal::ONE.checked_div(rate).ok_or_else(|| {
+            FxRateError::InvalidRate(format!("{} to {} cannot be inverted: {}", to, from, rate))
+        }),
+        None => Err(FxRateError::RateNotFound { from, to }),
+    }
+}
+
+/// Rejects rates that are not positive, which could not be inverted.
+pub(crate) fn check_rate(
+    base: Currency,
+    quote: Currency,
+    rate: Decimal,
+) -> Result<(), FxRateError> {
+    if rate <= Decimal::ZERO {
+        return Err(FxRateError::InvalidRate(format!(
+            "{} to {} must be positive, got {}",
+            base, quote, rate
+        )));
+    }
+
+    Ok(())
+}
+
+/// How amounts in a foreign currency are converted: the rates to use and the spread the bank
+/// keeps, as a fraction of the rate.
+#[derive(Clone)]
+pub struct FxPolicy {
+    pub rates: Arc<dyn FxRateProvider + Send + Sync>,
+    pub spread: Decimal,
+}
+
+impl FxPolicy {
+    pub fn new(rates: Arc<dyn FxRateProvider + Send + Sync>, spread: Decimal) -> Self {
+        Self { rates, spread }
+    }
+}
+
+/// A rate as listed in a rates file, one unit of `base` is worth `rate` units of `quote`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FxRate {
+    pub base: Currency,
+    pub quote: Currency,
+    pub rate: Decimal,
+}
+
+/// Reads rates from a CSV file with `base,quote,rate` lines such as `EUR,USD,1.0850`. A header
+/// line, blank lines and lines starting with `#` are skipped.
+pub fn read_rates_csv(path: &str) -> Result<Vec<FxRate>, FxRateError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| FxRateError::FxRateProviderError(format!("Cannot read {}: {}", path, e)))?;
+
+    contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .filter(|(_, line)| !line.to_ascii_lowercase().starts_with("base,"))
+        .map(|(number, line)| {
+            let invalid = |reason: String| {
+                FxRateError::InvalidRate(format!("{}:{}: {}", path, number, reason))
+            };
+
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [base, quote, rate] = fields.as_slice() else {
+                return Err(invalid(format!("expected base,quote,rate, got {}", line)));
+            };
+
+            let rate = Decimal::from_str(rate).map_err(|e| invalid(e.to_string()))?;
+            if rate <= Decimal::ZERO {
+                return Err(invalid(format!("rate must be positive, got {}", rate)));
+            }
+
+            Ok(FxRate {
+                base: base.parse().map_err(|e| invalid(format!("{}", e)))?,
+                quote: quote.parse().map_err(|e| invalid(format!("{}", e)))?,
+                rate,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,15 @@
+use ulid::Ulid;
+
+use crate::ledger::{JournalEntry, TrialBalance};
+
+use super::repository::RepositoryError;
+
+/// Double-entry books of the bank, kept as journal entries posted for account events.
+pub trait Ledger {
+    /// Stores an entry, ignoring it when an entry for the same event was already posted.
+    fn post(&self, entry: JournalEntry) -> Result<(), RepositoryError>;
+    /// Entries posted for the events of a customer account, oldest first.
+    fn get_entries(&self, account_id: Ulid) -> Result<Vec<JournalEntry>, RepositoryError>;
+    /// Debits and credits of every ledger account over all posted entries.
+    fn trial_balance(&self) -> Result<TrialBalance, RepositoryError>;
+}
@@ -0,0 +1,36 @@
+use thiserror::Error;
+use ulid::Ulid;
+
+use super::event::EventMetadata;
+
+#[derive(Debug, Error)]
+pub enum OutboxError {
+    #[error("Outbox error: {0}")]
+    OutboxError(String),
+}
+
+/// An event waiting in the outbox to be published to the event bus. The payload is the
+/// serialised envelope exactly as it was written to the event store.
+#[derive(Debug, Clone)]
+pub struct OutboxMessage {
+    pub id: u64,
+    pub sequence_number: Ulid,
+    pub aggregate_id: Ulid,
+    pub aggregate_type: String,
+    pub event_type: String,
+    pub payload: String,
+    pub metadata: EventMetadata,
+    pub attempts: u32,
+}
+
+/// Events are written to the outbox in the same transaction as the event store, the relay then
+/// publishes them so the store and the bus can never permanently diverge.
+pub trait Outbox {
+    /// Returns undispatched messages in the order they were stored, oldest first.
+    fn get_pending_messages(&self, limit: usize) -> Result<Vec<OutboxMessage>, OutboxError>;
+    fn mark_dispatched(&self, id: u64) -> Result<(), OutboxError>;
+    fn mark_failed(&self, id: u64, error: &str) -> Result<(), OutboxError>;
+    /// Marks dispatched messages stored after `after_sequence_number` (all of them when `None`)
+    /// as pending again so the relay publishes them once more. Returns how many were requeued.
+    fn requeue(&self, after_sequence_number: Option<Ulid>) -> Result<usize, OutboxError>;
+}
@@ -0,0 +1,12 @@
+use ulid::Ulid;
+
+use super::repository::RepositoryError;
+
+/// A read model built from the event stream, tracking how far into the stream it has got.
+pub trait Projection {
+    /// Drops and recreates the read model, forgetting its checkpoint.
+    fn reset(&self) -> Result<(), RepositoryError>;
+    /// Sequence number of the last event applied to the read model.
+    fn get_checkpoint(&self) -> Result<Option<Ulid>, RepositoryError>;
+    fn save_checkpoint(&self, sequence_number: Ulid) -> Result<(), RepositoryError>;
+}
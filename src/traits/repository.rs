@@ -8,6 +8,8 @@ use thiserror::Error;
 pub enum RepositoryError {
     #[error("Repository error: {0}")]
     RepositoryError(String),
+    #[error("Not found: {0}")]
+    NotFound(Ulid),
 }
 
 pub trait Repository<T: Aggregate<T> + Default> {
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+use ulid::Ulid;
+
+#[derive(Debug, Error)]
+pub enum SnapshotStoreError {
+    #[error("Snapshot store error: {0}")]
+    SnapshotStoreError(String),
+}
+
+/// State of an aggregate after applying every event up to and including `version`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot<T> {
+    pub aggregate_id: Ulid,
+    pub aggregate_type: String,
+    pub version: u64,
+    pub state: T,
+}
+
+/// Decides after which appends a new snapshot of an aggregate is taken.
+#[derive(Debug, Clone, Copy)]
+pub enum SnapshotPolicy {
+    Never,
+    EveryNEvents(u64),
+}
+
+impl SnapshotPolicy {
+    /// Whether moving an aggregate from `previous_version` to `version` crossed a snapshot
+    /// boundary, commands appending several events at once still trigger a snapshot.
+    pub fn should_snapshot(&self, previous_version: u64, version: u64) -> bool {
+        match self {
+            SnapshotPolicy::Never => false,
+            SnapshotPolicy::EveryNEvents(0) => false,
+            SnapshotPolicy::EveryNEvents(n) => previous_version / n != version / n,
+        }
+    }
+}
+
+impl Default for SnapshotPolicy {
+    fn default() -> Self {
+        SnapshotPolicy::EveryNEvents(100)
+    }
+}
+
+pub trait SnapshotStore {
+    /// Stores the snapshot, replacing any older snapshot of the same aggregate.
+    fn save_snapshot<T: Serialize>(&self, snapshot: &Snapshot<T>)
+    -> Result<(), SnapshotStoreError>;
+    /// Returns the latest snapshot of the aggregate, if one was taken.
+    fn get_snapshot<T: DeserializeOwned>(
+        &self,
+        aggregate_id: Ulid,
+        aggregate_type: &str,
+    ) -> Result<Option<Snapshot<T>>, SnapshotStoreError>;
+}
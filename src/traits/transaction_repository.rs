@@ -0,0 +1,23 @@
+use ulid::Ulid;
+
+use crate::account::transactions::{Transaction, TransactionPage, TransactionQuery};
+
+use super::repository::RepositoryError;
+
+/// Statement of every account: the transactions that changed its balance, next to the
+/// accounts themselves.
+pub trait TransactionRepository {
+    /// Stores a transaction, ignoring it when one was already recorded for the same event. A
+    /// reversal also marks the transaction it undid as reversed by it.
+    fn record_transaction(&self, transaction: Transaction) -> Result<(), RepositoryError>;
+    /// Latest transaction of an account, whose balance the next one continues from.
+    fn get_last_transaction(
+        &self,
+        account_id: Ulid,
+    ) -> Result<Option<Transaction>, RepositoryError>;
+    fn get_transactions(
+        &self,
+        account_id: Ulid,
+        query: &TransactionQuery,
+    ) -> Result<TransactionPage, RepositoryError>;
+}
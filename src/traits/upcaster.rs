@@ -0,0 +1,215 @@
+use serde_json::Value;
+
+/// Schema version of events stored before envelopes recorded one.
+pub const INITIAL_SCHEMA_VERSION: u32 = 1;
+
+/// Turns the serialised form of an event into the form of the next schema version.
+pub type Upcast = fn(Value) -> Value;
+
+struct Upcaster {
+    event_type: &'static str,
+    from_version: u32,
+    upcast: Upcast,
+}
+
+/// Upcasters of an event type, bringing events stored with an older schema version up to date
+/// while they are read, one version at a time.
+#[derive(Default)]
+pub struct UpcasterRegistry {
+    upcasters: Vec<Upcaster>,
+}
+
+impl UpcasterRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `upcast` to turn version `from_version` of `event_type` into version
+    /// `from_version + 1`.
+    pub fn register(mut self, event_type: &'static str, from_version: u32, upcast: Upcast) -> Self {
+        self.upcasters.push(Upcaster {
+            event_type,
+            from_version,
+            upcast,
+        });
+        self
+    }
+
+    /// Adds the upcasters of `other`, used by event enums to combine those of their variants.
+    pub fn merge(mut self, other: UpcasterRegistry) -> Self {
+        self.upcasters.extend(other.upcasters);
+        self
+    }
+
+    /// Applies the upcasters of `event_type` starting at `version` until none is left, returning
+    /// the upcast event and the version it ended at.
+    pub fn upcast(&self, event_type: &str, mut version: u32, mut event: Value) -> (Value, u32) {
+        while let Some(upcaster) = self
+            .upcasters
+            .iter()
+            .find(|u| u.event_type == event_type && u.from_version == version)
+        {
+            event = (upcaster.upcast)(event);
+            version += 1;
+        }
+
+        (event, version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use ulid::Ulid;
+
+    use super::*;
+    use crate::traits::Event;
+    use crate::traits::event::{ApplyError, EventEnvelope, EventMetadata};
+
+    /// v1 stored `{"id", "value"}`, v2 renamed them to `{"account_id", "amount"}` and v3 added
+    /// `currency`.
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(tag = "event_type", rename = "credited")]
+    struct CreditedEvent {
+        account_id: Ulid,
+        amount: Decimal,
+        currency: String,
+    }
+
+    fn v1_to_v2(mut event: Value) -> Value {
+        if let Some(event) = event.as_object_mut() {
+            if let Some(id) = event.remove("id") {
+                event.insert("account_id".to_string(), id);
+            }
+            if let Some(value) = event.remove("value") {
+                event.insert("amount".to_string(), value);
+            }
+        }
+        event
+    }
+
+    fn v2_to_v3(mut event: Value) -> Value {
+        if let Some(event) = event.as_object_mut() {
+            event.insert("currency".to_string(), json!("EUR"));
+        }
+        event
+    }
+
+    impl Event<()> for CreditedEvent {
+        fn aggregate_id(&self) -> Ulid {
+            self.account_id
+        }
+
+        fn aggregate_type(&self) -> &str {
+            "account"
+        }
+
+        fn event_type(&self) -> &str {
+            "credited"
+        }
+
+        fn apply(&self, _state: &mut ()) -> Result<(), ApplyError> {
+            Ok(())
+        }
+
+        fn schema_version(&self) -> u32 {
+            3
+        }
+
+        fn upcasters() -> UpcasterRegistry {
+            UpcasterRegistry::new()
+                .register("credited", 1, v1_to_v2)
+                .register("credited", 2, v2_to_v3)
+        }
+    }
+
+    fn stored(schema_version: Option<u32>, event: Value) -> Vec<u8> {
+        let mut envelope = json!({
+            "sequence_number": Ulid::new(),
+            "version": 1,
+            "aggregate_id": Ulid::new(),
+            "aggregate_type": "account",
+            "event_type": "credited",
+            "event": event,
+        });
+        if let Some(schema_version) = schema_version {
+            envelope["schema_version"] = json!(schema_version);
+        }
+        serde_json::to_vec(&envelope).unwrap()
+    }
+
+    fn decode(json: &[u8]) -> EventEnvelope<(), CreditedEvent> {
+        EventEnvelope::from_json(json).unwrap()
+    }
+
+    #[test]
+    fn upcasts_through_every_version() {
+        let account_id = Ulid::new();
+
+        let envelope = decode(&stored(
+            Some(1),
+            json!({ "event_type": "credited", "id": account_id, "value": "5" }),
+        ));
+
+        assert_eq!(envelope.schema_version, 3);
+        assert_eq!(envelope.event.account_id, account_id);
+        assert_eq!(envelope.event.amount, Decimal::from(5));
+        assert_eq!(envelope.event.currency, "EUR");
+    }
+
+    #[test]
+    fn upcasts_from_an_intermediate_version() {
+        let envelope = decode(&stored(
+            Some(2),
+            json!({ "event_type": "credited", "account_id": Ulid::new(), "amount": "5" }),
+        ));
+
+        assert_eq!(envelope.schema_version, 3);
+        assert_eq!(envelope.event.currency, "EUR");
+    }
+
+    #[test]
+    fn envelopes_without_schema_version_are_the_initial_version() {
+        let envelope = decode(&stored(
+            None,
+            json!({ "event_type": "credited", "id": Ulid::new(), "value": "5" }),
+        ));
+
+        assert_eq!(envelope.schema_version, 3);
+        assert_eq!(envelope.event.currency, "EUR");
+    }
+
+    #[test]
+    fn current_events_round_trip_unchanged() {
+        let event = CreditedEvent {
+            account_id: Ulid::new(),
+            amount: Decimal::from(5),
+            currency: "USD".to_string(),
+        };
+        let envelope = EventEnvelope::new(
+            Ulid::new(),
+            1,
+            event.account_id,
+            "account".to_string(),
+            "credited".to_string(),
+            event,
+            EventMetadata::new(),
+        );
+        assert_eq!(envelope.schema_version, 3);
+
+        let decoded = decode(&serde_json::to_vec(&envelope).unwrap());
+
+        assert_eq!(decoded.schema_version, 3);
+        assert_eq!(decoded.event.currency, "USD");
+    }
+
+    #[test]
+    fn upcasters_only_apply_to_their_event_type() {
+        let registry = CreditedEvent::upcasters();
+        let event = json!({ "id": 1 });
+
+        assert_eq!(registry.upcast("debited", 1, event.clone()), (event, 1));
+    }
+}
@@ -0,0 +1,70 @@
+pub mod commands;
+pub mod events;
+pub mod transfer_process_manager;
+pub mod transfer_service;
+
+use serde::{Deserialize, Serialize};
+pub use transfer_process_manager::TransferProcessManager;
+pub use transfer_service::TransferService;
+use ulid::Ulid;
+
+use crate::money::Money;
+use crate::traits::event::ApplyError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransferStatus {
+    /// Requested, nothing has moved yet.
+    Initiated,
+    /// Taken from the source account, not yet given to the destination.
+    Debited,
+    /// Completed, the destination account received the funds.
+    Credited,
+    /// The source account could not be debited, nothing moved.
+    Failed,
+    /// The destination could not be credited and the source was refunded.
+    Compensated,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transfer {
+    pub transfer_id: Option<Ulid>,
+    pub source_account_id: Option<Ulid>,
+    pub destination_account_id: Option<Ulid>,
+    /// Amount moved, in the source account currency. The destination is credited its
+    /// converted value when it is held in another currency.
+    pub amount: Money,
+    pub status: TransferStatus,
+}
+
+impl Transfer {
+    /// Checks that the transfer is in the state a step starts from, returning its ID.
+    pub fn require_status(&self, expected: TransferStatus, step: &str) -> Result<Ulid, ApplyError> {
+        let transfer_id = self.transfer_id.ok_or_else(|| {
+            ApplyError::InvariantViolated(format!(
+                "Cannot {} a transfer that was never initiated",
+                step
+            ))
+        })?;
+
+        if self.status != expected {
+            return Err(ApplyError::InvariantViolated(format!(
+                "Cannot {} transfer {} in status {:?}, expected {:?}",
+                step, transfer_id, self.status, expected
+            )));
+        }
+
+        Ok(transfer_id)
+    }
+}
+
+impl Default for Transfer {
+    fn default() -> Self {
+        Self {
+            transfer_id: None,
+            source_account_id: None,
+            destination_account_id: None,
+            amount: Money::default(),
+            status: TransferStatus::Initiated,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+pub mod compensate_transfer_command;
+pub mod fail_transfer_command;
+pub mod initiate_transfer_command;
+pub mod record_transfer_credit_command;
+pub mod record_transfer_debit_command;
+
+pub use compensate_transfer_command::CompensateTransferCommand;
+pub use fail_transfer_command::FailTransferCommand;
+pub use initiate_transfer_command::InitiateTransferCommand;
+pub use record_transfer_credit_command::RecordTransferCreditCommand;
+pub use record_transfer_debit_command::RecordTransferDebitCommand;
+
+// Re-export error types
+pub use initiate_transfer_command::InitiateTransferError;
+
+use thiserror::Error;
+
+/// Error shared by the commands that move a transfer from one step to the next.
+#[derive(Debug, Error)]
+pub enum TransferStepError {
+    #[error("Invalid transfer state: {0}")]
+    InvalidState(String),
+}
@@ -0,0 +1,23 @@
+use crate::{
+    traits::Command,
+    transfer::{Transfer, TransferStatus, events::TransferCompensatedEvent},
+};
+
+use super::TransferStepError;
+
+pub struct CompensateTransferCommand {
+    pub reason: String,
+}
+
+impl Command<Transfer, TransferCompensatedEvent, TransferStepError> for CompensateTransferCommand {
+    fn execute(&self, state: Transfer) -> Result<Vec<TransferCompensatedEvent>, TransferStepError> {
+        let transfer_id = state
+            .require_status(TransferStatus::Debited, "compensate")
+            .map_err(|e| TransferStepError::InvalidState(e.to_string()))?;
+
+        Ok(vec![TransferCompensatedEvent {
+            transfer_id,
+            reason: self.reason.clone(),
+        }])
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{
+    traits::Command,
+    transfer::{Transfer, TransferStatus, events::TransferFailedEvent},
+};
+
+use super::TransferStepError;
+
+pub struct FailTransferCommand {
+    pub reason: String,
+}
+
+impl Command<Transfer, TransferFailedEvent, TransferStepError> for FailTransferCommand {
+    fn execute(&self, state: Transfer) -> Result<Vec<TransferFailedEvent>, TransferStepError> {
+        let transfer_id = state
+            .require_status(TransferStatus::Initiated, "fail")
+            .map_err(|e| TransferStepError::InvalidState(e.to_string()))?;
+
+        Ok(vec![TransferFailedEvent {
+            transfer_id,
+            reason: self.reason.clone(),
+        }])
+    }
+}
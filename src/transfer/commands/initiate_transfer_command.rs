@@ -0,0 +1,44 @@
+use rust_decimal::Decimal;
+use thiserror::Error;
+use ulid::Ulid;
+
+use crate::{
+    money::{AmountError, Currency, Money},
+    traits::Command,
+    transfer::{Transfer, events::TransferInitiatedEvent},
+};
+
+#[derive(Debug, Error)]
+pub enum InitiateTransferError {
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(#[from] AmountError),
+    #[error("Same account: {0}")]
+    SameAccount(String),
+}
+
+pub struct InitiateTransferCommand {
+    pub source_account_id: Ulid,
+    pub destination_account_id: Ulid,
+    pub amount: Decimal,
+    pub currency: Currency,
+}
+
+impl Command<Transfer, TransferInitiatedEvent, InitiateTransferError> for InitiateTransferCommand {
+    fn execute(&self, _: Transfer) -> Result<Vec<TransferInitiatedEvent>, InitiateTransferError> {
+        // A negative amount would silently move money in the opposite direction
+        let amount = Money::new(self.amount, self.currency)?;
+
+        if self.source_account_id == self.destination_account_id {
+            return Err(InitiateTransferError::SameAccount(
+                "Cannot transfer to the source account itself.".to_string(),
+            ));
+        }
+
+        Ok(vec![TransferInitiatedEvent {
+            transfer_id: Ulid::new(),
+            source_account_id: self.source_account_id,
+            destination_account_id: self.destination_account_id,
+            amount,
+        }])
+    }
+}
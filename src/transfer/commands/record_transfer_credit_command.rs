@@ -0,0 +1,18 @@
+use crate::{
+    traits::Command,
+    transfer::{Transfer, TransferStatus, events::TransferCreditedEvent},
+};
+
+use super::TransferStepError;
+
+pub struct RecordTransferCreditCommand;
+
+impl Command<Transfer, TransferCreditedEvent, TransferStepError> for RecordTransferCreditCommand {
+    fn execute(&self, state: Transfer) -> Result<Vec<TransferCreditedEvent>, TransferStepError> {
+        let transfer_id = state
+            .require_status(TransferStatus::Debited, "credit")
+            .map_err(|e| TransferStepError::InvalidState(e.to_string()))?;
+
+        Ok(vec![TransferCreditedEvent { transfer_id }])
+    }
+}
@@ -0,0 +1,18 @@
+use crate::{
+    traits::Command,
+    transfer::{Transfer, TransferStatus, events::TransferDebitedEvent},
+};
+
+use super::TransferStepError;
+
+pub struct RecordTransferDebitCommand;
+
+impl Command<Transfer, TransferDebitedEvent, TransferStepError> for RecordTransferDebitCommand {
+    fn execute(&self, state: Transfer) -> Result<Vec<TransferDebitedEvent>, TransferStepError> {
+        let transfer_id = state
+            .require_status(TransferStatus::Initiated, "debit")
+            .map_err(|e| TransferStepError::InvalidState(e.to_string()))?;
+
+        Ok(vec![TransferDebitedEvent { transfer_id }])
+    }
+}
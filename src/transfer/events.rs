@@ -0,0 +1,91 @@
+pub mod transfer_compensated_event;
+pub mod transfer_credited_event;
+pub mod transfer_debited_event;
+pub mod transfer_failed_event;
+pub mod transfer_initiated_event;
+
+use serde::{Deserialize, Serialize};
+pub use transfer_compensated_event::TransferCompensatedEvent;
+pub use transfer_credited_event::TransferCreditedEvent;
+pub use transfer_debited_event::TransferDebitedEvent;
+pub use transfer_failed_event::TransferFailedEvent;
+pub use transfer_initiated_event::TransferInitiatedEvent;
+use ulid::Ulid;
+
+use crate::{
+    traits::Event, traits::event::ApplyError, traits::upcaster::UpcasterRegistry,
+    transfer::Transfer,
+};
+
+// Tagged with `EVENT_TYPE_TAG`, the variant names must match each event's `event_type()`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event_type")]
+pub enum TransferEvent {
+    #[serde(rename = "transfer_initiated")]
+    Initiated(TransferInitiatedEvent),
+    #[serde(rename = "transfer_debited")]
+    Debited(TransferDebitedEvent),
+    #[serde(rename = "transfer_credited")]
+    Credited(TransferCreditedEvent),
+    #[serde(rename = "transfer_failed")]
+    Failed(TransferFailedEvent),
+    #[serde(rename = "transfer_compensated")]
+    Compensated(TransferCompensatedEvent),
+}
+
+pub const TRANSFER_AGGREGATE_TYPE: &str = "transfer";
+
+impl Event<Transfer> for TransferEvent {
+    fn apply(&self, state: &mut Transfer) -> Result<(), ApplyError> {
+        match self {
+            TransferEvent::Initiated(e) => e.apply(state),
+            TransferEvent::Debited(e) => e.apply(state),
+            TransferEvent::Credited(e) => e.apply(state),
+            TransferEvent::Failed(e) => e.apply(state),
+            TransferEvent::Compensated(e) => e.apply(state),
+        }
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        match self {
+            TransferEvent::Initiated(e) => e.aggregate_id(),
+            TransferEvent::Debited(e) => e.aggregate_id(),
+            TransferEvent::Credited(e) => e.aggregate_id(),
+            TransferEvent::Failed(e) => e.aggregate_id(),
+            TransferEvent::Compensated(e) => e.aggregate_id(),
+        }
+    }
+
+    fn aggregate_type(&self) -> &str {
+        TRANSFER_AGGREGATE_TYPE
+    }
+
+    fn event_type(&self) -> &str {
+        match self {
+            TransferEvent::Initiated(e) => e.event_type(),
+            TransferEvent::Debited(e) => e.event_type(),
+            TransferEvent::Credited(e) => e.event_type(),
+            TransferEvent::Failed(e) => e.event_type(),
+            TransferEvent::Compensated(e) => e.event_type(),
+        }
+    }
+
+    fn schema_version(&self) -> u32 {
+        match self {
+            TransferEvent::Initiated(e) => e.schema_version(),
+            TransferEvent::Debited(e) => e.schema_version(),
+            TransferEvent::Credited(e) => e.schema_version(),
+            TransferEvent::Failed(e) => e.schema_version(),
+            TransferEvent::Compensated(e) => e.schema_version(),
+        }
+    }
+
+    fn upcasters() -> UpcasterRegistry {
+        UpcasterRegistry::new()
+            .merge(TransferInitiatedEvent::upcasters())
+            .merge(TransferDebitedEvent::upcasters())
+            .merge(TransferCreditedEvent::upcasters())
+            .merge(TransferFailedEvent::upcasters())
+            .merge(TransferCompensatedEvent::upcasters())
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{
+    traits::Event, traits::event::ApplyError, transfer::Transfer, transfer::TransferStatus,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferCreditedEvent {
+    pub transfer_id: Ulid,
+}
+
+impl Event<Transfer> for TransferCreditedEvent {
+    fn apply(&self, state: &mut Transfer) -> Result<(), ApplyError> {
+        state.require_status(TransferStatus::Debited, "credit")?;
+        state.status = TransferStatus::Credited;
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.transfer_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "transfer"
+    }
+
+    fn event_type(&self) -> &str {
+        "transfer_credited"
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{
+    traits::Event, traits::event::ApplyError, transfer::Transfer, transfer::TransferStatus,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferFailedEvent {
+    pub transfer_id: Ulid,
+    pub reason: String,
+}
+
+impl Event<Transfer> for TransferFailedEvent {
+    fn apply(&self, state: &mut Transfer) -> Result<(), ApplyError> {
+        state.require_status(TransferStatus::Initiated, "fail")?;
+        state.status = TransferStatus::Failed;
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.transfer_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "transfer"
+    }
+
+    fn event_type(&self) -> &str {
+        "transfer_failed"
+    }
+}
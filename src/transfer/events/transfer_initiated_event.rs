@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{
+    money::{Money, add_default_currency},
+    traits::Event,
+    traits::event::ApplyError,
+    traits::upcaster::UpcasterRegistry,
+    transfer::Transfer,
+    transfer::TransferStatus,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferInitiatedEvent {
+    pub transfer_id: Ulid,
+    pub source_account_id: Ulid,
+    pub destination_account_id: Ulid,
+    #[serde(flatten)]
+    pub amount: Money,
+}
+
+impl Event<Transfer> for TransferInitiatedEvent {
+    fn apply(&self, state: &mut Transfer) -> Result<(), ApplyError> {
+        if state.transfer_id.is_some() {
+            return Err(ApplyError::InvariantViolated(
+                "Transfer already initiated".to_string(),
+            ));
+        }
+
+        state.transfer_id = Some(self.transfer_id);
+        state.source_account_id = Some(self.source_account_id);
+        state.destination_account_id = Some(self.destination_account_id);
+        state.amount = self.amount;
+        state.status = TransferStatus::Initiated;
+        Ok(())
+    }
+
+    fn aggregate_id(&self) -> Ulid {
+        self.transfer_id
+    }
+
+    fn aggregate_type(&self) -> &str {
+        "transfer"
+    }
+
+    fn event_type(&self) -> &str {
+        "transfer_initiated"
+    }
+
+    fn schema_version(&self) -> u32 {
+        2
+    }
+
+    fn upcasters() -> UpcasterRegistry {
+        UpcasterRegistry::new().register("transfer_initiated", 1, add_default_currency)
+    }
+}
@@ -0,0 +1,570 @@
+use crate::account::account_service::AccountServiceError;
+use crate::account::{Account, AccountService};
+use crate::traits::event::{EventEnvelope, EventMetadata};
+use crate::traits::event_bus::EventBusError;
+use crate::traits::fx_rate_provider::FxPolicy;
+use crate::traits::{EventBus, EventStore, Repository, SnapshotStore};
+
+use super::events::{
+    TRANSFER_AGGREGATE_TYPE, TransferDebitedEvent, TransferEvent, TransferInitiatedEvent,
+};
+use super::transfer_service::TransferServiceError;
+use super::{Transfer, TransferService, TransferStatus};
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TransferProcessManagerError {
+    #[error("Account service error: {0}")]
+    AccountServiceError(#[from] AccountServiceError),
+
+    #[error("Transfer service error: {0}")]
+    TransferServiceError(#[from] TransferServiceError),
+}
+
+/// Drives transfers to completion by reacting to their events: debits the source once a
+/// transfer is initiated, then credits the destination, refunding the source if that fails.
+///
+/// Every step is applied to an account at most once, so a redelivered event resumes the
+/// transfer where it stopped. Only rejected steps fail or compensate the transfer,
+/// infrastructure errors are returned so the event is handled again.
+pub struct TransferProcessManager<
+    R: Repository<Account> + Send + Sync + Clone + 'static,
+    B: EventBus + Send + Sync + Clone + 'static,
+    S: EventStore + SnapshotStore + Send + Sync + Clone + 'static,
+> {
+    repository: R,
+    event_bus: B,
+    event_store: S,
+    account_service: AccountService<R, S>,
+    transfer_service: TransferService<S>,
+    fx_policy: Option<FxPolicy>,
+}
+
+impl<
+    R: Repository<Account> + Send + Sync + Clone + 'static,
+    B: EventBus + Send + Sync + Clone + 'static,
+    S: EventStore + SnapshotStore + Send + Sync + Clone + 'static,
+> TransferProcessManager<R, B, S>
+{
+    pub fn new(repository: R, event_bus: B, event_store: S) -> Self {
+        Self {
+            account_service: AccountService::new(repository.clone(), event_store.clone()),
+            transfer_service: TransferService::new(event_store.clone()),
+            repository,
+            event_bus,
+            event_store,
+            fx_policy: None,
+        }
+    }
+
+    /// Converts transfers into the currency of the destination account instead of refunding
+    /// them.
+    pub fn with_fx_policy(mut self, fx_policy: FxPolicy) -> Self {
+        self.account_service = self.account_service.with_fx_policy(fx_policy.clone());
+        self.fx_policy = Some(fx_policy);
+        self
+    }
+
+    pub fn listen(&self) {
+        let repository = self.repository.clone();
+        let event_bus = self.event_bus.clone();
+        let event_store = self.event_store.clone();
+        let fx_policy = self.fx_policy.clone();
+
+        self.event_bus.subscribe(
+            "transfer_process_manager",
+            TRANSFER_AGGREGATE_TYPE,
+            Box::new(move |envelope: EventEnvelope<Transfer, TransferEvent>| {
+                let mut process_manager = TransferProcessManager::new(
+                    repository.clone(),
+                    event_bus.clone(),
+                    event_store.clone(),
+                );
+                if let Some(fx_policy) = &fx_policy {
+                    process_manager = process_manager.with_fx_policy(fx_policy.clone());
+                }
+                process_manager
+                    .handle(envelope)
+                    .map_err(|e| EventBusError::HandleError(e.to_string()))
+            }),
+        );
+    }
+
+    /// Reacts to a transfer event, every event appended in response is caused by it.
+    pub fn handle(
+        &self,
+        envelope: EventEnvelope<Transfer, TransferEvent>,
+    ) -> Result<(), TransferProcessManagerError> {
+        let metadata = EventMetadata::caused_by(&envelope);
+
+        match envelope.event {
+            TransferEvent::Initiated(event) => self.handle_transfer_initiated(event, &metadata),
+            TransferEvent::Debited(event) => self.handle_transfer_debited(event, &metadata),
+            // Credited, failed and compensated transfers are finished
+            _ => Ok(()),
+        }
+    }
+
+    pub fn handle_transfer_initiated(
+        &self,
+        event: TransferInitiatedEvent,
+        metadata: &EventMetadata,
+    ) -> Result<(), TransferProcessManagerError> {
+        // A redelivered event finds the transfer already past this step
+        let transfer = self.transfer_service.get_transfer(event.transfer_id)?;
+        if transfer.status != TransferStatus::Initiated {
+            return Ok(());
+        }
+
+        match self.account_service.debit_transfer(
+            event.source_account_id,
+            event.transfer_id,
+            event.amount.value(),
+            event.amount.currency(),
+            metadata,
+        ) {
+            Ok(()) => self
+                .transfer_service
+                .record_debit(event.transfer_id, metadata)?,
+            Err(e) if e.is_transient() => return Err(e.into()),
+            Err(e) => self
+                .transfer_service
+                .fail(event.transfer_id, e.to_string(), metadata)?,
+        }
+
+        Ok(())
+    }
+
+    pub fn handle_transfer_debited(
+        &self,
+        event: TransferDebitedEvent,
+        metadata: &EventMetadata,
+    ) -> Result<(), TransferProcessManagerError> {
+        let transfer = self.transfer_service.get_transfer(event.transfer_id)?;
+        if transfer.status != TransferStatus::Debited {
+            return Ok(());
+        }
+
+        let (Some(source_account_id), Some(destination_account_id)) =
+            (transfer.source_account_id, transfer.destination_account_id)
+        else {
+            return Ok(());
+        };
+
+        match self.account_service.credit_transfer(
+            destination_account_id,
+            event.transfer_id,
+            transfer.amount.value(),
+            transfer.amount.currency(),
+            metadata,
+        ) {
+            Ok(()) => self
+                .transfer_service
+                .record_credit(event.transfer_id, metadata)?,
+            Err(e) if e.is_transient() => return Err(e.into()),
+            Err(e) => {
+                // Give the money back before recording the transfer as compensated
+                let reason = e.to_string();
+                self.account_service.refund_transfer(
+                    source_account_id,
+                    event.transfer_id,
+                    &reason,
+                    metadata,
+                )?;
+                self.transfer_service
+                    .compensate(event.transfer_id, reason, metadata)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::events::{ACCOUNT_AGGREGATE_TYPE, AccountEvent};
+    use crate::account::repositories::AccountRepositoryInMemory;
+    use crate::event_bus_in_memory::EventBusInMemory;
+    use crate::event_store_in_memory::EventStoreInMemory;
+    use crate::fx_rate_provider_in_memory::FxRateProviderInMemory;
+    use crate::money::Currency;
+    use crate::outbox_relay::OutboxRelay;
+    use crate::traits::Outbox;
+    use crate::traits::event_store::{AggregateSummary, EventStoreError};
+    use crate::traits::snapshot_store::{Snapshot, SnapshotStoreError};
+    use rust_decimal::Decimal;
+    use serde::{Deserialize, Serialize, de::DeserializeOwned};
+    use std::sync::{Arc, Mutex};
+    use ulid::Ulid;
+
+    /// In-memory store whose next `failing_transfer_appends` appends to a transfer fail, as if
+    /// the database went away between two steps of the process manager.
+    #[derive(Clone, Default)]
+    struct FlakyStore {
+        inner: EventStoreInMemory,
+        failing_transfer_appends: Arc<Mutex<u32>>,
+    }
+
+    impl EventStore for FlakyStore {
+        fn append_events<T, E: crate::traits::Event<T> + Serialize>(
+            &self,
+            aggregate_id: Ulid,
+            aggregate_type: &str,
+            expected_version: u64,
+            events: Vec<E>,
+            metadata: &EventMetadata,
+        ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError> {
+            let mut failing = self.failing_transfer_appends.lock().unwrap();
+            if aggregate_type == TRANSFER_AGGREGATE_TYPE && *failing > 0 {
+                *failing -= 1;
+                return Err(EventStoreError::EventStoreError(
+                    "database is locked".to_string(),
+                ));
+            }
+            drop(failing);
+
+            self.inner.append_events(
+                aggregate_id,
+                aggregate_type,
+                expected_version,
+                events,
+                metadata,
+            )
+        }
+
+        fn get_events_for_aggregate<T, E>(
+            &self,
+            aggregate_id: Ulid,
+            aggregate_type: &str,
+        ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError>
+        where
+            E: crate::traits::Event<T> + Serialize + for<'de> Deserialize<'de>,
+        {
+            self.inner
+                .get_events_for_aggregate(aggregate_id, aggregate_type)
+        }
+
+        fn get_events_for_aggregate_since<T, E>(
+            &self,
+            aggregate_id: Ulid,
+            aggregate_type: &str,
+            after_version: u64,
+        ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError>
+        where
+            E: crate::traits::Event<T> + Serialize + for<'de> Deserialize<'de>,
+        {
+            self.inner
+                .get_events_for_aggregate_since(aggregate_id, aggregate_type, after_version)
+        }
+
+        fn get_events_for_aggregate_type<T, E>(
+            &self,
+            aggregate_type: &str,
+            after_sequence_number: Option<Ulid>,
+            limit: usize,
+        ) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError>
+        where
+            E: crate::traits::Event<T> + Serialize + for<'de> Deserialize<'de>,
+        {
+            self.inner
+                .get_events_for_aggregate_type(aggregate_type, after_sequence_number, limit)
+        }
+
+        fn get_all_events<T, E>(&self) -> Result<Vec<EventEnvelope<T, E>>, EventStoreError>
+        where
+            E: crate::traits::Event<T> + Serialize + for<'de> Deserialize<'de>,
+        {
+            self.inner.get_all_events()
+        }
+
+        fn get_aggregates(
+            &self,
+            aggregate_type: Option<&str>,
+        ) -> Result<Vec<AggregateSummary>, EventStoreError> {
+            self.inner.get_aggregates(aggregate_type)
+        }
+
+        fn get_aggregate(
+            &self,
+            aggregate_id: Ulid,
+        ) -> Result<Option<AggregateSummary>, EventStoreError> {
+            self.inner.get_aggregate(aggregate_id)
+        }
+    }
+
+    impl SnapshotStore for FlakyStore {
+        fn save_snapshot<T: Serialize>(
+            &self,
+            snapshot: &Snapshot<T>,
+        ) -> Result<(), SnapshotStoreError> {
+            self.inner.save_snapshot(snapshot)
+        }
+
+        fn get_snapshot<T: DeserializeOwned>(
+            &self,
+            aggregate_id: Ulid,
+            aggregate_type: &str,
+        ) -> Result<Option<Snapshot<T>>, SnapshotStoreError> {
+            self.inner.get_snapshot(aggregate_id, aggregate_type)
+        }
+    }
+
+    struct Setup {
+        event_store: FlakyStore,
+        accounts: AccountService<AccountRepositoryInMemory, FlakyStore>,
+        transfers: TransferService<FlakyStore>,
+        relay: OutboxRelay<EventStoreInMemory, EventBusInMemory>,
+    }
+
+    fn setup() -> Setup {
+        let event_store = FlakyStore::default();
+        let event_bus = EventBusInMemory::new();
+        let repository = AccountRepositoryInMemory::new();
+
+        let rates = FxRateProviderInMemory::new()
+            .with_rate(Currency::EUR, Currency::JPY, Decimal::from(160))
+            .unwrap();
+        TransferProcessManager::new(repository.clone(), event_bus.clone(), event_store.clone())
+            .with_fx_policy(FxPolicy::new(Arc::new(rates), Decimal::ZERO))
+            .listen();
+
+        Setup {
+            accounts: AccountService::new(repository, event_store.clone()),
+            transfers: TransferService::new(event_store.clone()),
+            relay: OutboxRelay::new(event_store.inner.clone(), event_bus),
+            event_store,
+        }
+    }
+
+    impl Setup {
+        fn open_account(&self, balance: i64) -> Ulid {
+            self.open_account_in(balance, Currency::EUR)
+        }
+
+        fn open_account_in(&self, balance: i64, currency: Currency) -> Ulid {
+            self.accounts
+                .create_account(Decimal::from(balance), currency, &EventMetadata::new())
+                .unwrap()
+                .account_id
+                .unwrap()
+        }
+
+        fn events(&self, account_id: Ulid) -> Vec<AccountEvent> {
+            self.event_store
+                .get_events_for_aggregate::<Account, AccountEvent>(
+                    account_id,
+                    ACCOUNT_AGGREGATE_TYPE,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|e| e.event)
+                .collect()
+        }
+
+        fn balance(&self, account_id: Ulid) -> Decimal {
+            Account::load::<_, AccountServiceError>(&self.event_store, account_id)
+                .unwrap()
+                .0
+                .balance
+        }
+
+        /// Initiates a transfer and relays until the process manager has nothing left to do.
+        fn transfer(&self, source_id: Ulid, destination_id: Ulid, amount: i64) -> TransferStatus {
+            let transfer_id = self
+                .transfers
+                .initiate(
+                    source_id,
+                    destination_id,
+                    Decimal::from(amount),
+                    Currency::EUR,
+                    &EventMetadata::new().with_correlation_id("transfer-test"),
+                )
+                .unwrap()
+                .transfer_id
+                .unwrap();
+            self.relay.relay_pending().unwrap();
+            self.transfers.get_transfer(transfer_id).unwrap().status
+        }
+    }
+
+    #[test]
+    fn transfer_moves_funds_between_accounts() {
+        let setup = setup();
+        let source_id = setup.open_account(100);
+        let destination_id = setup.open_account(0);
+
+        assert_eq!(
+            setup.transfer(source_id, destination_id, 40),
+            TransferStatus::Credited
+        );
+        assert_eq!(setup.balance(source_id), Decimal::from(60));
+        assert_eq!(setup.balance(destination_id), Decimal::from(40));
+    }
+
+    #[test]
+    fn transfers_into_another_currency_are_converted() {
+        let setup = setup();
+        let source_id = setup.open_account(100);
+        let destination_id = setup.open_account_in(0, Currency::JPY);
+
+        assert_eq!(
+            setup.transfer(source_id, destination_id, 40),
+            TransferStatus::Credited
+        );
+        assert_eq!(setup.balance(source_id), Decimal::from(60));
+        assert_eq!(setup.balance(destination_id), Decimal::from(6400));
+    }
+
+    #[test]
+    fn insufficient_funds_fail_the_transfer() {
+        let setup = setup();
+        let source_id = setup.open_account(10);
+        let destination_id = setup.open_account(0);
+
+        assert_eq!(
+            setup.transfer(source_id, destination_id, 40),
+            TransferStatus::Failed
+        );
+        assert_eq!(setup.balance(source_id), Decimal::from(10));
+        assert_eq!(setup.balance(destination_id), Decimal::from(0));
+    }
+
+    #[test]
+    fn failed_credit_refunds_the_source() {
+        let setup = setup();
+        let source_id = setup.open_account(100);
+
+        // The destination account was never opened, so crediting it fails
+        assert_eq!(
+            setup.transfer(source_id, Ulid::new(), 40),
+            TransferStatus::Compensated
+        );
+        assert_eq!(setup.balance(source_id), Decimal::from(100));
+
+        // The refund reverses the debit rather than depositing into the source
+        let events = setup.events(source_id);
+        let (AccountEvent::Withdrawn(debit), AccountEvent::TransactionReversed(refund)) =
+            (&events[1], &events[2])
+        else {
+            panic!("expected the debit and its reversal, got {:?}", events);
+        };
+        assert_eq!(refund.reversed_event_type, "withdraw");
+        assert_eq!(refund.amount, debit.amount);
+    }
+
+    #[test]
+    fn refunds_reach_frozen_source_accounts_once() {
+        let setup = setup();
+        let source_id = setup.open_account(100);
+        let transfer_id = Ulid::new();
+        let metadata = EventMetadata::new();
+
+        setup
+            .accounts
+            .debit_transfer(
+                source_id,
+                transfer_id,
+                Decimal::from(40),
+                Currency::EUR,
+                &metadata,
+            )
+            .unwrap();
+        setup
+            .accounts
+            .freeze_account(source_id, "fraud review", &metadata)
+            .unwrap();
+
+        for _ in 0..2 {
+            setup
+                .accounts
+                .refund_transfer(source_id, transfer_id, "destination closed", &metadata)
+                .unwrap();
+        }
+
+        assert_eq!(setup.balance(source_id), Decimal::from(100));
+        assert_eq!(setup.events(source_id).len(), 4);
+    }
+
+    #[test]
+    fn redelivered_transfers_debit_the_source_once() {
+        let setup = setup();
+        let source_id = setup.open_account(100);
+        let destination_id = setup.open_account(0);
+
+        let transfer_id = setup
+            .transfers
+            .initiate(
+                source_id,
+                destination_id,
+                Decimal::from(40),
+                Currency::EUR,
+                &EventMetadata::new(),
+            )
+            .unwrap()
+            .transfer_id
+            .unwrap();
+
+        // Recording the debit fails after the source was debited, the transfer stays initiated
+        *setup.event_store.failing_transfer_appends.lock().unwrap() = 1;
+        setup.relay.relay_pending().unwrap();
+        assert_eq!(
+            setup.transfers.get_transfer(transfer_id).unwrap().status,
+            TransferStatus::Initiated
+        );
+        assert_eq!(setup.balance(source_id), Decimal::from(60));
+
+        // Delivering the initiated transfer again resumes it without debiting twice
+        setup.event_store.inner.requeue(None).unwrap();
+        setup.relay.relay_pending().unwrap();
+
+        assert_eq!(
+            setup.transfers.get_transfer(transfer_id).unwrap().status,
+            TransferStatus::Credited
+        );
+        assert_eq!(setup.balance(source_id), Decimal::from(60));
+        assert_eq!(setup.balance(destination_id), Decimal::from(40));
+        let debits = setup
+            .events(source_id)
+            .into_iter()
+            .filter(|e| matches!(e, AccountEvent::Withdrawn(_)))
+            .count();
+        assert_eq!(debits, 1);
+    }
+
+    #[test]
+    fn reactions_are_caused_by_the_transfer_events() {
+        let setup = setup();
+        let source_id = setup.open_account(100);
+        let destination_id = setup.open_account(0);
+        setup.transfer(source_id, destination_id, 40);
+
+        let transfer_events = setup
+            .event_store
+            .get_events_for_aggregate_type::<Transfer, TransferEvent>(
+                TRANSFER_AGGREGATE_TYPE,
+                None,
+                10,
+            )
+            .unwrap();
+        let withdrawal = setup
+            .event_store
+            .get_events_for_aggregate::<Account, AccountEvent>(source_id, ACCOUNT_AGGREGATE_TYPE)
+            .unwrap()
+            .pop()
+            .unwrap();
+
+        assert_eq!(
+            withdrawal.metadata.causation_id,
+            Some(transfer_events[0].sequence_number)
+        );
+        assert_eq!(
+            transfer_events[1].metadata.causation_id,
+            Some(transfer_events[0].sequence_number)
+        );
+        for metadata in [&withdrawal.metadata, &transfer_events[2].metadata] {
+            assert_eq!(metadata.correlation_id.as_deref(), Some("transfer-test"));
+        }
+    }
+}
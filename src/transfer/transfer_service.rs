@@ -0,0 +1,168 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use ulid::Ulid;
+
+use crate::money::Currency;
+use crate::traits::event::{ApplyError, EventMetadata};
+use crate::traits::event_store::EventStoreError;
+use crate::traits::{Aggregate, Command, Event, EventStore};
+
+use super::Transfer;
+use super::commands::{
+    CompensateTransferCommand, FailTransferCommand, InitiateTransferCommand, InitiateTransferError,
+    RecordTransferCreditCommand, RecordTransferDebitCommand, TransferStepError,
+};
+use super::events::{TRANSFER_AGGREGATE_TYPE, TransferEvent};
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TransferServiceError {
+    #[error("Event application error: {0}")]
+    ApplyError(#[from] ApplyError),
+    #[error("Event store error: {0}")]
+    EventStoreError(#[from] EventStoreError),
+    #[error("Initiate transfer command error: {0}")]
+    InitiateTransferError(#[from] InitiateTransferError),
+    #[error("Transfer step error: {0}")]
+    TransferStepError(#[from] TransferStepError),
+    #[error("Transfer not found: {0}")]
+    TransferNotFound(Ulid),
+}
+
+impl TransferServiceError {
+    /// Whether the operation failed on the infrastructure rather than being rejected, so it
+    /// may succeed when it is retried.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, TransferServiceError::EventStoreError(_))
+    }
+}
+
+// Transfers are only read back by the process manager, straight from the event store
+pub struct TransferService<E: EventStore> {
+    event_store: E,
+}
+
+impl<E: EventStore> TransferService<E> {
+    pub fn new(event_store: E) -> Self {
+        Self { event_store }
+    }
+
+    pub fn initiate(
+        &self,
+        source_account_id: Ulid,
+        destination_account_id: Ulid,
+        amount: Decimal,
+        currency: Currency,
+        metadata: &EventMetadata,
+    ) -> Result<Transfer, TransferServiceError> {
+        let mut transfer = Transfer::default();
+
+        let command = InitiateTransferCommand {
+            source_account_id,
+            destination_account_id,
+            amount,
+            currency,
+        };
+
+        let events = command.execute(transfer.clone())?;
+
+        self.commit(&mut transfer, 0, events, metadata)?;
+
+        Ok(transfer)
+    }
+
+    pub fn record_debit(
+        &self,
+        transfer_id: Ulid,
+        metadata: &EventMetadata,
+    ) -> Result<(), TransferServiceError> {
+        let (mut transfer, version) = self.load_transfer(transfer_id)?;
+        let events = RecordTransferDebitCommand.execute(transfer.clone())?;
+        self.commit(&mut transfer, version, events, metadata)
+    }
+
+    pub fn record_credit(
+        &self,
+        transfer_id: Ulid,
+        metadata: &EventMetadata,
+    ) -> Result<(), TransferServiceError> {
+        let (mut transfer, version) = self.load_transfer(transfer_id)?;
+        let events = RecordTransferCreditCommand.execute(transfer.clone())?;
+        self.commit(&mut transfer, version, events, metadata)
+    }
+
+    pub fn fail(
+        &self,
+        transfer_id: Ulid,
+        reason: String,
+        metadata: &EventMetadata,
+    ) -> Result<(), TransferServiceError> {
+        let (mut transfer, version) = self.load_transfer(transfer_id)?;
+        let events = FailTransferCommand { reason }.execute(transfer.clone())?;
+        self.commit(&mut transfer, version, events, metadata)
+    }
+
+    pub fn compensate(
+        &self,
+        transfer_id: Ulid,
+        reason: String,
+        metadata: &EventMetadata,
+    ) -> Result<(), TransferServiceError> {
+        let (mut transfer, version) = self.load_transfer(transfer_id)?;
+        let events = CompensateTransferCommand { reason }.execute(transfer.clone())?;
+        self.commit(&mut transfer, version, events, metadata)
+    }
+
+    pub fn get_transfer(&self, transfer_id: Ulid) -> Result<Transfer, TransferServiceError> {
+        self.load_transfer(transfer_id)
+            .map(|(transfer, _)| transfer)
+    }
+
+    /// Rebuilds the transfer from its event stream, returning it together with the version of
+    /// the last event applied.
+    fn load_transfer(&self, transfer_id: Ulid) -> Result<(Transfer, u64), TransferServiceError> {
+        let events_envelopes = self
+            .event_store
+            .get_events_for_aggregate::<Transfer, TransferEvent>(
+                transfer_id,
+                TRANSFER_AGGREGATE_TYPE,
+            )?;
+
+        let Some(version) = events_envelopes.last().map(|e| e.version()) else {
+            return Err(TransferServiceError::TransferNotFound(transfer_id));
+        };
+        let transfer =
+            Transfer::from_history(events_envelopes.into_iter().map(|e| e.event).collect())?;
+
+        Ok((transfer, version))
+    }
+
+    /// Applies the events produced by a command to `transfer`, then appends them to the event
+    /// store in a single transaction.
+    fn commit<Ev: Event<Transfer> + Serialize>(
+        &self,
+        transfer: &mut Transfer,
+        expected_version: u64,
+        events: Vec<Ev>,
+        metadata: &EventMetadata,
+    ) -> Result<(), TransferServiceError> {
+        for event in &events {
+            event.apply(transfer)?;
+        }
+
+        let transfer_id = transfer
+            .transfer_id
+            .ok_or_else(|| ApplyError::InvariantViolated("Transfer ID is required".to_string()))?;
+
+        self.event_store.append_events(
+            transfer_id,
+            TRANSFER_AGGREGATE_TYPE,
+            expected_version,
+            events,
+            metadata,
+        )?;
+
+        Ok(())
+    }
+}